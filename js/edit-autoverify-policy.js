@@ -0,0 +1,29 @@
+"use strict";
+
+window.addEventListener("load", function () {
+    for (let elem of this.document.getElementsByClassName("edit-policy")) {
+        elem.addEventListener("click", (event) => {
+            let dataset = event.target.closest("a").dataset;
+            this.document.getElementById("policy_details").open = true;
+            this.document.getElementById("policy_puzzle_id").value =
+                dataset.puzzleId;
+            this.document.getElementById(
+                "policy_max_trusted_network_latency_ms"
+            ).value = dataset.maxTrustedNetworkLatencyMs;
+            this.document.getElementById("policy_max_inspection_time_ms").value =
+                dataset.maxInspectionTimeMs;
+            this.document.getElementById(
+                "policy_max_scramble_application_time_ms"
+            ).value = dataset.maxScrambleApplicationTimeMs;
+            this.document.getElementById("policy_max_upload_gap_hours").value =
+                dataset.maxUploadGapHours;
+            this.document.getElementById("policy_allowed_programs").value =
+                dataset.allowedPrograms;
+            this.document.getElementById("policy_allow_variants").value =
+                dataset.allowVariants;
+            this.document.getElementById("policy_allow_solver_notes").value =
+                dataset.allowSolverNotes;
+            this.document.getElementById("policy_audit_log_comment").value = "";
+        });
+    }
+});
@@ -0,0 +1,18 @@
+"use strict";
+
+window.addEventListener("load", function () {
+    for (let elem of this.document.getElementsByClassName("edit-webhook")) {
+        elem.addEventListener("click", (event) => {
+            let dataset = event.target.closest("a").dataset;
+            this.document.getElementById("webhook_details").open = true;
+            this.document.getElementById("webhook_id").value = dataset.id;
+            this.document.getElementById("webhook_name").value = dataset.name;
+            this.document.getElementById("webhook_url").value = dataset.url;
+            this.document.getElementById("webhook_secret").value = "";
+            this.document.getElementById("webhook_events").value =
+                dataset.events;
+            this.document.getElementById("webhook_enabled").checked =
+                dataset.enabled == "true";
+        });
+    }
+});
@@ -0,0 +1,14 @@
+"use strict";
+
+window.addEventListener("load", function () {
+    let form = this.document.getElementById("search-form");
+    form.addEventListener("submit", () => {
+        // Omit empty fields so that the URL stays short and unset filters
+        // are not parsed as invalid values.
+        for (let elem of form.elements) {
+            if (elem.name && elem.value === "") {
+                elem.disabled = true;
+            }
+        }
+    });
+});
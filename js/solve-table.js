@@ -84,6 +84,11 @@ async function handleFilterUpdate() {
         }
     }
 
+    // Update date inputs
+    for (let input of document.querySelectorAll("input.date-filter")) {
+        input.value = url.searchParams.get(input.dataset.filter) ?? "";
+    }
+
     // Update dropdown state
     let active_event_button;
     if (event === null) {
@@ -133,6 +138,13 @@ async function handleFilterUpdate() {
     xhr.send();
 }
 
+document.addEventListener("change", (event) => {
+    if (event.target.matches("input.date-filter")) {
+        event.target.dataset.filterValue = event.target.value;
+        updateParam(event);
+    }
+});
+
 document.addEventListener("click", (event) => {
     if (event.target.matches(".filter")) {
         if (event.target.matches("a")) {
@@ -143,171 +155,17 @@ document.addEventListener("click", (event) => {
     }
 });
 
-var historyChart;
 function updateChart() {
-    if (historyChart !== undefined) {
-        historyChart.destroy();
-    }
-
-    if (url.searchParams.get("history")) {
-        document.getElementById("history-chart-div").hidden = false;
-        historyChart = createChart();
-    }
-}
-
-function createChart() {
-    var chartData = [];
-    var ctx = document.getElementById("history-chart");
-
-    const clrs = ['gray', 'yellow', 'red', 'orange', 'green', '#3498db', 'pink', 'purple'];
-    var slvrs = [];
-
-    for (let elem of document.getElementsByClassName("solve-row")) {
-        var formattedSolveDate = dateFns.format(
-            elem.dataset.solveDate,
-            "yyyy-MM-dd"
-        );
-        if (isFmc()) {
-            chartData.push({
-                x: formattedSolveDate,
-                y: elem.dataset.moveCount,
-                solver: elem.dataset.solverName,
-            });
-        } else {
-            chartData.push({
-                x: formattedSolveDate,
-                y: elem.dataset.speedCs,
-                solver: elem.dataset.solverName,
-            });
-        }
-
-        // add all the solvers to a list
-        if (!slvrs.includes(elem.dataset.solverName)) {
-            slvrs.push(elem.dataset.solverName);
-        }
-    }
-
-    // change the color of the line between data points depending on user's prefered light/dark theme.
-    var lineColor = "#FFFFFF";
-    const prefersLightMode = window.matchMedia("(prefers-color-scheme: light)").matches;
-    if (prefersLightMode) {
-        lineColor = "#000000";
-    }
-
-    var solveData = {
-        datasets: [
-            {
-                borderColor: lineColor,
-                backgroundColor: lineColor,
-                data: chartData,
-                pointBackgroundColor: function(context) {
-                    const dataIndex = context.dataIndex;
-                    const originalDataPoint = context.dataset.data[dataIndex];
-                    const solverName = originalDataPoint.solver;
-                    return clrs[slvrs.indexOf(solverName)%8];
-                },
-                pointBorderColor: "#000000",
-                radius: 5,
-                hoverRadius: 8,
-            },
-        ],
-    };
-
-
-    return new Chart(ctx, {
-        type: "line",
-        data: solveData,
-        options: {
-            stepped: "after",
-            plugins: {
-                legend: {
-                    display: false,
-                },
-                tooltip: {
-                    callbacks: {
-                        title: function (context) {
-                            return dateFns.format(
-                                context[0].parsed.x,
-                                "yyyy-MM-dd"
-                            );
-                        },
-                        label: function (context) {
-                            let label = context.dataset.label || "";
-
-                            if (context.parsed.y !== null) {
-                                if (isFmc()) {
-                                    label = context.parsed.y;
-                                } else {
-                                    label = csToString(context.parsed.y);
-                                }
-                            }
-                            return label;
-                        },
-                        footer: function (context) {
-                            const dataIndex = context[0].dataIndex;
-                            const originalDataPoint =
-                                context[0].dataset.data[dataIndex];
-                            const solverName = originalDataPoint.solver;
-                            return [`by ${solverName}`];
-                        },
-                    },
-                },
-            },
-            scales: {
-                y: {
-                    ticks: {
-                        callback: function (value) {
-                            if (isFmc()) return value;
-                            else return csToStringAxis(value);
-                        },
-                    },
-                },
-                x: {
-                    type: "time",
-                    time: {
-                        unit: "month",
-                        displayFormats: {
-                            day: "YYYY MM DD",
-                        },
-                    },
-                    max: new Date(),
-                },
-            },
-        },
-    });
-}
-
-// takes in a number of centiseconds, and returns a formatted string
-function csToString(cs) {
-    var d = new Date(0, 0, 0, 0, 0, 0, cs * 10);
-    var cs = d.getMilliseconds() / 10;
-    var s = d.getSeconds();
-    var m = d.getMinutes();
-    var h = d.getHours();
-    var label = `${h}h ${m}m ${s}.${cs}s`;
-    if (h == 0) {
-        label = `${m}m ${s}.${cs}s`;
-    }
-    if (h == 0 && m == 0) {
-        label = `${s}.${cs}s`;
-    }
-    return label;
-}
-
-function csToStringAxis(cs) {
-    var d = new Date(0, 0, 0, 0, 0, 0, cs * 10);
-    var cs = d.getMilliseconds() / 10;
-    var s = d.getSeconds();
-    var m = d.getMinutes();
-    var h = d.getHours();
-    var label = `${h}h ${m}m ${s}s`;
-    if (h == 0) {
-        label = `${m}m ${s}s`;
+    const chartDiv = document.getElementById("history-chart-div");
+    if (chartDiv === null) {
+        return;
     }
-    if (h == 0 && m == 0) {
-        label = `${s}s`;
+    const showChart = url.searchParams.get("history") !== null;
+    if (showChart) {
+        document.getElementById("history-chart").data =
+            "/chart/record-history?" + url.searchParams;
     }
-    return label;
+    chartDiv.hidden = !showChart;
 }
 
 window.addEventListener("load", handleFilterUpdate);
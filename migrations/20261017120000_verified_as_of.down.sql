@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS SolveLog_solve_id;
+
+DROP FUNCTION IF EXISTS speed_verified_as_of, fmc_verified_as_of;
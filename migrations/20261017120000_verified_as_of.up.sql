@@ -0,0 +1,57 @@
+-- Returns whether a solve's speed result was accepted at a given time,
+-- according to the audit log. Solves that were verified before the audit log
+-- existed are treated as having always been verified.
+CREATE OR REPLACE FUNCTION speed_verified_as_of(solve_id INTEGER, as_of TIMESTAMPTZ)
+RETURNS BOOLEAN AS $$
+    SELECT CASE
+        WHEN latest IS NOT NULL THEN (latest->>'new')::BOOLEAN IS TRUE
+        ELSE (
+            SELECT (json_data->'speed_verified'->>0)::BOOLEAN
+            FROM SolveLog
+            WHERE SolveLog.solve_id = $1
+                AND json_data->>'type' = 'migrated'
+            LIMIT 1
+        ) IS TRUE
+    END
+    FROM (
+        SELECT (
+            SELECT json_data
+            FROM SolveLog
+            WHERE SolveLog.solve_id = $1
+                AND json_data->>'type' = 'speed_verified'
+                AND timestamp < $2
+            ORDER BY timestamp DESC, id DESC
+            LIMIT 1
+        ) AS latest
+    ) AS s
+$$ LANGUAGE SQL STABLE;
+
+-- Returns whether a solve's fewest-moves result was accepted at a given time,
+-- according to the audit log. Solves that were verified before the audit log
+-- existed are treated as having always been verified.
+CREATE OR REPLACE FUNCTION fmc_verified_as_of(solve_id INTEGER, as_of TIMESTAMPTZ)
+RETURNS BOOLEAN AS $$
+    SELECT CASE
+        WHEN latest IS NOT NULL THEN (latest->>'new')::BOOLEAN IS TRUE
+        ELSE (
+            SELECT (json_data->'fmc_verified'->>0)::BOOLEAN
+            FROM SolveLog
+            WHERE SolveLog.solve_id = $1
+                AND json_data->>'type' = 'migrated'
+            LIMIT 1
+        ) IS TRUE
+    END
+    FROM (
+        SELECT (
+            SELECT json_data
+            FROM SolveLog
+            WHERE SolveLog.solve_id = $1
+                AND json_data->>'type' = 'fmc_verified'
+                AND timestamp < $2
+            ORDER BY timestamp DESC, id DESC
+            LIMIT 1
+        ) AS latest
+    ) AS s
+$$ LANGUAGE SQL STABLE;
+
+CREATE INDEX IF NOT EXISTS SolveLog_solve_id ON SolveLog (solve_id);
@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS Webhook (
+    id INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    -- Shared secret used to sign payloads with HMAC-SHA256
+    secret TEXT NOT NULL,
+    -- Events to send; empty means all events
+    events TEXT[] NOT NULL DEFAULT '{}',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE TABLE IF NOT EXISTS WebhookDelivery (
+    id INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+
+    webhook_id INTEGER REFERENCES Webhook ON DELETE CASCADE NOT NULL,
+    -- Identifier shared by all attempts to deliver the same payload
+    delivery_id TEXT NOT NULL,
+    event TEXT NOT NULL,
+    solve_id INTEGER REFERENCES Solve ON DELETE SET NULL,
+    attempt INTEGER NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    status_code INTEGER,
+    error TEXT,
+    success BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS WebhookDelivery_timestamp ON WebhookDelivery (timestamp DESC);
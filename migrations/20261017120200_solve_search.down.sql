@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS Solve_upload_date;
+DROP INDEX IF EXISTS Solve_moderator_notes_search;
+DROP INDEX IF EXISTS Solve_solver_notes_search;
@@ -0,0 +1,8 @@
+-- Full-text indexes for solve search. The expressions must match those in
+-- `src/db/search.rs` exactly for Postgres to use them.
+CREATE INDEX IF NOT EXISTS Solve_solver_notes_search
+    ON Solve USING GIN (to_tsvector('simple', solver_notes));
+CREATE INDEX IF NOT EXISTS Solve_moderator_notes_search
+    ON Solve USING GIN (to_tsvector('simple', moderator_notes));
+
+CREATE INDEX IF NOT EXISTS Solve_upload_date ON Solve (upload_date DESC);
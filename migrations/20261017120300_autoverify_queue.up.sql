@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS AutoVerifyQueue (
+    solve_id INTEGER PRIMARY KEY REFERENCES Solve ON DELETE CASCADE,
+
+    enqueued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Number of times a worker has started verifying the solve
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+
+    -- Worker currently verifying the solve, if any. The lease is ignored
+    -- after it expires, so that a crashed worker does not block the solve.
+    leased_by TEXT,
+    lease_expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS AutoVerifyQueue_enqueued_at ON AutoVerifyQueue (enqueued_at);
@@ -0,0 +1,2 @@
+ALTER TABLE AutoVerifyQueue DROP COLUMN IF EXISTS needs_manual_review;
+ALTER TABLE AutoVerifyQueue DROP COLUMN IF EXISTS next_attempt_at;
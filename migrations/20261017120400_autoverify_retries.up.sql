@@ -0,0 +1,7 @@
+-- Time before which a failed solve will not be retried
+ALTER TABLE AutoVerifyQueue ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ;
+-- Whether auto-verification has given up on the solve
+ALTER TABLE AutoVerifyQueue ADD COLUMN IF NOT EXISTS needs_manual_review BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Previously, solves with 3 or more attempts were never retried.
+UPDATE AutoVerifyQueue SET needs_manual_review = TRUE WHERE attempts >= 3;
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS AutoVerifyPolicy (
+    id INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+
+    -- Puzzle that this policy overrides, or NULL for the default policy
+    puzzle_id INTEGER UNIQUE REFERENCES Puzzle ON DELETE CASCADE,
+
+    -- NULL inherits the value from the default policy
+    max_trusted_network_latency_ms INTEGER,
+    max_inspection_time_ms INTEGER,
+    max_scramble_application_time_ms INTEGER,
+    max_upload_gap_hours INTEGER,
+    -- Abbreviations of programs whose solves may be auto-verified
+    allowed_programs TEXT[],
+    allow_variants BOOLEAN,
+    allow_solver_notes BOOLEAN
+);
+
+-- At most one default policy
+CREATE UNIQUE INDEX IF NOT EXISTS AutoVerifyPolicy_default ON AutoVerifyPolicy ((TRUE))
+    WHERE puzzle_id IS NULL;
+
+-- Fields of the default policy that are NULL use the defaults built into the
+-- server.
+INSERT INTO AutoVerifyPolicy (puzzle_id) VALUES (NULL) ON CONFLICT DO NOTHING;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS AverageComponent;
+DROP TABLE IF EXISTS SolveLogFile;
@@ -0,0 +1,21 @@
+-- Log files after the first one, for averages submitted as several files
+CREATE TABLE IF NOT EXISTS SolveLogFile (
+    id INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+
+    solve_id INTEGER REFERENCES Solve ON DELETE CASCADE NOT NULL,
+    file_name TEXT NOT NULL,
+    contents BYTEA NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS SolveLogFile_solve_id ON SolveLogFile (solve_id);
+
+-- Individual times in an average, found by auto-verification
+CREATE TABLE IF NOT EXISTS AverageComponent (
+    solve_id INTEGER REFERENCES Solve ON DELETE CASCADE NOT NULL,
+    -- Index of the component in the order the solves were done, starting at 0
+    position INTEGER NOT NULL,
+    -- NULL if the solve was not completed (DNF)
+    speed_cs INTEGER,
+
+    PRIMARY KEY (solve_id, position)
+);
@@ -0,0 +1 @@
+ALTER TABLE Solve DROP COLUMN IF EXISTS auto_verifier_version;
@@ -0,0 +1,3 @@
+-- Version of the verifier that produced auto_verify_output, or NULL if it was
+-- produced before versions were recorded
+ALTER TABLE Solve ADD COLUMN IF NOT EXISTS auto_verifier_version TEXT;
@@ -0,0 +1,23 @@
+-- SHA-256 hash of every log file and scramble seed ever attached to a solve,
+-- used to find solves that share a log file or scramble. Rows are never
+-- removed when a log file is replaced, so the original file is still matched.
+CREATE TABLE IF NOT EXISTS ContentHash (
+    id INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+    kind TEXT NOT NULL, -- 'log_file' or 'scramble_seed'
+    hash BYTEA NOT NULL,
+    solve_id INTEGER REFERENCES Solve ON DELETE CASCADE NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (kind, hash, solve_id)
+);
+CREATE INDEX IF NOT EXISTS ContentHash_kind_hash ON ContentHash (kind, hash);
+
+INSERT INTO ContentHash (kind, hash, solve_id)
+    SELECT 'log_file', sha256(log_file_contents), id FROM Solve
+        WHERE octet_length(log_file_contents) > 0
+    UNION
+    SELECT 'log_file', sha256(contents), solve_id FROM SolveLogFile
+        WHERE octet_length(contents) > 0
+    UNION
+    SELECT 'scramble_seed', sha256(convert_to(scramble_seed, 'UTF8')), id FROM Solve
+        WHERE scramble_seed <> ''
+    ON CONFLICT DO NOTHING;
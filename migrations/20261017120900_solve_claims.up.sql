@@ -0,0 +1,7 @@
+-- Moderator reviewing a pending solve. A claim has no effect after it expires.
+CREATE TABLE IF NOT EXISTS SolveClaim (
+    solve_id INTEGER PRIMARY KEY REFERENCES Solve ON DELETE CASCADE,
+    moderator_id INTEGER REFERENCES UserAccount NOT NULL,
+    claimed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,9 @@
+-- First moderator approval of a solve that would displace a world record. A
+-- second, different moderator must confirm it before the solve is accepted.
+CREATE TABLE IF NOT EXISTS RecordApproval (
+    solve_id INTEGER REFERENCES Solve ON DELETE CASCADE NOT NULL,
+    event_class TEXT NOT NULL, -- 'speed' or 'fewest-moves'
+    moderator_id INTEGER REFERENCES UserAccount NOT NULL,
+    approved_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (solve_id, event_class)
+);
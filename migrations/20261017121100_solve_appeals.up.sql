@@ -0,0 +1,12 @@
+-- Appeal filed by a solver against the rejection of their solve. Filing an
+-- appeal sends the solve back to the pending queue.
+CREATE TABLE IF NOT EXISTS SolveAppeal (
+    id INTEGER PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+    solve_id INTEGER REFERENCES Solve ON DELETE CASCADE NOT NULL,
+    message TEXT NOT NULL,
+    filed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMPTZ -- NULL while the solve is pending review
+);
+
+-- A solve can only have one open appeal at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS SolveAppeal_open ON SolveAppeal (solve_id) WHERE resolved_at IS NULL;
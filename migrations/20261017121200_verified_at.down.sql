@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS Solve_solver_verified_at;
+ALTER TABLE Solve DROP COLUMN IF EXISTS verified_at;
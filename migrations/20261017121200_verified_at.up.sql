@@ -0,0 +1,17 @@
+-- Time at which a result of the solve was last accepted, or NULL if neither
+-- result is accepted
+ALTER TABLE Solve ADD COLUMN IF NOT EXISTS verified_at TIMESTAMPTZ;
+
+-- Solves that were verified before the audit log existed are treated as having
+-- been verified when they were uploaded.
+UPDATE Solve
+    SET verified_at = GREATEST(upload_date, (
+        SELECT MAX(timestamp)
+        FROM SolveLog
+        WHERE SolveLog.solve_id = Solve.id
+            AND json_data->>'type' IN ('speed_verified', 'fmc_verified')
+            AND (json_data->>'new')::BOOLEAN IS TRUE
+    ))
+    WHERE speed_verified OR fmc_verified;
+
+CREATE INDEX IF NOT EXISTS Solve_solver_verified_at ON Solve (solver_id, verified_at DESC, id DESC);
@@ -1,7 +1,10 @@
+use axum::response::{IntoResponse, Redirect, Response};
 use axum_typed_multipart::TryFromMultipart;
+use itertools::Itertools;
 
 use crate::api::{UpdatePendingSubmissionsResponse, UpdateSolveResponse};
-use crate::db::{SolveId, User};
+use crate::autoverify::AutoVerifyReport;
+use crate::db::{AutoVerifyPolicyData, PuzzleId, SolveId, User};
 use crate::{AppError, AppState, RequestBody};
 
 #[derive(Debug, TryFromMultipart)]
@@ -21,7 +24,7 @@ impl RequestBody for RequestAutoVerifySolve {
             return Err(AppError::NotAuthorized);
         }
         let solve_id = SolveId(self.solve_id);
-        state.autoverifier.enqueue(solve_id).await;
+        state.enqueue_autoverify(solve_id).await?;
         Ok(UpdateSolveResponse { solve_id })
     }
 }
@@ -41,8 +44,102 @@ impl RequestBody for RequestAutoVerifyAllSolves {
             return Err(AppError::NotAuthorized);
         }
         for solve in state.get_pending_submissions().await?.into_iter() {
-            state.autoverifier.enqueue(solve.id).await;
+            state.enqueue_autoverify(solve.id).await?;
         }
         Ok(UpdatePendingSubmissionsResponse)
     }
 }
+
+/// Update to the default auto-verification policy or a puzzle override. For
+/// an override, blank fields inherit from the default policy.
+#[derive(Debug, TryFromMultipart)]
+pub struct UpdateAutoVerifyPolicy {
+    /// Blank for the default policy.
+    pub puzzle_id: Option<i32>,
+    pub max_trusted_network_latency_ms: Option<i32>,
+    pub max_inspection_time_ms: Option<i32>,
+    pub max_scramble_application_time_ms: Option<i32>,
+    pub max_upload_gap_hours: Option<i32>,
+    /// Comma-separated list of program abbreviations.
+    pub allowed_programs: Option<String>,
+    pub allow_variants: Option<bool>,
+    pub allow_solver_notes: Option<bool>,
+    pub audit_log_comment: Option<String>,
+}
+impl RequestBody for UpdateAutoVerifyPolicy {
+    type Response = UpdateAutoVerifyPolicyResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let editor = user.ok_or(AppError::NotLoggedIn)?;
+
+        let data = AutoVerifyPolicyData {
+            max_trusted_network_latency_ms: self.max_trusted_network_latency_ms,
+            max_inspection_time_ms: self.max_inspection_time_ms,
+            max_scramble_application_time_ms: self.max_scramble_application_time_ms,
+            max_upload_gap_hours: self.max_upload_gap_hours,
+            allowed_programs: self.allowed_programs.map(|programs| {
+                programs
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_owned)
+                    .collect_vec()
+            }),
+            allow_variants: self.allow_variants,
+            allow_solver_notes: self.allow_solver_notes,
+        };
+
+        state
+            .update_autoverify_policy(
+                &editor,
+                self.puzzle_id.map(PuzzleId),
+                data,
+                &self.audit_log_comment.unwrap_or_default(),
+            )
+            .await?;
+
+        Ok(UpdateAutoVerifyPolicyResponse)
+    }
+}
+
+#[must_use]
+#[derive(serde::Serialize)]
+pub struct UpdateAutoVerifyPolicyResponse;
+impl IntoResponse for UpdateAutoVerifyPolicyResponse {
+    fn into_response(self) -> Response {
+        Redirect::to("/autoverify-policy").into_response()
+    }
+}
+
+/// Dry run of auto-verification on a solve, for moderators.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct AutoVerifyReportRequest {
+    id: SolveId,
+    /// Whether to run the verifier again instead of using its stored output.
+    #[serde(default)]
+    rerun: bool,
+}
+impl RequestBody for AutoVerifyReportRequest {
+    type Response = AutoVerifyReport;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        if !user.ok_or(AppError::NotLoggedIn)?.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+        state.autoverify_report(self.id, self.rerun).await
+    }
+}
+
+impl IntoResponse for AutoVerifyReport {
+    fn into_response(self) -> Response {
+        axum::Json(self).into_response()
+    }
+}
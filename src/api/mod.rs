@@ -11,7 +11,9 @@ pub mod edit_user;
 pub mod pb;
 pub mod pkce;
 pub mod submit_solve;
+pub mod v1;
 pub mod verify_solve;
+pub mod webhooks;
 
 // TODO: give this a better home
 #[derive(Debug)]
@@ -4,11 +4,12 @@ use axum::response::IntoResponse;
 use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipartError};
 use chrono::{NaiveDate, NaiveTime, TimeDelta, Utc};
 use futures::FutureExt;
+use itertools::Itertools;
 use serde::Serialize;
 use sha2::Digest;
 
 use crate::api::UpdateSolveResponse;
-use crate::db::{SolveDbFields, SolveId, User, UserId};
+use crate::db::{AVERAGE_SIZE, SolveDbFields, SolveId, User, UserId};
 use crate::traits::{Linkable, RequestBody};
 use crate::{AppError, AppState, RecentlySubmittedSolve};
 
@@ -161,7 +162,7 @@ impl RequestBody for ManualSubmitSolveRequest {
         }
 
         let solve_id = state
-            .add_solve_external(&user, solve_data.into_raw(user.id), false)
+            .add_solve_external(&user, solve_data.into_raw(user.id), vec![], false, true)
             .await?;
 
         Ok(UpdateSolveResponse { solve_id })
@@ -205,10 +206,13 @@ pub struct AutoSolveData {
     pub solver_notes: Option<String>,
     pub computer_assisted: bool,
     pub will_upload_video: bool,
-    pub log_file: FieldData<Bytes>,
+    /// Whether the log files contain the solves of an average.
+    pub average: Option<bool>,
+    /// One or more log files. Several log files are only allowed for averages.
+    pub log_file: Vec<FieldData<Bytes>>,
 }
 
-pub struct AutoSubmitSolveRequest(AutoSolveData);
+pub struct AutoSubmitSolveRequest(pub(crate) AutoSolveData);
 impl_try_from_multipart_wrapper!(AutoSubmitSolveRequest(AutoSolveData));
 impl RequestBody for AutoSubmitSolveRequest {
     type Response = AutoSubmitSolveResponse;
@@ -225,9 +229,22 @@ impl RequestBody for AutoSubmitSolveRequest {
             solver_notes,
             computer_assisted,
             will_upload_video,
+            average,
             log_file,
         } = self.0;
 
+        let average = average.unwrap_or(false);
+        if log_file.len() > 1 && !average {
+            return Err(AppError::InvalidQuery(
+                "multiple log files are only allowed for averages".to_string(),
+            ));
+        }
+        if log_file.len() > AVERAGE_SIZE {
+            return Err(AppError::InvalidQuery(format!(
+                "an average has at most {AVERAGE_SIZE} log files",
+            )));
+        }
+
         let mut program = state.get_program_from_abbr(&program_abbr).await?;
         if program.is_none() {
             program = state.get_program_from_abbr("X").await?;
@@ -237,7 +254,11 @@ impl RequestBody for AutoSubmitSolveRequest {
             None => 1,
         };
 
-        let log_file_hash = sha2::Sha256::digest(&log_file.contents).to_vec();
+        let mut hasher = sha2::Sha256::new();
+        for data in &log_file {
+            hasher.update(&data.contents);
+        }
+        let log_file_hash = hasher.finalize().to_vec();
         let now = Utc::now();
 
         let mut recently_submitted = state.recently_submitted.lock().await;
@@ -249,6 +270,18 @@ impl RequestBody for AutoSubmitSolveRequest {
             });
         }
 
+        let mut log_files = log_file.into_iter();
+        let first_log_file = log_files.next().ok_or(AppError::NoLogFile)?;
+        let extra_log_files = log_files
+            .map(|data| {
+                let file_name = data
+                    .metadata
+                    .file_name
+                    .unwrap_or_else(|| "unknown.txt".to_string());
+                (file_name, data.contents.into())
+            })
+            .collect_vec();
+
         let solve_data = SolveData {
             solve_id: None,
             puzzle_id: 1, // Other
@@ -264,7 +297,7 @@ impl RequestBody for AutoSubmitSolveRequest {
             solve_cs: None,
             uses_filters: false,
             uses_macros: false,
-            average: false,
+            average,
             one_handed: false,
             blind: false,
             memo_h: None,
@@ -275,18 +308,24 @@ impl RequestBody for AutoSubmitSolveRequest {
             move_count: None,
             computer_assisted,
             replace_log_file: Some(true),
-            log_file: Some(log_file),
+            log_file: Some(first_log_file),
             audit_log_comment: None,
         };
 
         let solve_id = state
-            .add_solve_external(&user, solve_data.into_raw(user.id), true)
+            .add_solve_external(
+                &user,
+                solve_data.into_raw(user.id),
+                extra_log_files,
+                true,
+                true,
+            )
             .await?;
 
         let expiry = now + AUTOVERIFY_REQUEST_DUPLICATE_TIMEOUT;
         recently_submitted.insert(log_file_hash, RecentlySubmittedSolve { solve_id, expiry });
 
-        state.autoverifier.enqueue(solve_id).await;
+        state.enqueue_autoverify(solve_id).await?;
 
         Ok(AutoSubmitSolveResponse {
             url: solve_id.absolute_url(),
@@ -0,0 +1,449 @@
+//! Versioned public JSON API.
+//!
+//! The structure of every response in this module is part of the public API,
+//! so fields must not be renamed or removed without introducing a new version.
+//! See `docs/api.md` for documentation.
+
+use std::collections::HashMap;
+
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+
+use crate::db::{
+    CategoryQuery, Event, FullSolve, MainPageCategory, Program, PublicUser, Puzzle,
+    RankedFullSolve, ScoreQuery, SearchFacet, SolveFlags, SolveId, User, Variant,
+};
+use crate::html::leaderboards::global::{GlobalLeaderboardQuery, GlobalLeaderboardTable};
+use crate::html::leaderboards::per_puzzle::PuzzleLeaderboardTable;
+use crate::html::search::SolveSearch;
+use crate::html::user_page::SolverLeaderboardTable;
+use crate::traits::{Linkable, RequestBody};
+use crate::{AppError, AppState};
+
+/// Public view of a solve.
+///
+/// Speed and fewest-moves results are omitted unless the viewer is allowed to
+/// see them.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ApiSolve {
+    pub id: SolveId,
+    pub url: String,
+
+    pub solve_date: DateTime<Utc>,
+    pub upload_date: DateTime<Utc>,
+    pub solver_notes: Option<String>,
+
+    pub puzzle: Puzzle,
+    pub variant: Option<Variant>,
+    pub flags: SolveFlags,
+    pub program: Program,
+
+    pub speed_cs: Option<i32>,
+    pub memo_cs: Option<i32>,
+    pub speed_verified: Option<bool>,
+    pub move_count: Option<i32>,
+    pub fmc_verified: Option<bool>,
+
+    pub has_log_file: bool,
+    pub video_url: Option<String>,
+
+    pub solver: PublicUser,
+    pub solver_url: String,
+}
+impl ApiSolve {
+    pub fn from_full_solve(solve: &FullSolve, viewer: Option<&User>) -> Self {
+        let show_speed = solve.can_view_speed(viewer);
+        let show_fmc = solve.can_view_fmc(viewer);
+        Self {
+            id: solve.id,
+            url: solve.absolute_url(),
+
+            solve_date: solve.solve_date,
+            upload_date: solve.upload_date,
+            solver_notes: solve.solver_notes.clone().filter(|s| !s.is_empty()),
+
+            puzzle: solve.puzzle.clone(),
+            variant: solve.variant.clone(),
+            flags: solve.flags,
+            program: solve.program.clone(),
+
+            speed_cs: solve.speed_cs.filter(|_| show_speed),
+            memo_cs: solve.memo_cs.filter(|_| show_speed),
+            speed_verified: solve.speed_verified.filter(|_| show_speed),
+            move_count: solve.move_count.filter(|_| show_fmc),
+            fmc_verified: solve.fmc_verified.filter(|_| show_fmc),
+
+            has_log_file: solve.log_file_name.is_some(),
+            video_url: solve.video_url.clone(),
+
+            solver: solve.solver.clone(),
+            solver_url: solve.solver.absolute_url(),
+        }
+    }
+}
+
+/// Event in which a solve is ranked.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ApiEvent {
+    pub name: String,
+    pub url: String,
+}
+impl From<&Event> for ApiEvent {
+    fn from(event: &Event) -> Self {
+        Self {
+            name: event.name(),
+            url: event.absolute_url(),
+        }
+    }
+}
+
+/// Solve on a leaderboard.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ApiLeaderboardEntry {
+    /// Rank within the event, or `None` if not applicable.
+    pub rank: Option<i64>,
+    pub event: ApiEvent,
+    /// Number of distinct solvers in the event, or `None` if not applicable.
+    pub total_solvers: Option<i64>,
+    pub solve: ApiSolve,
+}
+
+/// Solver on a score leaderboard.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ApiScoreEntry {
+    pub rank: i64,
+    pub solver: PublicUser,
+    pub solver_url: String,
+    pub score: String,
+}
+
+/// Global leaderboard, which has either the record in each event or a score
+/// for each solver.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct GlobalLeaderboardApiRequest(GlobalLeaderboardTable);
+
+#[derive(serde::Serialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GlobalLeaderboardApiResponse {
+    Records {
+        query: CategoryQuery,
+        entries: Vec<ApiLeaderboardEntry>,
+    },
+    Scores {
+        query: ScoreQuery,
+        entries: Vec<ApiScoreEntry>,
+    },
+}
+
+impl RequestBody for GlobalLeaderboardApiRequest {
+    type Response = GlobalLeaderboardApiResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let as_of = self.0.as_of_cutoff()?;
+        match self.0.global_leaderboard_query() {
+            GlobalLeaderboardQuery::Category(query) => {
+                let solver_counts: HashMap<MainPageCategory, i64> = state
+                    .get_all_puzzles_counts(&query, as_of)
+                    .await?
+                    .into_iter()
+                    .collect();
+
+                let entries = state
+                    .get_all_puzzles_leaderboard(&query, as_of)
+                    .await?
+                    .into_iter()
+                    .map(|(event, solve)| ApiLeaderboardEntry {
+                        rank: None,
+                        event: ApiEvent::from(&event),
+                        total_solvers: Some(
+                            *solver_counts
+                                .get(&query.main_page_category_of_solve(&solve))
+                                .unwrap_or(&0),
+                        ),
+                        solve: ApiSolve::from_full_solve(&solve, user.as_ref()),
+                    })
+                    .sorted_by_key(|entry| entry.total_solvers.map(|n| -n))
+                    .collect();
+
+                Ok(GlobalLeaderboardApiResponse::Records { query, entries })
+            }
+
+            GlobalLeaderboardQuery::Score(query) => {
+                let entries = state
+                    .get_score_leaderboard(query, as_of)
+                    .await?
+                    .into_iter()
+                    .map(|(rank, solver, score)| ApiScoreEntry {
+                        rank,
+                        solver_url: solver.absolute_url(),
+                        solver,
+                        score,
+                    })
+                    .collect();
+
+                Ok(GlobalLeaderboardApiResponse::Scores { query, entries })
+            }
+        }
+    }
+}
+
+/// Leaderboard or record history for a single puzzle.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct PuzzleLeaderboardApiRequest(PuzzleLeaderboardTable);
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct PuzzleLeaderboardApiResponse {
+    pub puzzle: Puzzle,
+    pub query: CategoryQuery,
+    pub history: bool,
+    /// Number of distinct solvers on the leaderboard, or `None` for record
+    /// history.
+    pub total_solvers: Option<i64>,
+    pub entries: Vec<ApiLeaderboardEntry>,
+}
+
+impl RequestBody for PuzzleLeaderboardApiRequest {
+    type Response = PuzzleLeaderboardApiResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let query = self.0.category_query()?;
+        let puzzle = state
+            .get_puzzle(self.0.id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let history = self.0.history;
+
+        let entries = self
+            .0
+            .ranked_solves(&state, &puzzle, &query)
+            .await?
+            .into_iter()
+            .map(|RankedFullSolve { rank, solve }| ApiLeaderboardEntry {
+                rank: (!history).then_some(rank),
+                event: ApiEvent::from(&query.event_of_solve(&solve)),
+                total_solvers: None,
+                solve: ApiSolve::from_full_solve(&solve, user.as_ref()),
+            })
+            .collect_vec();
+
+        let total_solvers = (!history).then(|| {
+            entries
+                .iter()
+                .map(|entry| entry.solve.solver.id)
+                .unique()
+                .count() as i64
+        });
+
+        Ok(PuzzleLeaderboardApiResponse {
+            puzzle,
+            query,
+            history,
+            total_solvers,
+            entries,
+        })
+    }
+}
+
+/// Personal bests of a solver in every event matching a category query.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct SolverApiRequest(SolverLeaderboardTable);
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct SolverApiResponse {
+    pub solver: PublicUser,
+    pub query: CategoryQuery,
+    pub entries: Vec<ApiLeaderboardEntry>,
+}
+
+impl RequestBody for SolverApiRequest {
+    type Response = SolverApiResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let query = self.0.category_query()?;
+        let solver = state
+            .get_opt_user(self.0.id)
+            .await?
+            .ok_or(AppError::NotFound)?
+            .to_public();
+
+        if self.0.history {
+            let entries = state
+                .get_solver_pb_history(solver.id, &query)
+                .await?
+                .into_iter()
+                .flat_map(|(_category, solves)| solves)
+                .sorted_by_key(|ranked_solve| ranked_solve.solve.solve_date)
+                .map(|RankedFullSolve { rank, solve }| ApiLeaderboardEntry {
+                    rank: Some(rank),
+                    event: ApiEvent::from(&query.event_of_solve(&solve)),
+                    total_solvers: None,
+                    solve: ApiSolve::from_full_solve(&solve, user.as_ref()),
+                })
+                .collect();
+
+            return Ok(SolverApiResponse {
+                solver,
+                query,
+                entries,
+            });
+        }
+
+        let solver_counts: HashMap<MainPageCategory, i64> = state
+            .get_all_puzzles_counts(&query, None)
+            .await?
+            .into_iter()
+            .collect();
+
+        let entries = state
+            .get_solver_pbs(solver.id, &query)
+            .await?
+            .into_iter()
+            .sorted_by_key(|(category, solve)| {
+                (solve.rank, *solver_counts.get(category).unwrap_or(&0))
+            })
+            .map(
+                |(category, RankedFullSolve { rank, solve })| ApiLeaderboardEntry {
+                    rank: Some(rank),
+                    event: ApiEvent::from(&query.event_of_solve(&solve)),
+                    total_solvers: Some(*solver_counts.get(&category).unwrap_or(&0)),
+                    solve: ApiSolve::from_full_solve(&solve, user.as_ref()),
+                },
+            )
+            .collect();
+
+        Ok(SolverApiResponse {
+            solver,
+            query,
+            entries,
+        })
+    }
+}
+
+/// Single solve.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct SolveApiRequest {
+    id: SolveId,
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct SolveApiResponse {
+    pub event: ApiEvent,
+    pub solve: ApiSolve,
+}
+
+impl RequestBody for SolveApiRequest {
+    type Response = SolveApiResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let solve = state.get_solve(self.id).await?;
+        if !solve.can_view_opt(user.as_ref()) {
+            return Err(AppError::NotAuthorized);
+        }
+
+        Ok(SolveApiResponse {
+            event: ApiEvent::from(&solve.primary_event()),
+            solve: ApiSolve::from_full_solve(&solve, user.as_ref()),
+        })
+    }
+}
+
+/// Page of solves matching a search.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct SearchApiRequest(SolveSearch);
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct SearchApiResponse {
+    /// Total number of matching solves, across all pages.
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub solves: Vec<ApiSolve>,
+    pub facets: ApiSearchFacets,
+}
+
+/// Number of matching solves for each puzzle and program.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ApiSearchFacets {
+    pub puzzles: Vec<SearchFacet>,
+    pub programs: Vec<SearchFacet>,
+}
+
+impl RequestBody for SearchApiRequest {
+    type Response = SearchApiResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let results = state
+            .search_solves(&self.0.search_query()?, user.as_ref())
+            .await?;
+
+        Ok(SearchApiResponse {
+            total: results.total,
+            page: self.0.page(),
+            per_page: self.0.per_page(),
+            solves: results
+                .solves
+                .iter()
+                .map(|solve| ApiSolve::from_full_solve(solve, user.as_ref()))
+                .collect(),
+            facets: ApiSearchFacets {
+                puzzles: results.puzzles,
+                programs: results.programs,
+            },
+        })
+    }
+}
+
+impl IntoResponse for GlobalLeaderboardApiResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+impl IntoResponse for PuzzleLeaderboardApiResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+impl IntoResponse for SolverApiResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+impl IntoResponse for SolveApiResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+impl IntoResponse for SearchApiResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
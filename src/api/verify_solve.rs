@@ -1,14 +1,19 @@
 use axum_typed_multipart::{TryFromField, TryFromMultipart};
 
 use crate::api::UpdateSolveResponse;
-use crate::db::{SolveId, User};
+use crate::db::{RejectionReason, SolveId, User};
 use crate::{AppError, AppState, RequestBody};
 
+/// Maximum length of an appeal message, in characters.
+const MAX_APPEAL_MESSAGE_LEN: usize = 2000;
+
 #[derive(TryFromMultipart)]
 pub struct VerifySolveRequest {
     solve_id: i32,
     speed: Option<VerifyAction>,
     fmc: Option<VerifyAction>,
+    /// [`RejectionReason::id()`] of the reason for rejecting the solve.
+    rejection_reason: Option<String>,
     audit_log_comment: Option<String>,
 }
 
@@ -40,13 +45,20 @@ impl RequestBody for VerifySolveRequest {
         let editor = user.ok_or(AppError::NotLoggedIn)?;
 
         let audit_log_comment = self.audit_log_comment.unwrap_or_default();
+        let rejection_reason = match self.rejection_reason.as_deref() {
+            None | Some("") => None,
+            Some(id) => Some(RejectionReason::from_id(id).ok_or_else(|| {
+                AppError::InvalidQuery(format!("unknown rejection reason {id:?}"))
+            })?),
+        };
 
         if let Some(speed_verify) = self.speed {
             state
-                .verify_speed(
+                .verify_speed_with_reason(
                     &editor,
                     SolveId(self.solve_id),
                     speed_verify.into(),
+                    rejection_reason,
                     &audit_log_comment,
                 )
                 .await?;
@@ -54,10 +66,11 @@ impl RequestBody for VerifySolveRequest {
 
         if let Some(fmc_verify) = self.fmc {
             state
-                .verify_fmc(
+                .verify_fmc_with_reason(
                     &editor,
                     SolveId(self.solve_id),
                     fmc_verify.into(),
+                    rejection_reason,
                     &audit_log_comment,
                 )
                 .await?;
@@ -68,3 +81,69 @@ impl RequestBody for VerifySolveRequest {
         })
     }
 }
+
+#[derive(TryFromMultipart)]
+pub struct ClaimSolveRequest {
+    solve_id: i32,
+}
+
+impl RequestBody for ClaimSolveRequest {
+    type Response = UpdateSolveResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let editor = user.ok_or(AppError::NotLoggedIn)?;
+        let solve_id = SolveId(self.solve_id);
+        state.claim_solve(&editor, solve_id).await?;
+        Ok(UpdateSolveResponse { solve_id })
+    }
+}
+
+#[derive(TryFromMultipart)]
+pub struct ReleaseSolveClaimRequest {
+    solve_id: i32,
+}
+
+impl RequestBody for ReleaseSolveClaimRequest {
+    type Response = UpdateSolveResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let editor = user.ok_or(AppError::NotLoggedIn)?;
+        let solve_id = SolveId(self.solve_id);
+        state.release_solve_claim(&editor, solve_id).await?;
+        Ok(UpdateSolveResponse { solve_id })
+    }
+}
+
+#[derive(TryFromMultipart)]
+pub struct AppealSolveRequest {
+    solve_id: i32,
+    message: String,
+}
+
+impl RequestBody for AppealSolveRequest {
+    type Response = UpdateSolveResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let editor = user.ok_or(AppError::NotLoggedIn)?;
+        let solve_id = SolveId(self.solve_id);
+        if self.message.chars().count() > MAX_APPEAL_MESSAGE_LEN {
+            return Err(AppError::CannotAppeal(format!(
+                "message is longer than {MAX_APPEAL_MESSAGE_LEN} characters"
+            )));
+        }
+        state.file_appeal(&editor, solve_id, &self.message).await?;
+        Ok(UpdateSolveResponse { solve_id })
+    }
+}
@@ -0,0 +1,86 @@
+use axum::response::{IntoResponse, Redirect, Response};
+use axum_typed_multipart::TryFromMultipart;
+use itertools::Itertools;
+
+use crate::db::{User, WebhookData, WebhookId};
+use crate::{AppError, AppState, RequestBody};
+
+#[derive(TryFromMultipart)]
+pub struct UpdateWebhook {
+    pub id: Option<i32>,
+    pub name: String,
+    pub url: String,
+    /// Blank to keep the existing secret, or to generate one for a new
+    /// webhook.
+    pub secret: Option<String>,
+    /// Comma-separated list of event names. Blank for all events.
+    pub events: Option<String>,
+    pub enabled: bool,
+    pub audit_log_comment: Option<String>,
+}
+impl RequestBody for UpdateWebhook {
+    type Response = UpdateWebhooksResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let editor = user.ok_or(AppError::NotLoggedIn)?;
+
+        let data = WebhookData {
+            name: self.name,
+            url: self.url.trim().to_string(),
+            secret: self.secret.filter(|s| !s.is_empty()),
+            events: self
+                .events
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_owned)
+                .collect_vec(),
+            enabled: self.enabled,
+        };
+
+        let generated_secret = if let Some(id) = self.id {
+            state
+                .update_webhook(
+                    &editor,
+                    WebhookId(id),
+                    data,
+                    &self.audit_log_comment.unwrap_or_default(),
+                )
+                .await?;
+            None
+        } else {
+            let is_generated = data.secret.is_none();
+            let (_, secret) = state.add_webhook(&editor, data).await?;
+            is_generated.then_some(secret)
+        };
+
+        Ok(UpdateWebhooksResponse {
+            editor,
+            generated_secret,
+        })
+    }
+}
+
+#[must_use]
+pub struct UpdateWebhooksResponse {
+    editor: User,
+    /// Secret generated for a new webhook, which is shown only once.
+    generated_secret: Option<String>,
+}
+impl IntoResponse for UpdateWebhooksResponse {
+    fn into_response(self) -> Response {
+        match self.generated_secret {
+            Some(secret) => crate::render_html_template(
+                "webhook-secret.html",
+                &Some(self.editor),
+                serde_json::json!({ "secret": secret }),
+            ),
+            None => Redirect::to("/webhooks").into_response(),
+        }
+    }
+}
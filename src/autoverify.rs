@@ -1,291 +1,258 @@
 use std::{
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 
-use chrono::TimeDelta;
+use chrono::{TimeDelta, Utc};
 use hyperspeedcube_cli_types::verification::{Durations, SolveVerification};
 use itertools::Itertools;
-use tokio::{sync::Mutex, time::timeout};
-use tokio_condvar::Condvar;
+use serde::Serialize;
+use tokio::{sync::Notify, time::timeout};
 
 use crate::{
     AppError, AppResult, AppState,
-    db::{SolveDbFields, SolveId},
+    db::{
+        AVERAGE_SIZE, AutoVerifyPolicy, FullSolve, RejectionReason, SolveDbFields, SolveId,
+        UNKNOWN_VERIFIER_VERSION, User, average_of,
+    },
+    verifier::{Verifier, verifier_for_program},
 };
 
-const VERIFICATION_TIMEOUT: Duration = Duration::from_secs(60); // 60 seconds
-
-/// Maximum network latency to allow before requiring manual review of a
-/// speedsolve submission.
-const MAX_TRUSTED_NETWORK_LATENCY: TimeDelta = TimeDelta::seconds(5);
-/// Maximum inspection time to allow before requiring manual review of a
-/// speedsolve submission.
-const MAX_INSPECTION_TIME: TimeDelta = TimeDelta::seconds(60);
-/// Maximum time allowed for scrambling a puzzle before requiring manual review
-/// of a speedsolve submission.
-const MAX_SCRAMBLE_APPLICATION_TIME: TimeDelta = TimeDelta::seconds(5);
-/// Maximum time to allow between a solve's completion and its upload before
-/// requiring manual review of a speedsolve or FMC submission, unless the solve
-/// completion was timestamped.
-const MAX_UPLOAD_GAP: Duration = Duration::from_hours(48); // 2 days
-
-#[derive(Default)]
+/// Time after which another worker may take over a solve, in case the worker
+/// verifying it has crashed.
+const LEASE_DURATION: TimeDelta = TimeDelta::minutes(5);
+/// Maximum number of attempts to verify a solve before it needs manual review.
+const MAX_ATTEMPTS: i32 = 5;
+/// Delay before retrying a failed solve. Each subsequent retry waits twice as
+/// long as the previous one.
+const INITIAL_RETRY_DELAY: TimeDelta = TimeDelta::seconds(30);
+/// Interval at which idle workers check the queue for solves that are ready to
+/// be retried, even if no solves have been enqueued.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Pool of auto-verification workers.
+///
+/// The queue itself is stored in the `AutoVerifyQueue` table so that it
+/// survives restarts.
 pub struct SolveAutoVerifier {
-    queue: Mutex<VecDeque<SolveId>>,
-    condvar: Condvar,
+    /// Identifier for this process, used to lease solves in the queue.
+    process_id: String,
+    notify: Notify,
+    /// Whether a re-verification campaign is running.
+    reverify_running: AtomicBool,
 }
 
-impl SolveAutoVerifier {
-    pub async fn enqueue(&self, solve: SolveId) {
-        let mut queue = self.queue.lock().await;
-        if !queue.contains(&solve) {
-            tracing::info!("Enqueueing solve {solve} for autoverification");
-            queue.push_back(solve);
-            self.condvar.notify_one();
-        } else {
-            tracing::info!("Solve {solve} is already queued for autoverification");
+impl Default for SolveAutoVerifier {
+    fn default() -> Self {
+        Self {
+            process_id: format!(
+                "{}-{}",
+                std::process::id(),
+                crate::util::random_b64_string(8),
+            ),
+            notify: Notify::new(),
+            reverify_running: AtomicBool::new(false),
         }
     }
+}
 
-    pub async fn index_of(&self, solve: SolveId) -> Option<usize> {
-        self.queue.lock().await.iter().position(|&id| id == solve)
+impl SolveAutoVerifier {
+    /// Wakes up a worker to check the queue.
+    pub fn notify(&self) {
+        self.notify.notify_one();
     }
 
-    pub async fn queue_snapshot(&self) -> Vec<SolveId> {
-        self.queue.lock().await.iter().copied().collect()
+    /// Marks a re-verification campaign as running until the returned guard
+    /// is dropped, or returns `None` if one is already running.
+    pub fn try_start_reverify(self: &Arc<Self>) -> Option<ReverifyGuard> {
+        (!self.reverify_running.swap(true, Ordering::SeqCst))
+            .then(|| ReverifyGuard(Arc::clone(self)))
     }
+}
 
-    pub async fn wait_for_next(&self) -> SolveId {
-        let mut queue = self.queue.lock().await;
-        loop {
-            match queue.front() {
-                Some(&id) => break id,
-                None => queue = self.condvar.wait(queue).await,
-            }
-        }
+/// Marks the running re-verification campaign as finished when dropped, even
+/// if the campaign fails or panics.
+pub struct ReverifyGuard(Arc<SolveAutoVerifier>);
+impl Drop for ReverifyGuard {
+    fn drop(&mut self) {
+        self.0.reverify_running.store(false, Ordering::SeqCst);
     }
+}
 
-    pub async fn pop_next(&self) {
-        self.queue.lock().await.pop_front();
-    }
+/// Returns whether an auto-verification error is likely to be temporary, so
+/// the solve should be retried.
+fn is_retryable(e: &AppError) -> bool {
+    matches!(
+        e,
+        AppError::SolveVerificationTimeout(_) | AppError::IoError(_)
+    )
 }
 
 impl AppState {
-    pub async fn autoverify_solve_immediately(&self, solve_id: SolveId) -> AppResult<()> {
-        let editor = self.get_hsc_auto_verify_dummy_user().await?;
+    /// Adds a solve to the auto-verification queue.
+    pub async fn enqueue_autoverify(&self, solve_id: SolveId) -> AppResult {
+        if self.add_to_autoverify_queue(solve_id).await? {
+            tracing::info!("Enqueueing solve {solve_id} for autoverification");
+        } else {
+            tracing::info!("Solve {solve_id} is already queued for autoverification");
+        }
+        self.autoverifier.notify();
+        Ok(())
+    }
 
-        tracing::info!("Autoverifying solve {solve_id} ...");
+    /// Returns the position of a solve in the auto-verification queue, where
+    /// 0 means it is currently being verified.
+    pub async fn autoverify_queue_index(&self, solve_id: SolveId) -> AppResult<Option<usize>> {
+        Ok(self
+            .get_autoverify_queue()
+            .await?
+            .iter()
+            .filter(|entry| !entry.needs_manual_review)
+            .position(|entry| entry.solve_id == solve_id))
+    }
 
-        match self.compute_autoverify_status(solve_id).await {
-            Ok(auto_verify_output) => {
-                let data = self.get_solve(solve_id).await?;
+    /// Starts [`crate::env::AUTOVERIFY_WORKERS`] workers to process the
+    /// auto-verification queue, resuming any solves whose lease expired
+    /// before they were verified.
+    pub async fn run_autoverify_workers(&self) {
+        match self.release_expired_autoverify_leases().await {
+            Ok(0) => (),
+            Ok(n) => tracing::info!("Resuming autoverification of {n} interrupted solves"),
+            Err(e) => tracing::error!("Error releasing autoverify leases: {e}"),
+        }
 
-                let puzzle_id = match self
-                    .get_or_create_puzzle_with_hsc_id(&auto_verify_output.puzzle_canonical_id)
-                    .await
-                {
-                    Ok(id) => id,
-                    Err(e @ AppError::PuzzleIsNotLeaderboardEligible(_)) => {
-                        tracing::info!("Autoverifier rejected solve {solve_id}: {e}");
-                        let audit_log_comment = e.to_string();
-                        let mut has_move_count = data.move_count.is_some();
-                        let has_speed = data.speed_cs.is_some();
-                        if !has_move_count && !has_speed {
-                            // Ensure there's some data to reject
-                            let mut new_data = SolveDbFields::from(data);
-                            new_data.move_count = Some(1);
-                            has_move_count = true;
-                            self.update_solve(
-                                solve_id,
-                                new_data,
-                                &editor,
-                                "Adding move count so the solve can be rejected",
-                            )
-                            .await?;
-                        }
-                        if has_move_count {
-                            self.verify_fmc(&editor, solve_id, Some(false), &audit_log_comment)
-                                .await?;
-                        }
-                        if has_speed {
-                            self.verify_speed(&editor, solve_id, Some(false), &audit_log_comment)
-                                .await?;
-                        }
-                        return Ok(());
-                    }
-                    Err(e) => return Err(e),
-                };
-
-                let Durations {
-                    scramble_network_latency,
-                    scramble_application,
-                    inspection,
-                    mut speedsolve,
-                    memo,
-                    blindsolve,
-                    timestamp_network_latency,
-                } = auto_verify_output.durations;
-
-                let mut reasons_to_not_autoverify_anything = vec![];
-                let mut reasons_to_not_autoverify_speed = vec![];
-
-                // Add excess inspection time to speedsolve duration.
-                match inspection {
-                    Some(inspection_dur) => {
-                        if let Some(speedsolve_time) = &mut speedsolve
-                            && let Some(excess_inspection) =
-                                inspection_dur.checked_sub(&MAX_INSPECTION_TIME)
-                            && excess_inspection > TimeDelta::zero()
-                        {
-                            tracing::info!("Adding {excess_inspection} to speedsolve {solve_id}");
-                            *speedsolve_time += excess_inspection;
-                        }
-                    }
-                    None => {
-                        reasons_to_not_autoverify_speed.push("Inspection time unknown".to_string());
-                    }
-                };
-
-                let fields = SolveDbFields {
-                    puzzle_id: puzzle_id.0,
-                    variant_id: data.variant.map(|v| {
-                        reasons_to_not_autoverify_anything
-                            .push("Variants require manual review".to_string());
-                        v.id.0
-                    }),
-                    program_id: {
-                        if data.program.abbr != "HSC2" {
-                            reasons_to_not_autoverify_anything
-                                .push("Programs other than HSC2 require manual review".to_string());
-                        }
-                        data.program.id.0
-                    },
-                    solver_id: data.solver.id.0,
-                    solve_date: {
-                        auto_verify_output
-                            .verified_timestamps
-                            .completion
-                            .unwrap_or_else(|| {
-                                if data.solve_date < data.upload_date - MAX_UPLOAD_GAP {
-                                    reasons_to_not_autoverify_anything.push(format!(
-                                        "Solve was uploaded {} days after claimed completion date",
-                                        (data.upload_date - data.solve_date).num_days(),
-                                    ));
-                                }
-                                data.solve_date.min(data.upload_date)
-                            })
-                    },
-                    solver_notes: data
-                        .solver_notes
-                        .filter(|s| !s.is_empty())
-                        .inspect(|_| {
-                            reasons_to_not_autoverify_anything
-                                .push("Solver note requires manual review".to_string());
-                        })
-                        .unwrap_or_default(),
-                    moderator_notes: data.moderator_notes,
-                    auto_verify_output: Some(serde_json::to_value(&auto_verify_output)?),
-                    average: {
-                        if data.flags.average {
-                            return Err(AppError::Other("averages cannot be autoverified".into()));
-                        }
-                        data.flags.average
-                    },
-                    blind: blindsolve.is_some(),
-                    filters: auto_verify_output.used_filters,
-                    macros: auto_verify_output.used_macros,
-                    one_handed: {
-                        if data.flags.one_handed {
-                            reasons_to_not_autoverify_anything
-                                .push("One-handed solve requires manual review".to_string());
-                        }
-                        data.flags.one_handed
-                    },
-                    computer_assisted: data.flags.computer_assisted, // we trust
-                    move_count: auto_verify_output.solution_stm.try_into().ok(),
-                    speed_cs: blindsolve
-                        .or(speedsolve)
-                        .and_then(duration_to_cs)
-                        .or_else(|| {
-                            if data.speed_cs.is_some() {
-                                reasons_to_not_autoverify_speed
-                                    .push("Speedsolve autoverification failed".to_string());
-                            }
-                            data.speed_cs
-                        }),
-                    memo_cs: memo.and_then(duration_to_cs).or_else(|| {
-                        if data.memo_cs.is_some() {
-                            reasons_to_not_autoverify_speed
-                                .push("Memorization time requires manual review".to_string());
-                        }
-                        data.memo_cs
-                    }),
-                    log_file: None, // don't change
-                    video_url: {
-                        if data.video_url.is_some() {
-                            reasons_to_not_autoverify_anything
-                                .push("Video requires manual review".to_string());
-                        }
-                        data.video_url
-                    },
-                };
-
-                for (name, duration, max_time) in [
-                    (
-                        "Scramble network latency",
-                        scramble_network_latency,
-                        MAX_TRUSTED_NETWORK_LATENCY,
-                    ),
-                    (
-                        "Scramble application time",
-                        scramble_application,
-                        MAX_SCRAMBLE_APPLICATION_TIME,
-                    ),
-                    (
-                        "Timestamp network latency",
-                        timestamp_network_latency,
-                        MAX_TRUSTED_NETWORK_LATENCY,
-                    ),
-                ] {
-                    match duration {
-                        Some(dur) => {
-                            if dur > max_time {
-                                reasons_to_not_autoverify_speed.push(format!("{name} was {dur}"));
-                            }
-                        }
-                        None => reasons_to_not_autoverify_speed.push(format!("{name} unknown")),
-                    }
-                }
+        let worker_count = (*crate::env::AUTOVERIFY_WORKERS).max(1);
+        tracing::info!("Starting {worker_count} autoverify workers");
+        let workers = (0..worker_count).map(|i| {
+            let state = self.clone();
+            let worker_id = format!("{}-{i}", self.autoverifier.process_id);
+            tokio::spawn(async move { state.run_autoverify_worker(&worker_id).await })
+        });
+        futures::future::join_all(workers).await;
+    }
 
-                let mut audit_log_comment = itertools::chain!(
-                    &reasons_to_not_autoverify_anything,
-                    &reasons_to_not_autoverify_speed,
-                    &auto_verify_output.errors,
-                )
-                .join("\n");
-                if !audit_log_comment.is_empty() {
-                    audit_log_comment = format!("Unable to autoverify:\n{audit_log_comment}");
+    /// Processes the auto-verification queue forever.
+    async fn run_autoverify_worker(&self, worker_id: &str) {
+        loop {
+            let lease_expires_at = Utc::now() + LEASE_DURATION;
+            let entry = match self
+                .lease_next_autoverify(worker_id, lease_expires_at)
+                .await
+            {
+                Ok(Some(entry)) => entry,
+                Ok(None) => {
+                    // Wait until a solve is enqueued or might be ready to retry.
+                    let _ = timeout(POLL_INTERVAL, self.autoverifier.notify.notified()).await;
+                    continue;
+                }
+                Err(e) => {
+                    tracing::error!("Error reading autoverify queue: {e}");
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    continue;
                 }
+            };
+
+            let solve_id = entry.solve_id;
+            let attempt = entry.attempts;
+            let result = if attempt > MAX_ATTEMPTS {
+                // The last attempt was interrupted, perhaps by a crash.
+                Err(AppError::Other(format!(
+                    "Interrupted after {MAX_ATTEMPTS} attempts",
+                )))
+            } else {
+                tracing::info!(
+                    worker_id,
+                    "Autoverify attempt {attempt} for solve {solve_id}"
+                );
+                self.autoverify_solve_immediately(solve_id).await
+            };
+
+            let done = match result {
+                Ok(()) => self
+                    .remove_from_autoverify_queue(solve_id)
+                    .await
+                    .map(|()| true)
+                    .map_err(AppError::from),
+                Err(e) => self.handle_autoverify_failure(solve_id, attempt, e).await,
+            };
+            match done {
+                Ok(true) => self.alert_discord_of_auto_verification(solve_id).await,
+                Ok(false) => (),
+                Err(e) => tracing::error!("Error updating autoverify queue: {e}"),
+            }
+        }
+    }
+
+    /// Records a failed attempt to verify a solve in the queue and the solve
+    /// audit log, and either schedules a retry or marks the solve as needing
+    /// manual review. Returns whether the solve has left the queue.
+    async fn handle_autoverify_failure(
+        &self,
+        solve_id: SolveId,
+        attempt: i32,
+        error: AppError,
+    ) -> AppResult<bool> {
+        let editor = self.get_hsc_auto_verify_dummy_user().await?;
+        let error_message = error.to_string();
 
-                let verify_fmc =
-                    fields.move_count.is_some() && reasons_to_not_autoverify_anything.is_empty();
-                let verify_speed = fields.speed_cs.is_some()
-                    && reasons_to_not_autoverify_anything.is_empty()
-                    && reasons_to_not_autoverify_speed.is_empty();
+        let retry_delay = (attempt < MAX_ATTEMPTS && is_retryable(&error))
+            .then(|| INITIAL_RETRY_DELAY * 2_i32.pow(attempt.max(1) as u32 - 1));
+        let comment = match retry_delay {
+            Some(delay) => format!(
+                "Auto-verification attempt {attempt} failed: {error_message}\n\
+                 Retrying in {} seconds",
+                delay.num_seconds(),
+            ),
+            None => format!(
+                "Auto-verification attempt {attempt} failed: {error_message}\n\
+                 Giving up; solve needs manual review",
+            ),
+        };
+        tracing::warn!("Solve {solve_id}: {comment}");
 
-                self.update_solve(solve_id, fields, &editor, &audit_log_comment)
+        let mut transaction = self.pool.begin().await?;
+        Self::add_solve_log_entry(
+            &mut transaction,
+            &editor,
+            solve_id,
+            crate::db::AuditLogEvent::Updated {
+                object: None,
+                fields: BTreeMap::new(),
+                comment: Some(comment),
+            },
+        )
+        .await?;
+        transaction.commit().await?;
+
+        match retry_delay {
+            Some(delay) => {
+                self.retry_autoverify_later(solve_id, &error_message, Utc::now() + delay)
                     .await?;
+                Ok(false)
+            }
+            None => {
+                self.give_up_autoverify(solve_id, &error_message).await?;
+                Ok(true)
+            }
+        }
+    }
 
-                if verify_fmc {
-                    self.verify_fmc(&editor, solve_id, Some(true), "").await?;
-                }
-                if verify_speed {
-                    self.verify_speed(&editor, solve_id, Some(true), "").await?;
-                }
+    pub async fn autoverify_solve_immediately(&self, solve_id: SolveId) -> AppResult<()> {
+        let editor = self.get_hsc_auto_verify_dummy_user().await?;
 
+        tracing::info!("Autoverifying solve {solve_id} ...");
+
+        match self.run_autoverify(solve_id, false).await {
+            Ok(result) => {
+                self.save_autoverify_result(&editor, solve_id, result, "")
+                    .await?;
                 tracing::info!("Autoverification of solve {solve_id} succeeded");
             }
+            Err(e) if is_retryable(&e) => return Err(e),
             Err(e) => {
                 let mut transaction = self.pool.begin().await?;
                 Self::add_solve_log_entry(
@@ -308,38 +275,817 @@ impl AppState {
         Ok(())
     }
 
-    async fn compute_autoverify_status(&self, solve: SolveId) -> AppResult<SolveVerification> {
-        let log_file_contents = self
-            .get_log_file_contents(solve, &self.pool)
-            .await?
-            .unwrap_or_default();
+    /// Runs the verifier on a solve and returns what should be saved. If
+    /// `dry_run` is `true`, nothing is written to the database, not even a
+    /// new puzzle.
+    pub(crate) async fn run_autoverify(
+        &self,
+        solve_id: SolveId,
+        dry_run: bool,
+    ) -> AppResult<AutoVerifyResult> {
+        self.run_autoverify_with_versions(solve_id, dry_run, &mut HashMap::new())
+            .await
+    }
+
+    /// Same as [`Self::run_autoverify()`], but takes the verifier version from
+    /// `versions`, keyed by program abbreviation, instead of asking the
+    /// verifier if it is already known.
+    pub(crate) async fn run_autoverify_with_versions(
+        &self,
+        solve_id: SolveId,
+        dry_run: bool,
+        versions: &mut HashMap<String, String>,
+    ) -> AppResult<AutoVerifyResult> {
+        let data = self.get_solve(solve_id).await?;
+        // Programs without a verifier are left for manual review without
+        // running anything.
+        let verifier = verifier_for_program(&data.program.abbr)
+            .ok_or_else(|| AppError::NoVerifierForProgram(data.program.abbr.clone()))?;
+        let (verifications, verifier_stderr) =
+            self.compute_autoverify_status(verifier, solve_id).await?;
+        let verifier_version = match versions.get(&data.program.abbr) {
+            Some(version) => version.clone(),
+            None => {
+                let version = verifier.version().await?;
+                versions.insert(data.program.abbr.clone(), version.clone());
+                version
+            }
+        };
+
+        let outcome = self
+            .apply_autoverify_policy(data, &verifications, dry_run)
+            .await?;
+
+        Ok(AutoVerifyResult {
+            verifier_version,
+            verifications,
+            verifier_stderr,
+            outcome,
+        })
+    }
+
+    /// Checks the solves reported by the verifier against the
+    /// auto-verification policy.
+    async fn apply_autoverify_policy(
+        &self,
+        data: FullSolve,
+        verifications: &[SolveVerification],
+        dry_run: bool,
+    ) -> AppResult<AutoVerifyOutcome> {
+        let solve_id = data.id;
+        if verifications.is_empty() {
+            return Err(AppError::VerificationFailed(
+                "no verification output".to_string(),
+            ));
+        }
+
+        // An average uses every solve in its log files. Otherwise, only the
+        // first solve counts.
+        let is_average = data.flags.average;
+        let components = if is_average {
+            verifications
+        } else {
+            &verifications[..1]
+        };
+        let auto_verify_output = &components[0];
+
+        let hsc_puzzle_id = &auto_verify_output.puzzle_canonical_id;
+        let puzzle = if dry_run {
+            self.find_puzzle_with_hsc_id(hsc_puzzle_id).await
+        } else {
+            self.get_or_create_puzzle_with_hsc_id(hsc_puzzle_id)
+                .await
+                .map(Ok)
+        };
+        let (puzzle_id, new_puzzle_name) = match puzzle {
+            Ok(Ok(id)) => (id, None),
+            // The puzzle would be created, which only happens in a dry run.
+            Ok(Err(puzzle_data)) => (data.puzzle.id, Some(puzzle_data.name)),
+            Err(e @ AppError::PuzzleIsNotLeaderboardEligible(_)) => {
+                return Ok(AutoVerifyOutcome::Ineligible(e));
+            }
+            Err(e) => return Err(e),
+        };
+        let policy = match new_puzzle_name {
+            // A new puzzle cannot have an override.
+            Some(_) => self.get_default_autoverify_policy().await?,
+            None => self.get_autoverify_policy(puzzle_id).await?,
+        };
+
+        let mut checks = vec![];
+        let mut verifier_errors = vec![];
+
+        if is_average {
+            checks.push(PolicyCheck::new(
+                "Average size",
+                components.len(),
+                AVERAGE_SIZE,
+                components.len() == AVERAGE_SIZE,
+                format!(
+                    "Average has {} solves instead of {AVERAGE_SIZE}",
+                    components.len(),
+                ),
+            ));
+            let same_puzzle = components.iter().all(|component| {
+                component.puzzle_canonical_id == auto_verify_output.puzzle_canonical_id
+            });
+            checks.push(PolicyCheck::new(
+                "Same puzzle",
+                yes_no(same_puzzle),
+                "required",
+                same_puzzle,
+                "Average contains solves of different puzzles",
+            ));
+        }
+
+        let mut checked_speedsolves = vec![];
+        for (i, component) in components.iter().enumerate() {
+            let prefix = if is_average {
+                format!("Solve {}: ", i + 1)
+            } else {
+                String::new()
+            };
+            let mut checked = check_speedsolve(&policy, solve_id, &prefix, &component.durations);
+            checks.append(&mut checked.checks);
+            verifier_errors.extend(component.errors.iter().map(|e| format!("{prefix}{e}")));
+            checked_speedsolves.push(checked);
+        }
+
+        let blind = checked_speedsolves.iter().any(|checked| checked.blind);
+        if is_average {
+            checks.push(
+                PolicyCheck::new(
+                    "Blindfolded average",
+                    yes_no(blind),
+                    "requires manual review",
+                    !blind,
+                    "Blindfolded averages require manual review",
+                )
+                .speed_only(),
+            );
+        }
+
+        let component_times_cs = checked_speedsolves
+            .iter()
+            .map(|checked| checked.time.and_then(duration_to_cs))
+            .collect_vec();
+        let verified_speed_cs = if is_average {
+            average_of(&component_times_cs)
+        } else {
+            component_times_cs.first().copied().flatten()
+        };
+        let verified_memo_cs = match &checked_speedsolves[..] {
+            [checked] => checked.memo.and_then(duration_to_cs),
+            _ => None,
+        };
+
+        // An average was completed when its last solve was.
+        let verified_completion = components
+            .iter()
+            .map(|component| component.verified_timestamps.completion)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|completions| completions.into_iter().max());
+
+        checks.push(PolicyCheck::new(
+            "Variant",
+            data.variant.as_ref().map_or("none", |v| v.name.as_str()),
+            allowed_str(policy.allow_variants),
+            data.variant.is_none() || policy.allow_variants,
+            "Variants require manual review",
+        ));
+        checks.push(PolicyCheck::new(
+            "Program",
+            &data.program.abbr,
+            policy.allowed_programs.join(", "),
+            policy.allows_program(&data.program.abbr),
+            format!("Program {} requires manual review", data.program.abbr),
+        ));
+        let upload_gap = data.upload_date - data.solve_date;
+        checks.push(match verified_completion {
+            Some(_) => PolicyCheck::new(
+                "Upload gap",
+                "completion was timestamped",
+                format!("{} hours", policy.max_upload_gap.num_hours()),
+                true,
+                "",
+            ),
+            None => PolicyCheck::new(
+                "Upload gap",
+                format!("{} hours", upload_gap.num_hours()),
+                format!("{} hours", policy.max_upload_gap.num_hours()),
+                upload_gap <= policy.max_upload_gap,
+                format!(
+                    "Solve was uploaded {} days after claimed completion date",
+                    upload_gap.num_days(),
+                ),
+            ),
+        });
+        let solver_notes = data.solver_notes.filter(|s| !s.is_empty());
+        checks.push(PolicyCheck::new(
+            "Solver notes",
+            yes_no(solver_notes.is_some()),
+            allowed_str(policy.allow_solver_notes),
+            solver_notes.is_none() || policy.allow_solver_notes,
+            "Solver note requires manual review",
+        ));
+        checks.push(PolicyCheck::new(
+            "One-handed",
+            yes_no(data.flags.one_handed),
+            "requires manual review",
+            !data.flags.one_handed,
+            "One-handed solve requires manual review",
+        ));
+        checks.push(PolicyCheck::new(
+            "Video",
+            data.video_url.as_deref().unwrap_or("none"),
+            "requires manual review",
+            data.video_url.is_none(),
+            "Video requires manual review",
+        ));
+        checks.push(
+            PolicyCheck::new(
+                "Verified time",
+                verified_speed_cs.map_or("none".to_string(), crate::util::render_time),
+                "required for speedsolves",
+                verified_speed_cs.is_some() || data.speed_cs.is_none(),
+                "Speedsolve autoverification failed",
+            )
+            .speed_only(),
+        );
+        checks.push(
+            PolicyCheck::new(
+                "Verified memorization time",
+                verified_memo_cs.map_or("none".to_string(), crate::util::render_time),
+                "required if a memorization time is claimed",
+                verified_memo_cs.is_some() || data.memo_cs.is_none(),
+                "Memorization time requires manual review",
+            )
+            .speed_only(),
+        );
 
-        let f = tempfile::NamedTempFile::new()?;
-        std::fs::write(&f, log_file_contents)?;
+        let fields = SolveDbFields {
+            puzzle_id: puzzle_id.0,
+            variant_id: data.variant.map(|v| v.id.0),
+            program_id: data.program.id.0,
+            solver_id: data.solver.id.0,
+            solve_date: verified_completion
+                .unwrap_or_else(|| data.solve_date.min(data.upload_date)),
+            solver_notes: solver_notes.unwrap_or_default(),
+            moderator_notes: data.moderator_notes,
+            auto_verify_output: Some(if is_average {
+                serde_json::to_value(components)?
+            } else {
+                serde_json::to_value(auto_verify_output)?
+            }),
+            average: is_average,
+            blind,
+            filters: components.iter().any(|component| component.used_filters),
+            macros: components.iter().any(|component| component.used_macros),
+            one_handed: data.flags.one_handed,
+            computer_assisted: data.flags.computer_assisted, // we trust
+            move_count: if is_average {
+                None // averages are speedsolves only
+            } else {
+                auto_verify_output.solution_stm.try_into().ok()
+            },
+            speed_cs: verified_speed_cs.or(data.speed_cs),
+            memo_cs: verified_memo_cs.or(data.memo_cs),
+            log_file: None, // don't change
+            video_url: data.video_url,
+        };
 
-        let output = timeout(
-            VERIFICATION_TIMEOUT,
-            async_process::Command::new(&*crate::env::HSC2_PATH)
-                .arg("verify")
-                .arg(f.path())
-                .output(),
+        let verify_fmc = fields.move_count.is_some()
+            && checks.iter().all(|check| check.passed || check.speed_only);
+        let verify_speed = fields.speed_cs.is_some() && checks.iter().all(|check| check.passed);
+
+        Ok(AutoVerifyOutcome::Verified(Box::new(VerifiedSolve {
+            fields,
+            component_times_cs: (is_average && component_times_cs.len() == AVERAGE_SIZE)
+                .then_some(component_times_cs),
+            verify_fmc,
+            verify_speed,
+            checks,
+            verifier_errors,
+            new_puzzle_name,
+        })))
+    }
+
+    /// Runs auto-verification on a solve without changing anything and
+    /// explains what it would do.
+    ///
+    /// Unless `rerun` is `true`, the verifier output stored with the solve is
+    /// used if there is any, so that the verifier is only run once per log
+    /// file.
+    pub async fn autoverify_report(
+        &self,
+        solve_id: SolveId,
+        rerun: bool,
+    ) -> AppResult<AutoVerifyReport> {
+        let data = self.get_solve(solve_id).await?;
+        let old_fields = SolveDbFields::from(data.clone());
+        let stored_verifications = stored_verifications(&data).filter(|_| !rerun);
+        let from_stored_output = stored_verifications.is_some();
+        let result = match stored_verifications {
+            Some(verifications) => {
+                let verifier_version = self
+                    .get_auto_verifier_version(solve_id)
+                    .await?
+                    .unwrap_or_else(|| UNKNOWN_VERIFIER_VERSION.to_string());
+                let outcome = self
+                    .apply_autoverify_policy(data, &verifications, true)
+                    .await?;
+                AutoVerifyResult {
+                    verifier_version,
+                    verifications,
+                    verifier_stderr: String::new(),
+                    outcome,
+                }
+            }
+            None => self.run_autoverify(solve_id, true).await?,
+        };
+        let audit_log_comment = result.audit_log_comment();
+
+        let mut report = AutoVerifyReport {
+            solve_id,
+            from_stored_output,
+            verifier_version: result.verifier_version,
+            verifications: result.verifications,
+            verifier_stderr: result.verifier_stderr,
+            ineligible: None,
+            new_puzzle_name: None,
+            checks: vec![],
+            verifier_errors: vec![],
+            changed_fields: BTreeMap::new(),
+            verify_fmc: false,
+            verify_speed: false,
+            audit_log_comment,
+        };
+        match result.outcome {
+            AutoVerifyOutcome::Ineligible(e) => report.ineligible = Some(e.to_string()),
+            AutoVerifyOutcome::Verified(verified) => {
+                let VerifiedSolve {
+                    fields,
+                    verify_fmc,
+                    verify_speed,
+                    checks,
+                    verifier_errors,
+                    new_puzzle_name,
+                    ..
+                } = *verified;
+                report.changed_fields = changed_fields_map!(
+                    old_fields,
+                    fields,
+                    [
+                        solver_id,
+                        puzzle_id,
+                        variant_id,
+                        program_id,
+                        solve_date,
+                        solver_notes,
+                        moderator_notes,
+                        average,
+                        blind,
+                        filters,
+                        macros,
+                        one_handed,
+                        computer_assisted,
+                        move_count,
+                        speed_cs,
+                        memo_cs,
+                        video_url,
+                    ],
+                );
+                if let Some(name) = &new_puzzle_name {
+                    report.changed_fields.insert(
+                        "puzzle_id".to_string(),
+                        [
+                            format!("{:?}", old_fields.puzzle_id),
+                            format!("new puzzle {name:?}"),
+                        ],
+                    );
+                }
+                report.new_puzzle_name = new_puzzle_name;
+                report.checks = checks;
+                report.verifier_errors = verifier_errors;
+                report.verify_fmc = verify_fmc;
+                report.verify_speed = verify_speed;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Saves the result of auto-verifying a solve. `comment` is prepended to
+    /// the audit log comment.
+    pub(crate) async fn save_autoverify_result(
+        &self,
+        editor: &User,
+        solve_id: SolveId,
+        result: AutoVerifyResult,
+        comment: &str,
+    ) -> AppResult {
+        let audit_log_comment = [comment, &result.audit_log_comment()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .join("\n");
+        let verified = match result.outcome {
+            AutoVerifyOutcome::Ineligible(e) => {
+                return self
+                    .reject_ineligible_solve(editor, solve_id, &result, e, comment)
+                    .await;
+            }
+            AutoVerifyOutcome::Verified(verified) => *verified,
+        };
+        let VerifiedSolve {
+            fields,
+            component_times_cs,
+            verify_fmc,
+            verify_speed,
+            ..
+        } = verified;
+
+        self.update_solve(solve_id, fields, editor, &audit_log_comment)
+            .await?;
+        self.set_auto_verifier_version(solve_id, &result.verifier_version)
+            .await?;
+
+        if let Some(times) = &component_times_cs {
+            self.set_average_components(solve_id, times).await?;
+        }
+
+        if verify_fmc {
+            self.verify_fmc(editor, solve_id, Some(true), "").await?;
+        }
+        if verify_speed {
+            self.verify_speed(editor, solve_id, Some(true), "").await?;
+        }
+
+        Ok(())
+    }
+
+    /// Rejects a solve of a puzzle that is not eligible for the leaderboards,
+    /// after recording the move count and time reported by the verifier.
+    ///
+    /// Returns `error` if the verifier reported no solves or the solve has
+    /// neither a move count nor a time to reject, so that it is left for
+    /// manual review.
+    async fn reject_ineligible_solve(
+        &self,
+        editor: &User,
+        solve_id: SolveId,
+        result: &AutoVerifyResult,
+        error: AppError,
+        comment: &str,
+    ) -> AppResult {
+        let Some(first_verification) = result.verifications.first() else {
+            return Err(error);
+        };
+        let data = self.get_solve(solve_id).await?;
+        let is_average = data.flags.average;
+        // Only the first solve counts unless the solve is an average.
+        let reported_move_count = if is_average {
+            None
+        } else {
+            first_verification.solution_stm.try_into().ok()
+        };
+        let reported_times_cs = result
+            .verifications
+            .iter()
+            .map(|verification| verification.durations.speedsolve.and_then(duration_to_cs))
+            .collect_vec();
+        let reported_speed_cs = if is_average {
+            average_of(&reported_times_cs)
+        } else {
+            reported_times_cs.first().copied().flatten()
+        };
+
+        let mut fields = SolveDbFields::from(data);
+        fields.move_count = fields.move_count.or(reported_move_count);
+        fields.speed_cs = fields.speed_cs.or(reported_speed_cs);
+        let (has_move_count, has_speed) = (fields.move_count.is_some(), fields.speed_cs.is_some());
+        if !has_move_count && !has_speed {
+            return Err(error);
+        }
+        fields.auto_verify_output = Some(if is_average {
+            serde_json::to_value(&result.verifications)?
+        } else {
+            serde_json::to_value(first_verification)?
+        });
+        self.update_solve(
+            solve_id,
+            fields,
+            editor,
+            "Recording the result of the verifier",
         )
-        .await
-        .map_err(|_| AppError::SolveVerificationTimeout)??;
+        .await?;
+        self.set_auto_verifier_version(solve_id, &result.verifier_version)
+            .await?;
+
+        tracing::info!("Autoverifier rejected solve {solve_id}: {error}");
+        let audit_log_comment = [comment, &error.to_string()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .join("\n");
+        let reason = Some(RejectionReason::WrongCategory);
+        if has_move_count {
+            self.verify_fmc_with_reason(editor, solve_id, Some(false), reason, &audit_log_comment)
+                .await?;
+        }
+        if has_speed {
+            self.verify_speed_with_reason(
+                editor,
+                solve_id,
+                Some(false),
+                reason,
+                &audit_log_comment,
+            )
+            .await?;
+        }
+        Ok(())
+    }
 
-        drop(f);
+    /// Runs the verifier on each of a solve's log files and returns every
+    /// solve they contain along with anything the verifier wrote to stderr.
+    async fn compute_autoverify_status(
+        &self,
+        verifier: &dyn Verifier,
+        solve: SolveId,
+    ) -> AppResult<(Vec<SolveVerification>, String)> {
+        let log_files = self.get_all_log_file_contents(solve).await?;
+        if log_files.is_empty() {
+            return Err(AppError::NoLogFile);
+        }
 
-        let facts: Vec<SolveVerification> = serde_json::from_slice(&output.stdout)?;
+        let mut verifications = vec![];
+        let mut stderr = vec![];
+        for log_file_contents in &log_files {
+            let output = verifier.verify(log_file_contents).await?;
+            verifications.extend(output.solves);
+            if !output.stderr.is_empty() {
+                stderr.push(output.stderr);
+            }
+        }
+        let stderr = stderr.join("\n");
 
-        facts.into_iter().next().ok_or_else(|| {
-            AppError::Other(format!(
-                "no verification output. stdout:\n{}",
-                String::from_utf8_lossy(&output.stderr),
-            ))
-        })
+        if verifications.is_empty() {
+            return Err(AppError::VerificationFailed(format!(
+                "no verification output. stderr:\n{stderr}",
+            )));
+        }
+        Ok((verifications, stderr))
+    }
+}
+
+/// Result of auto-verifying a solve, before it is saved.
+pub(crate) struct AutoVerifyResult {
+    pub verifier_version: String,
+    /// Every solve in the log files.
+    pub verifications: Vec<SolveVerification>,
+    pub verifier_stderr: String,
+    pub outcome: AutoVerifyOutcome,
+}
+impl AutoVerifyResult {
+    /// Returns the reasons that the solve could not be fully auto-verified,
+    /// along with anything the verifier wrote to stderr.
+    pub fn audit_log_comment(&self) -> String {
+        let mut ret = String::new();
+        if let AutoVerifyOutcome::Verified(verified) = &self.outcome {
+            let failed = |speed_only| {
+                verified
+                    .checks
+                    .iter()
+                    .filter(move |check| !check.passed && check.speed_only == speed_only)
+                    .map(|check| &check.reason)
+            };
+            let reasons = itertools::chain!(failed(false), failed(true), &verified.verifier_errors)
+                .join("\n");
+            if !reasons.is_empty() {
+                ret = format!("Unable to autoverify:\n{reasons}");
+            }
+        }
+        if !self.verifier_stderr.is_empty() {
+            if !ret.is_empty() {
+                ret += "\n";
+            }
+            ret += &format!("Verifier stderr:\n{}", self.verifier_stderr);
+        }
+        ret
+    }
+}
+
+/// What auto-verification would do to a solve.
+pub(crate) enum AutoVerifyOutcome {
+    /// The solve is of a puzzle that is not eligible for the leaderboards, so
+    /// it should be rejected.
+    Ineligible(AppError),
+    /// The solve should be updated with data from the verifier.
+    Verified(Box<VerifiedSolve>),
+}
+
+/// Solve data from the verifier, after applying the auto-verification policy.
+pub(crate) struct VerifiedSolve {
+    pub fields: SolveDbFields,
+    /// Times of the solves in an average, with `None` for each DNF.
+    pub component_times_cs: Option<Vec<Option<i32>>>,
+    /// Whether the move count can be accepted.
+    pub verify_fmc: bool,
+    /// Whether the time can be accepted.
+    pub verify_speed: bool,
+    pub checks: Vec<PolicyCheck>,
+    /// Errors reported by the verifier. These do not prevent auto-verification
+    /// on their own.
+    pub verifier_errors: Vec<String>,
+    /// Name of the puzzle that would be created for the solve, in a dry run.
+    pub new_puzzle_name: Option<String>,
+}
+
+/// Result of checking a solve against one rule of the auto-verification
+/// policy.
+#[derive(Serialize, Debug, Clone)]
+pub struct PolicyCheck {
+    pub name: String,
+    /// Value from the solve.
+    pub value: String,
+    /// Limit or setting from the policy.
+    pub threshold: String,
+    pub passed: bool,
+    /// Whether failing the check only prevents the time from being accepted.
+    /// Otherwise, it prevents anything from being accepted.
+    pub speed_only: bool,
+    /// Reason recorded in the audit log if the check fails.
+    pub reason: String,
+}
+impl PolicyCheck {
+    fn new(
+        name: impl Into<String>,
+        value: impl ToString,
+        threshold: impl ToString,
+        passed: bool,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value: value.to_string(),
+            threshold: threshold.to_string(),
+            passed,
+            speed_only: false,
+            reason: reason.into(),
+        }
+    }
+
+    fn speed_only(mut self) -> Self {
+        self.speed_only = true;
+        self
+    }
+}
+
+/// Explanation of what auto-verification would do to a solve, for
+/// moderators.
+#[derive(Serialize, Debug)]
+pub struct AutoVerifyReport {
+    pub solve_id: SolveId,
+    /// Whether the report uses the verifier output stored with the solve
+    /// instead of running the verifier again.
+    pub from_stored_output: bool,
+    pub verifier_version: String,
+    /// Every solve in the log files, as reported by the verifier.
+    pub verifications: Vec<SolveVerification>,
+    pub verifier_stderr: String,
+    /// Why the solve would be rejected, if its puzzle is not eligible for the
+    /// leaderboards.
+    pub ineligible: Option<String>,
+    /// Name of the puzzle that would be created for the solve, if it does not
+    /// exist yet.
+    pub new_puzzle_name: Option<String>,
+    pub checks: Vec<PolicyCheck>,
+    pub verifier_errors: Vec<String>,
+    /// Fields that would change, as `[old, new]`.
+    pub changed_fields: BTreeMap<String, [String; 2]>,
+    /// Whether the move count would be accepted.
+    pub verify_fmc: bool,
+    /// Whether the time would be accepted.
+    pub verify_speed: bool,
+    /// Comment that would be recorded in the audit log.
+    pub audit_log_comment: String,
+}
+
+/// Returns the verifier output stored with a solve, or `None` if there is none
+/// or it cannot be parsed.
+fn stored_verifications(solve: &FullSolve) -> Option<Vec<SolveVerification>> {
+    let output = solve.auto_verify_output.clone()?;
+    let verifications: Vec<SolveVerification> = if solve.flags.average {
+        serde_json::from_value(output).ok()?
+    } else {
+        vec![serde_json::from_value(output).ok()?]
+    };
+    (!verifications.is_empty()).then_some(verifications)
+}
+
+/// Time of a single solve from a log file, after applying the
+/// auto-verification policy.
+struct CheckedSpeedsolve {
+    /// Total time, including any excess inspection time.
+    time: Option<TimeDelta>,
+    memo: Option<TimeDelta>,
+    blind: bool,
+    checks: Vec<PolicyCheck>,
+}
+
+/// Checks the durations of a single solve. `prefix` is prepended to the name
+/// and reason of each check.
+fn check_speedsolve(
+    policy: &AutoVerifyPolicy,
+    solve_id: SolveId,
+    prefix: &str,
+    durations: &Durations,
+) -> CheckedSpeedsolve {
+    let Durations {
+        scramble_network_latency,
+        scramble_application,
+        inspection,
+        mut speedsolve,
+        memo,
+        blindsolve,
+        timestamp_network_latency,
+    } = *durations;
+
+    let mut checks = vec![];
+
+    // Add excess inspection time to speedsolve duration.
+    if let Some(inspection_dur) = inspection
+        && let Some(speedsolve_time) = &mut speedsolve
+        && let Some(excess_inspection) = inspection_dur.checked_sub(&policy.max_inspection_time)
+        && excess_inspection > TimeDelta::zero()
+    {
+        tracing::info!("Adding {excess_inspection} to speedsolve {solve_id}");
+        *speedsolve_time += excess_inspection;
+    }
+    checks.push(
+        PolicyCheck::new(
+            format!("{prefix}Inspection time"),
+            inspection.map_or("unknown".to_string(), |dur| dur.to_string()),
+            format!(
+                "{} (excess is added to the time)",
+                policy.max_inspection_time
+            ),
+            inspection.is_some(),
+            format!("{prefix}Inspection time unknown"),
+        )
+        .speed_only(),
+    );
+
+    for (name, duration, max_time) in [
+        (
+            "Scramble network latency",
+            scramble_network_latency,
+            policy.max_trusted_network_latency,
+        ),
+        (
+            "Scramble application time",
+            scramble_application,
+            policy.max_scramble_application_time,
+        ),
+        (
+            "Timestamp network latency",
+            timestamp_network_latency,
+            policy.max_trusted_network_latency,
+        ),
+    ] {
+        let (value, reason) = match duration {
+            Some(dur) => (dur.to_string(), format!("{prefix}{name} was {dur}")),
+            None => ("unknown".to_string(), format!("{prefix}{name} unknown")),
+        };
+        checks.push(
+            PolicyCheck::new(
+                format!("{prefix}{name}"),
+                value,
+                max_time,
+                duration.is_some_and(|dur| dur <= max_time),
+                reason,
+            )
+            .speed_only(),
+        );
+    }
+
+    CheckedSpeedsolve {
+        time: blindsolve.or(speedsolve),
+        memo,
+        blind: blindsolve.is_some(),
+        checks,
     }
 }
 
 fn duration_to_cs(dur: TimeDelta) -> Option<i32> {
     (dur.num_milliseconds() / 10).try_into().ok()
 }
+
+fn yes_no(b: bool) -> &'static str {
+    if b { "yes" } else { "no" }
+}
+
+fn allowed_str(allowed: bool) -> &'static str {
+    if allowed {
+        "allowed"
+    } else {
+        "requires manual review"
+    }
+}
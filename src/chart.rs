@@ -0,0 +1,221 @@
+//! Server-side rendering of SVG charts.
+
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Utc};
+use itertools::Itertools;
+
+use crate::util::escape_xml;
+
+/// Colors used for distinguishing series, such as different record holders.
+const SERIES_COLORS: &[&str] = &[
+    "#808080", "#e6c700", "#e03131", "#f08c00", "#2f9e44", "#3498db", "#e64980", "#9c36b5",
+];
+
+/// Maximum number of labels on the time axis.
+const MAX_DATE_TICKS: usize = 10;
+/// Approximate number of labels on the value axis.
+const VALUE_TICKS: f64 = 5.0;
+
+/// Point on a [`StepChart`].
+#[derive(Debug, Clone)]
+pub struct ChartPoint {
+    pub date: DateTime<Utc>,
+    pub value: i32,
+    /// Series that the point belongs to, which determines its color.
+    pub series: String,
+    /// Tooltip text.
+    pub label: String,
+    pub url: Option<String>,
+}
+
+/// Chart of a value over time in which each point holds its value until the
+/// next one, such as a record progression.
+#[derive(Debug, Clone)]
+pub struct StepChart {
+    pub width: f64,
+    pub height: f64,
+    /// Points, in any order.
+    pub points: Vec<ChartPoint>,
+    /// Time at which the last value ends.
+    pub end: DateTime<Utc>,
+    /// Function used to format values on the value axis.
+    pub format_value: fn(i32) -> String,
+    /// Whether to draw axes and labels. Sparklines omit them.
+    pub axes: bool,
+}
+
+impl StepChart {
+    pub fn to_svg(&self) -> String {
+        let (w, h) = (self.width, self.height);
+        // Class names are scoped to the chart because sparklines are inlined
+        // into pages whose stylesheets use the same names.
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" class="step-chart" viewBox="0 0 {w} {h}">"#,
+        );
+        svg += "<style>\
+            .step-chart .axis { stroke: #888; stroke-width: 1; }\
+            .step-chart .grid { stroke: #8884; stroke-width: 1; }\
+            .step-chart .line { stroke: #000; stroke-width: 2; fill: none; }\
+            .step-chart .label { fill: #444; font: 11px sans-serif; }\
+            .step-chart .point { stroke: #000; stroke-width: 1; }\
+            @media (prefers-color-scheme: dark) {\
+                .step-chart .line { stroke: #fff; }\
+                .step-chart .label { fill: #ccc; }\
+            }\
+            </style>";
+
+        let points = self.points.iter().sorted_by_key(|p| p.date).collect_vec();
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            if self.axes {
+                svg += &format!(
+                    r#"<text class="label" x="{}" y="{}" text-anchor="middle">No records</text>"#,
+                    w / 2.0,
+                    h / 2.0,
+                );
+            }
+            return svg + "</svg>";
+        };
+
+        let (left, right, top, bottom) = if self.axes {
+            (80.0, w - 15.0, 15.0, h - 30.0)
+        } else {
+            (4.0, w - 4.0, 4.0, h - 4.0)
+        };
+
+        // Time axis
+        let t0 = first.date;
+        let mut t1 = std::cmp::max(self.end, last.date);
+        if t1 <= t0 {
+            t1 = t0 + TimeDelta::days(1);
+        }
+        let span = (t1 - t0).num_seconds() as f64;
+        let x = |t: DateTime<Utc>| left + (right - left) * (t - t0).num_seconds() as f64 / span;
+
+        // Value axis
+        let (min, max) = points
+            .iter()
+            .map(|p| p.value)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let padding = (f64::from(max - min) * 0.1).max(1.0);
+        let lo = (f64::from(min) - padding).max(0.0);
+        let hi = f64::from(max) + padding;
+        let y = |v: f64| bottom - (bottom - top) * (v - lo) / (hi - lo);
+
+        if self.axes {
+            let step = nice_step((hi - lo) / VALUE_TICKS);
+            let mut v = (lo / step).ceil() * step;
+            while v <= hi {
+                svg += &format!(
+                    r#"<line class="grid" x1="{left}" x2="{right}" y1="{y}" y2="{y}"/>"#,
+                    y = y(v),
+                );
+                svg += &format!(
+                    r#"<text class="label" x="{}" y="{}" text-anchor="end">{}</text>"#,
+                    left - 6.0,
+                    y(v) + 4.0,
+                    escape_xml(&(self.format_value)(v as i32)),
+                );
+                v += step;
+            }
+
+            for (t, text) in date_ticks(t0, t1) {
+                svg += &format!(
+                    r#"<line class="grid" x1="{x}" x2="{x}" y1="{top}" y2="{bottom}"/>"#,
+                    x = x(t),
+                );
+                svg += &format!(
+                    r#"<text class="label" x="{}" y="{}" text-anchor="middle">{text}</text>"#,
+                    x(t),
+                    bottom + 18.0,
+                );
+            }
+
+            svg += &format!(
+                r#"<polyline class="axis" fill="none" points="{left},{top} {left},{bottom} {right},{bottom}"/>"#,
+            );
+        }
+
+        // Line
+        let mut path = format!("M {} {}", x(first.date), y(f64::from(first.value)));
+        for p in &points[1..] {
+            path += &format!(" H {} V {}", x(p.date), y(f64::from(p.value)));
+        }
+        path += &format!(" H {}", x(t1));
+        svg += &format!(r#"<path class="line" d="{path}"/>"#);
+
+        // Points
+        let series = points.iter().map(|p| &p.series).unique().collect_vec();
+        let radius = if self.axes { 5.0 } else { 2.5 };
+        for p in &points {
+            let color_index = series.iter().position(|s| *s == &p.series).unwrap_or(0);
+            let color = SERIES_COLORS[color_index % SERIES_COLORS.len()];
+            let circle = format!(
+                r#"<circle class="point" cx="{}" cy="{}" r="{radius}" fill="{color}"><title>{}</title></circle>"#,
+                x(p.date),
+                y(f64::from(p.value)),
+                escape_xml(&p.label),
+            );
+            match &p.url {
+                Some(url) => {
+                    svg += &format!(
+                        r#"<a href="{}" target="_top">{circle}</a>"#,
+                        escape_xml(url),
+                    );
+                }
+                None => svg += &circle,
+            }
+        }
+
+        svg + "</svg>"
+    }
+}
+
+/// Returns a step size of the form 1, 2, or 5 times a power of 10 that is at
+/// least `rough_step`.
+fn nice_step(rough_step: f64) -> f64 {
+    let rough_step = rough_step.max(1.0);
+    let magnitude = 10_f64.powf(rough_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= rough_step)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Returns labeled ticks for the time axis between `t0` and `t1`, at the start
+/// of each year if the range is long enough or else at the start of each
+/// month.
+fn date_ticks(t0: DateTime<Utc>, t1: DateTime<Utc>) -> Vec<(DateTime<Utc>, String)> {
+    let to_time = |date: NaiveDate| date.and_time(chrono::NaiveTime::MIN).and_utc();
+
+    let years = (t0.year() + 1..=t1.year())
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+        .map(|date| (to_time(date), date.format("%Y").to_string()))
+        .collect_vec();
+    let ticks = if years.len() >= 2 {
+        years
+    } else {
+        std::iter::successors(t0.date_naive().with_day(1), |date| {
+            date.checked_add_months(chrono::Months::new(1))
+        })
+        .skip(1)
+        .map(|date| (to_time(date), date.format("%Y-%m").to_string()))
+        .take_while(|(t, _)| *t <= t1)
+        .collect_vec()
+    };
+
+    let stride = ticks.len().div_ceil(MAX_DATE_TICKS).max(1);
+    ticks.into_iter().step_by(stride).collect()
+}
+
+/// Response containing an SVG image.
+pub struct SvgResponse(pub String);
+
+impl IntoResponse for SvgResponse {
+    fn into_response(self) -> Response {
+        ([(header::CONTENT_TYPE, "image/svg+xml")], self.0).into_response()
+    }
+}
@@ -1,7 +1,11 @@
+use std::path::PathBuf;
+
 use tokio::sync::mpsc;
 
 use crate::AppState;
-use crate::db::UserId;
+use crate::db::{PuzzleId, UserId};
+use crate::reverify::ReverifyFilter;
+use crate::verifier::VerifierRunner;
 
 /// Hyperspeedcube leaderboards server.
 #[derive(clap::Parser, Debug)]
@@ -32,6 +36,35 @@ pub(crate) enum CliCommand {
         #[command(subcommand)]
         puzzle_command: CliPuzzleCommand,
     },
+    /// Imports data from a file
+    Import {
+        #[command(subcommand)]
+        import_command: CliImportCommand,
+    },
+    /// Exports all verified solves
+    Export {
+        #[arg(long, value_enum, default_value_t)]
+        format: CliExportFormat,
+        /// File to write to (default stdout)
+        output: Option<PathBuf>,
+    },
+    /// Re-verifies auto-verified solves with the current verifier
+    Reverify {
+        /// Only re-verify solves last verified by this verifier version
+        /// ("unknown" for solves verified before versions were recorded)
+        #[arg(long)]
+        from_version: Option<String>,
+        /// Only re-verify solves of this puzzle
+        #[arg(long)]
+        puzzle: Option<i32>,
+    },
+}
+
+#[derive(clap::ValueEnum, Debug, Default, Copy, Clone)]
+pub(crate) enum CliExportFormat {
+    #[default]
+    Csv,
+    Json,
 }
 
 impl CliCommand {
@@ -75,6 +108,53 @@ impl CliCommand {
             }
             CliCommand::User { user_command } => user_command.execute(&state).await,
             CliCommand::Puzzle { puzzle_command } => puzzle_command.execute(&state).await,
+            CliCommand::Import { import_command } => import_command.execute(&state).await,
+            CliCommand::Export { format, output } => {
+                let solves = crate::export::exported_solves(&state).await?;
+                let contents = match format {
+                    CliExportFormat::Csv => crate::export::solves_to_csv(&solves),
+                    CliExportFormat::Json => serde_json::to_string_pretty(&solves)?,
+                };
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, contents)?;
+                        eprintln!("Exported {} solves to {}", solves.len(), path.display());
+                    }
+                    None => print!("{contents}"),
+                }
+                Ok(())
+            }
+            CliCommand::Reverify {
+                from_version,
+                puzzle,
+            } => {
+                let filter = ReverifyFilter {
+                    verifier_version: from_version,
+                    puzzle_id: puzzle.map(PuzzleId),
+                };
+                let report = state.reverify_solves(&filter).await?;
+                println!("{}", report.to_text());
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub(crate) enum CliImportCommand {
+    /// Imports solves from a CSV or JSON file
+    Solves {
+        file: PathBuf,
+        /// Print what would be imported without changing the database
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+impl CliImportCommand {
+    pub async fn execute(self, state: &AppState) -> eyre::Result<()> {
+        match self {
+            CliImportCommand::Solves { file, dry_run } => state.import_solves(&file, dry_run).await,
         }
     }
 }
@@ -176,12 +256,7 @@ impl CliPuzzleCommand {
                     let old_name = puzzle_data.name.clone();
                     if let Some(hsc_id) = puzzle_data.hsc_id.clone() {
                         // IIFE to mimic try_block
-                        let hsc_command_output =
-                            async_process::Command::new(&*crate::env::HSC2_PATH)
-                                .arg("puzzle")
-                                .arg(&hsc_id)
-                                .output()
-                                .await;
+                        let hsc_command_output = VerifierRunner::hsc2().puzzle_info(&hsc_id).await;
                         let new_name = (|| {
                             Some(
                                 serde_json::from_slice::<
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, query_as};
+
+use crate::db::{AuditLogEvent, EventClass, SolveId, User};
+use crate::traits::Linkable;
+use crate::util::md_escape;
+use crate::webhooks::WebhookEvent;
+use crate::{AppError, AppResult, AppState};
+
+/// Maximum length of the appeal message quoted in the Discord alert, in
+/// characters.
+const MAX_DISCORD_APPEAL_MESSAGE_LEN: usize = 200;
+
+/// Open appeal by a solver against the rejection of their solve.
+#[derive(Serialize, Debug, Clone)]
+pub struct SolveAppeal {
+    pub id: i32,
+    pub solve_id: SolveId,
+    pub message: String,
+    pub filed_at: DateTime<Utc>,
+}
+
+struct SolveAppealRow {
+    id: i32,
+    solve_id: i32,
+    message: String,
+    filed_at: DateTime<Utc>,
+}
+impl From<SolveAppealRow> for SolveAppeal {
+    fn from(row: SolveAppealRow) -> Self {
+        Self {
+            id: row.id,
+            solve_id: SolveId(row.solve_id),
+            message: row.message,
+            filed_at: row.filed_at,
+        }
+    }
+}
+
+impl AppState {
+    /// Files an appeal by the solver against the rejection of their solve.
+    ///
+    /// Every rejected verdict of the solve is reset so that the solve goes
+    /// back to the pending queue.
+    pub async fn file_appeal(&self, editor: &User, solve_id: SolveId, message: &str) -> AppResult {
+        self.check_allow_user_actions()?;
+
+        let solve = self.get_solve(solve_id).await?;
+        if solve.solver.id != editor.id {
+            return Err(AppError::NotAuthorized);
+        }
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(AppError::CannotAppeal("message is empty".to_string()));
+        }
+        if solve.speed_verified != Some(false) && solve.fmc_verified != Some(false) {
+            return Err(AppError::CannotAppeal("solve was not rejected".to_string()));
+        }
+        if self.get_open_appeal(solve_id).await?.is_some() {
+            return Err(AppError::CannotAppeal(
+                "solve already has an open appeal".to_string(),
+            ));
+        }
+
+        let mut transaction = self.pool.begin().await?;
+
+        query!(
+            "INSERT INTO SolveAppeal (solve_id, message) VALUES ($1, $2)",
+            solve_id.0,
+            message,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        query!(
+            "UPDATE Solve
+                SET
+                    speed_verified = NULLIF(speed_verified, FALSE),
+                    speed_verified_by = CASE WHEN speed_verified = FALSE
+                        THEN NULL ELSE speed_verified_by END,
+                    fmc_verified = NULLIF(fmc_verified, FALSE),
+                    fmc_verified_by = CASE WHEN fmc_verified = FALSE
+                        THEN NULL ELSE fmc_verified_by END
+                WHERE id = $1",
+            solve_id.0,
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        let event = AuditLogEvent::Appealed {
+            message: message.to_string(),
+        };
+        Self::add_solve_log_entry(&mut transaction, editor, solve_id, event).await?;
+
+        transaction.commit().await?;
+
+        tracing::info!(editor_id = ?editor.id, ?solve_id, "Solve appealed.");
+
+        if let Ok(reset_solve) = self.get_solve(solve_id).await {
+            let reset_classes = [
+                (EventClass::Speed, solve.speed_verified),
+                (EventClass::Fmc, solve.fmc_verified),
+            ];
+            for (event_class, verified) in reset_classes {
+                if verified == Some(false) {
+                    self.send_webhooks(
+                        WebhookEvent::Unverified,
+                        &reset_solve,
+                        Some(event_class),
+                        None,
+                    )
+                    .await;
+                }
+            }
+        }
+
+        let mut quoted_message: String = message
+            .chars()
+            .take(MAX_DISCORD_APPEAL_MESSAGE_LEN)
+            .collect();
+        if quoted_message.len() < message.len() {
+            quoted_message.push('…');
+        }
+        self.send_private_discord_update(format!(
+            ":scales: {} appealed by {}: {}",
+            solve.markdown_with_puzzle_and_solver_name(),
+            editor.to_public().md_link(false),
+            md_escape(&quoted_message),
+        ))
+        .await;
+
+        Ok(())
+    }
+
+    /// Returns the open appeal on a solve, if there is one.
+    pub async fn get_open_appeal(&self, solve_id: SolveId) -> sqlx::Result<Option<SolveAppeal>> {
+        Ok(query_as!(
+            SolveAppealRow,
+            "SELECT id, solve_id, message, filed_at FROM SolveAppeal
+                WHERE solve_id = $1 AND resolved_at IS NULL",
+            solve_id.0,
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(SolveAppeal::from))
+    }
+
+    /// Returns every open appeal, oldest first.
+    pub async fn get_open_appeals(&self) -> sqlx::Result<Vec<SolveAppeal>> {
+        Ok(query_as!(
+            SolveAppealRow,
+            "SELECT id, solve_id, message, filed_at FROM SolveAppeal
+                WHERE resolved_at IS NULL
+                ORDER BY filed_at",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(SolveAppeal::from)
+        .collect())
+    }
+
+    /// Resolves the open appeal on a solve if it no longer needs review.
+    pub async fn resolve_appeal_if_reviewed(&self, solve_id: SolveId) -> sqlx::Result<()> {
+        query!(
+            "UPDATE SolveAppeal SET resolved_at = NOW()
+                WHERE solve_id = $1 AND resolved_at IS NULL
+                    AND NOT EXISTS (SELECT 1 FROM PendingSolve WHERE id = $1)",
+            solve_id.0,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::BTreeMap, fmt};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use crate::db::UserId;
+use crate::db::{RejectionReason, UserId};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UpdatedObject {
@@ -53,14 +53,30 @@ pub enum AuditLogEvent {
         old: Option<bool>,
         new: Option<bool>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<RejectionReason>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         comment: Option<String>,
     },
     FmcVerified {
         old: Option<bool>,
         new: Option<bool>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<RejectionReason>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+    },
+    /// First of the two moderator approvals needed to accept a solve that
+    /// would displace a world record.
+    RecordApproved {
+        event_class: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         comment: Option<String>,
     },
+    /// Solver appealed the rejection of a solve, which sent it back for
+    /// review.
+    Appealed {
+        message: String,
+    },
     /// Error deserializing
     Unknown {
         json: serde_json::Value,
@@ -122,23 +138,34 @@ impl AuditLogEvent {
             AuditLogEvent::SpeedVerified {
                 old: _,
                 new,
+                reason,
                 comment: _,
             } => match new {
-                Some(true) => "Accepted speed solve",
-                Some(false) => "Rejected speed solve",
-                None => "Unverified speed solve",
-            }
-            .to_string(),
+                Some(true) => "Accepted speed solve".to_string(),
+                Some(false) => match reason {
+                    Some(reason) => format!("Rejected speed solve ({})", reason.description()),
+                    None => "Rejected speed solve".to_string(),
+                },
+                None => "Unverified speed solve".to_string(),
+            },
             AuditLogEvent::FmcVerified {
                 old: _,
                 new,
+                reason,
                 comment: _,
             } => match new {
-                Some(true) => "Accepted FMC solve",
-                Some(false) => "Rejected FMC solve",
-                None => "Unverified FMC solve",
-            }
-            .to_string(),
+                Some(true) => "Accepted FMC solve".to_string(),
+                Some(false) => match reason {
+                    Some(reason) => format!("Rejected FMC solve ({})", reason.description()),
+                    None => "Rejected FMC solve".to_string(),
+                },
+                None => "Unverified FMC solve".to_string(),
+            },
+            AuditLogEvent::RecordApproved {
+                event_class,
+                comment: _,
+            } => format!("Approved {event_class} record, pending a second moderator"),
+            AuditLogEvent::Appealed { message } => format!("Appealed rejection: {message}"),
             AuditLogEvent::Unknown { .. } => return None,
         };
 
@@ -192,16 +219,35 @@ impl AuditLogEvent {
             AuditLogEvent::SpeedVerified {
                 old,
                 new,
+                reason,
                 comment: _,
             } => {
-                format!("Changed speed_verified from {old:?} to {new:?}")
+                let mut ret = format!("Changed speed_verified from {old:?} to {new:?}");
+                if let Some(reason) = reason {
+                    ret += &format!("\nReason: {}", reason.description());
+                }
+                ret
             }
             AuditLogEvent::FmcVerified {
                 old,
                 new,
+                reason,
                 comment: _,
             } => {
-                format!("Changed fmc_verified from {old:?} to {new:?}")
+                let mut ret = format!("Changed fmc_verified from {old:?} to {new:?}");
+                if let Some(reason) = reason {
+                    ret += &format!("\nReason: {}", reason.description());
+                }
+                ret
+            }
+            AuditLogEvent::RecordApproved {
+                event_class,
+                comment: _,
+            } => {
+                format!("Approved {event_class} record, pending a second moderator")
+            }
+            AuditLogEvent::Appealed { message } => {
+                format!("Appealed rejection and reopened for review\nMessage: {message}")
             }
             AuditLogEvent::Unknown { json } => {
                 format!("unknown: {json:?}")
@@ -225,10 +271,12 @@ impl AuditLogEvent {
             | AuditLogEvent::Migrated { .. }
             | AuditLogEvent::Added { .. }
             | AuditLogEvent::Submitted { .. }
+            | AuditLogEvent::Appealed { .. }
             | AuditLogEvent::Unknown { .. } => &None,
             AuditLogEvent::Updated { comment, .. }
             | AuditLogEvent::SpeedVerified { comment, .. }
-            | AuditLogEvent::FmcVerified { comment, .. } => comment,
+            | AuditLogEvent::FmcVerified { comment, .. }
+            | AuditLogEvent::RecordApproved { comment, .. } => comment,
         }
     }
 }
@@ -0,0 +1,434 @@
+use chrono::TimeDelta;
+use serde::Serialize;
+use sqlx::{Postgres, query, query_as, query_scalar};
+
+use crate::db::{AuditLogEvent, PuzzleId, UpdatedObject, User};
+use crate::{AppError, AppResult, AppState};
+
+/// Auto-verification policy as stored in the database. In a puzzle override,
+/// fields that are `None` inherit from the default policy.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct AutoVerifyPolicyData {
+    pub max_trusted_network_latency_ms: Option<i32>,
+    pub max_inspection_time_ms: Option<i32>,
+    pub max_scramble_application_time_ms: Option<i32>,
+    pub max_upload_gap_hours: Option<i32>,
+    /// Abbreviations of programs whose solves may be auto-verified.
+    pub allowed_programs: Option<Vec<String>>,
+    pub allow_variants: Option<bool>,
+    pub allow_solver_notes: Option<bool>,
+}
+impl AutoVerifyPolicyData {
+    fn validate(&self) -> AppResult {
+        for (name, value) in [
+            (
+                "max_trusted_network_latency_ms",
+                self.max_trusted_network_latency_ms,
+            ),
+            ("max_inspection_time_ms", self.max_inspection_time_ms),
+            (
+                "max_scramble_application_time_ms",
+                self.max_scramble_application_time_ms,
+            ),
+            ("max_upload_gap_hours", self.max_upload_gap_hours),
+        ] {
+            if value.is_some_and(|n| n < 0) {
+                return Err(AppError::InvalidQuery(format!(
+                    "{name} must not be negative"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether every field is set, as required for the default
+    /// policy.
+    fn is_complete(&self) -> bool {
+        self.max_trusted_network_latency_ms.is_some()
+            && self.max_inspection_time_ms.is_some()
+            && self.max_scramble_application_time_ms.is_some()
+            && self.max_upload_gap_hours.is_some()
+            && self.allowed_programs.is_some()
+            && self.allow_variants.is_some()
+            && self.allow_solver_notes.is_some()
+    }
+
+    /// Returns the policy with every field that is `None` taken from
+    /// `fallback` instead.
+    fn or(self, fallback: Self) -> Self {
+        Self {
+            max_trusted_network_latency_ms: self
+                .max_trusted_network_latency_ms
+                .or(fallback.max_trusted_network_latency_ms),
+            max_inspection_time_ms: self
+                .max_inspection_time_ms
+                .or(fallback.max_inspection_time_ms),
+            max_scramble_application_time_ms: self
+                .max_scramble_application_time_ms
+                .or(fallback.max_scramble_application_time_ms),
+            max_upload_gap_hours: self.max_upload_gap_hours.or(fallback.max_upload_gap_hours),
+            allowed_programs: self.allowed_programs.or(fallback.allowed_programs),
+            allow_variants: self.allow_variants.or(fallback.allow_variants),
+            allow_solver_notes: self.allow_solver_notes.or(fallback.allow_solver_notes),
+        }
+    }
+}
+
+/// Default policy or puzzle override, for display.
+#[derive(Serialize, Debug, Clone)]
+pub struct AutoVerifyPolicyEntry {
+    /// Puzzle that this policy overrides, or `None` for the default policy.
+    pub puzzle_id: Option<PuzzleId>,
+    pub puzzle_name: Option<String>,
+    #[serde(flatten)]
+    pub data: AutoVerifyPolicyData,
+}
+
+/// Effective auto-verification policy for a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoVerifyPolicy {
+    /// Maximum network latency to allow before requiring manual review of a
+    /// speedsolve submission.
+    pub max_trusted_network_latency: TimeDelta,
+    /// Maximum inspection time to allow before adding the excess to the
+    /// speedsolve time.
+    pub max_inspection_time: TimeDelta,
+    /// Maximum time allowed for scrambling a puzzle before requiring manual
+    /// review of a speedsolve submission.
+    pub max_scramble_application_time: TimeDelta,
+    /// Maximum time to allow between a solve's completion and its upload
+    /// before requiring manual review of a speedsolve or FMC submission,
+    /// unless the solve completion was timestamped.
+    pub max_upload_gap: TimeDelta,
+    /// Abbreviations of programs whose solves may be auto-verified.
+    pub allowed_programs: Vec<String>,
+    /// Whether solves with a variant may be auto-verified.
+    pub allow_variants: bool,
+    /// Whether solves with solver notes may be auto-verified.
+    pub allow_solver_notes: bool,
+}
+impl Default for AutoVerifyPolicy {
+    /// Returns the policy used for any field missing from the database.
+    fn default() -> Self {
+        Self {
+            max_trusted_network_latency: TimeDelta::seconds(5),
+            max_inspection_time: TimeDelta::seconds(60),
+            max_scramble_application_time: TimeDelta::seconds(5),
+            max_upload_gap: TimeDelta::hours(48), // 2 days
+            allowed_programs: vec!["HSC2".to_string()],
+            allow_variants: false,
+            allow_solver_notes: false,
+        }
+    }
+}
+impl From<AutoVerifyPolicy> for AutoVerifyPolicyData {
+    fn from(policy: AutoVerifyPolicy) -> Self {
+        Self {
+            max_trusted_network_latency_ms: i32::try_from(
+                policy.max_trusted_network_latency.num_milliseconds(),
+            )
+            .ok(),
+            max_inspection_time_ms: i32::try_from(policy.max_inspection_time.num_milliseconds())
+                .ok(),
+            max_scramble_application_time_ms: i32::try_from(
+                policy.max_scramble_application_time.num_milliseconds(),
+            )
+            .ok(),
+            max_upload_gap_hours: i32::try_from(policy.max_upload_gap.num_hours()).ok(),
+            allowed_programs: Some(policy.allowed_programs),
+            allow_variants: Some(policy.allow_variants),
+            allow_solver_notes: Some(policy.allow_solver_notes),
+        }
+    }
+}
+impl From<AutoVerifyPolicyData> for AutoVerifyPolicy {
+    fn from(data: AutoVerifyPolicyData) -> Self {
+        let default = Self::default();
+        Self {
+            max_trusted_network_latency: data
+                .max_trusted_network_latency_ms
+                .map_or(default.max_trusted_network_latency, |ms| {
+                    TimeDelta::milliseconds(ms.into())
+                }),
+            max_inspection_time: data
+                .max_inspection_time_ms
+                .map_or(default.max_inspection_time, |ms| {
+                    TimeDelta::milliseconds(ms.into())
+                }),
+            max_scramble_application_time: data
+                .max_scramble_application_time_ms
+                .map_or(default.max_scramble_application_time, |ms| {
+                    TimeDelta::milliseconds(ms.into())
+                }),
+            max_upload_gap: data
+                .max_upload_gap_hours
+                .map_or(default.max_upload_gap, |h| TimeDelta::hours(h.into())),
+            allowed_programs: data.allowed_programs.unwrap_or(default.allowed_programs),
+            allow_variants: data.allow_variants.unwrap_or(default.allow_variants),
+            allow_solver_notes: data
+                .allow_solver_notes
+                .unwrap_or(default.allow_solver_notes),
+        }
+    }
+}
+impl AutoVerifyPolicy {
+    /// Returns whether solves from the program with abbreviation `abbr` may be
+    /// auto-verified.
+    pub fn allows_program(&self, abbr: &str) -> bool {
+        self.allowed_programs
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(abbr))
+    }
+}
+
+impl AppState {
+    /// Returns the default auto-verification policy followed by every puzzle
+    /// override, sorted by puzzle name. Fields missing from the default policy
+    /// are filled in from [`AutoVerifyPolicy::default()`].
+    pub async fn get_all_autoverify_policies(&self) -> sqlx::Result<Vec<AutoVerifyPolicyEntry>> {
+        Ok(query!(
+            "SELECT AutoVerifyPolicy.*, Puzzle.name AS \"puzzle_name?\"
+                FROM AutoVerifyPolicy
+                LEFT JOIN Puzzle ON Puzzle.id = AutoVerifyPolicy.puzzle_id
+                ORDER BY AutoVerifyPolicy.puzzle_id IS NOT NULL, Puzzle.name",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| {
+            let data = AutoVerifyPolicyData {
+                max_trusted_network_latency_ms: row.max_trusted_network_latency_ms,
+                max_inspection_time_ms: row.max_inspection_time_ms,
+                max_scramble_application_time_ms: row.max_scramble_application_time_ms,
+                max_upload_gap_hours: row.max_upload_gap_hours,
+                allowed_programs: row.allowed_programs,
+                allow_variants: row.allow_variants,
+                allow_solver_notes: row.allow_solver_notes,
+            };
+            AutoVerifyPolicyEntry {
+                puzzle_id: row.puzzle_id.map(PuzzleId),
+                puzzle_name: row.puzzle_name,
+                data: match row.puzzle_id {
+                    Some(_) => data,
+                    None => data.or(AutoVerifyPolicy::default().into()),
+                },
+            }
+        })
+        .collect())
+    }
+
+    /// Returns the stored policy for a puzzle, or the default policy if
+    /// `puzzle_id` is `None`.
+    async fn get_autoverify_policy_data(
+        puzzle_id: Option<PuzzleId>,
+        executor: impl sqlx::Executor<'_, Database = Postgres>,
+    ) -> sqlx::Result<Option<AutoVerifyPolicyData>> {
+        query_as!(
+            AutoVerifyPolicyData,
+            "SELECT max_trusted_network_latency_ms, max_inspection_time_ms,
+                    max_scramble_application_time_ms, max_upload_gap_hours,
+                    allowed_programs, allow_variants, allow_solver_notes
+                FROM AutoVerifyPolicy
+                WHERE puzzle_id IS NOT DISTINCT FROM $1",
+            puzzle_id.map(|id| id.0),
+        )
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Returns the auto-verification policy for a puzzle, with its override
+    /// applied on top of the default policy.
+    pub async fn get_autoverify_policy(
+        &self,
+        puzzle_id: PuzzleId,
+    ) -> sqlx::Result<AutoVerifyPolicy> {
+        let puzzle_override = Self::get_autoverify_policy_data(Some(puzzle_id), &self.pool)
+            .await?
+            .unwrap_or_default();
+        let default = Self::get_autoverify_policy_data(None, &self.pool)
+            .await?
+            .unwrap_or_default();
+        Ok(puzzle_override.or(default).into())
+    }
+
+    /// Returns the default auto-verification policy, which applies to puzzles
+    /// without an override.
+    pub async fn get_default_autoverify_policy(&self) -> sqlx::Result<AutoVerifyPolicy> {
+        Ok(Self::get_autoverify_policy_data(None, &self.pool)
+            .await?
+            .unwrap_or_default()
+            .into())
+    }
+
+    /// Updates the default auto-verification policy, or the override for a
+    /// puzzle. An override with no fields set is deleted.
+    pub async fn update_autoverify_policy(
+        &self,
+        editor: &User,
+        puzzle_id: Option<PuzzleId>,
+        new_data: AutoVerifyPolicyData,
+        audit_log_comment: &str,
+    ) -> AppResult {
+        if !editor.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        new_data.validate()?;
+        if puzzle_id.is_none() && !new_data.is_complete() {
+            return Err(AppError::InvalidQuery(
+                "The default auto-verification policy must set every field".to_string(),
+            ));
+        }
+
+        let AutoVerifyPolicyData {
+            max_trusted_network_latency_ms,
+            max_inspection_time_ms,
+            max_scramble_application_time_ms,
+            max_upload_gap_hours,
+            allowed_programs,
+            allow_variants,
+            allow_solver_notes,
+        } = new_data.clone();
+
+        let mut transaction = self.pool.begin().await?;
+
+        let puzzle_name = match puzzle_id {
+            Some(id) => Some(
+                query_scalar!("SELECT name FROM Puzzle WHERE id = $1", id.0)
+                    .fetch_optional(&mut *transaction)
+                    .await?
+                    .ok_or_else(|| AppError::InvalidQuery(format!("No puzzle with ID {id}")))?,
+            ),
+            None => None,
+        };
+
+        let old_data = Self::get_autoverify_policy_data(puzzle_id, &mut *transaction)
+            .await?
+            .unwrap_or_default();
+
+        let policy_id = if puzzle_id.is_some() && new_data == AutoVerifyPolicyData::default() {
+            query_scalar!(
+                "DELETE FROM AutoVerifyPolicy WHERE puzzle_id = $1 RETURNING id",
+                puzzle_id.map(|id| id.0),
+            )
+            .fetch_optional(&mut *transaction)
+            .await?
+        } else {
+            let updated_id = query_scalar!(
+                "UPDATE AutoVerifyPolicy
+                    SET max_trusted_network_latency_ms = $2, max_inspection_time_ms = $3,
+                        max_scramble_application_time_ms = $4, max_upload_gap_hours = $5,
+                        allowed_programs = $6, allow_variants = $7, allow_solver_notes = $8
+                    WHERE puzzle_id IS NOT DISTINCT FROM $1
+                    RETURNING id",
+                puzzle_id.map(|id| id.0),
+                max_trusted_network_latency_ms,
+                max_inspection_time_ms,
+                max_scramble_application_time_ms,
+                max_upload_gap_hours,
+                allowed_programs.as_deref(),
+                allow_variants,
+                allow_solver_notes,
+            )
+            .fetch_optional(&mut *transaction)
+            .await?;
+            match updated_id {
+                Some(id) => Some(id),
+                None => Some(
+                    query_scalar!(
+                        "INSERT INTO AutoVerifyPolicy
+                            (puzzle_id, max_trusted_network_latency_ms, max_inspection_time_ms,
+                                max_scramble_application_time_ms, max_upload_gap_hours,
+                                allowed_programs, allow_variants, allow_solver_notes)
+                            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                            RETURNING id",
+                        puzzle_id.map(|id| id.0),
+                        max_trusted_network_latency_ms,
+                        max_inspection_time_ms,
+                        max_scramble_application_time_ms,
+                        max_upload_gap_hours,
+                        allowed_programs.as_deref(),
+                        allow_variants,
+                        allow_solver_notes,
+                    )
+                    .fetch_one(&mut *transaction)
+                    .await?,
+                ),
+            }
+        };
+
+        let fields = changed_fields_map!(
+            old_data,
+            new_data,
+            [
+                max_trusted_network_latency_ms,
+                max_inspection_time_ms,
+                max_scramble_application_time_ms,
+                max_upload_gap_hours,
+                allowed_programs,
+                allow_variants,
+                allow_solver_notes,
+            ],
+        );
+        let Some(policy_id) = policy_id.filter(|_| !fields.is_empty()) else {
+            // Nothing changed
+            return Ok(());
+        };
+        let policy_name = match &puzzle_name {
+            Some(name) => format!("{name} override"),
+            None => "default".to_string(),
+        };
+        let event = AuditLogEvent::Updated {
+            object: Some(UpdatedObject {
+                ty: "AutoVerifyPolicy".to_string(),
+                id: policy_id,
+                name: Some(policy_name),
+            }),
+            fields,
+            comment: Some(audit_log_comment.trim().to_string()).filter(|s| !s.is_empty()),
+        };
+        Self::add_general_log_entry(&mut transaction, editor, event).await?;
+
+        transaction.commit().await?;
+
+        tracing::info!(editor_id = ?editor.id.0, ?puzzle_id, ?new_data, "Updated autoverify policy");
+        let editor_name = editor.to_public().display_name();
+        let domain_name = &*crate::env::DOMAIN_NAME;
+        let policy_description = match &puzzle_name {
+            Some(name) => format!("the auto-verification policy for **{name}**"),
+            None => "the default auto-verification policy".to_string(),
+        };
+        let msg = format!(
+            "**{editor_name}** updated {policy_description}. \
+             See [all policies](<{domain_name}/autoverify-policy>) \
+             or [audit log](<{domain_name}/audit-log/general>)."
+        );
+        self.send_private_discord_update(msg).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_data_round_trip() {
+        let data = AutoVerifyPolicyData::from(AutoVerifyPolicy::default());
+        assert!(data.is_complete());
+        assert_eq!(data.max_inspection_time_ms, Some(60_000));
+        assert_eq!(AutoVerifyPolicy::from(data), AutoVerifyPolicy::default());
+    }
+
+    #[test]
+    fn test_override_inherits_default() {
+        let puzzle_override = AutoVerifyPolicyData {
+            max_upload_gap_hours: Some(1),
+            ..Default::default()
+        };
+        let policy = AutoVerifyPolicy::from(puzzle_override.or(AutoVerifyPolicy::default().into()));
+        assert_eq!(policy.max_upload_gap, TimeDelta::hours(1));
+        assert_eq!(policy.allowed_programs, ["HSC2"]);
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, query_as};
+
+use crate::AppState;
+use crate::db::SolveId;
+
+/// Solve waiting to be auto-verified.
+#[derive(Serialize, Debug, Clone)]
+pub struct AutoVerifyQueueEntry {
+    pub solve_id: SolveId,
+    pub enqueued_at: DateTime<Utc>,
+    /// Number of times a worker has started verifying the solve.
+    pub attempts: i32,
+    /// Error from the most recent failed attempt.
+    pub last_error: Option<String>,
+    /// Worker currently verifying the solve.
+    pub leased_by: Option<String>,
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    /// Time before which a failed solve will not be retried.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// Whether auto-verification has given up on the solve.
+    pub needs_manual_review: bool,
+}
+impl AutoVerifyQueueEntry {
+    /// Returns whether a worker is currently verifying the solve.
+    pub fn is_leased(&self) -> bool {
+        self.leased_by.is_some() && self.lease_expires_at.is_some_and(|t| t > Utc::now())
+    }
+    /// Returns whether the solve is waiting to be retried after a failure.
+    pub fn is_waiting_to_retry(&self) -> bool {
+        !self.is_leased() && self.next_attempt_at.is_some_and(|t| t > Utc::now())
+    }
+}
+
+impl AppState {
+    /// Adds a solve to the auto-verification queue and returns whether it was
+    /// added. If the solve is already in the queue, its attempts are reset and
+    /// it is taken out of manual review.
+    pub async fn add_to_autoverify_queue(&self, solve_id: SolveId) -> sqlx::Result<bool> {
+        let is_new = query!(
+            "INSERT INTO AutoVerifyQueue (solve_id) VALUES ($1)
+                ON CONFLICT (solve_id) DO UPDATE
+                    SET attempts = 0, last_error = NULL,
+                        next_attempt_at = NULL, needs_manual_review = FALSE
+                RETURNING (xmax = 0) AS is_new",
+            solve_id.0,
+        )
+        .fetch_one(&self.pool)
+        .await?
+        .is_new
+        .unwrap_or(false);
+        Ok(is_new)
+    }
+
+    /// Returns the auto-verification queue, with solves that are currently
+    /// being verified first.
+    pub async fn get_autoverify_queue(&self) -> sqlx::Result<Vec<AutoVerifyQueueEntry>> {
+        query_as!(
+            AutoVerifyQueueEntry,
+            "SELECT * FROM AutoVerifyQueue
+                ORDER BY (leased_by IS NOT NULL AND lease_expires_at > NOW()) DESC,
+                    enqueued_at, solve_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Leases the oldest solve in the auto-verification queue that is ready to
+    /// be verified and is not leased by another worker.
+    pub async fn lease_next_autoverify(
+        &self,
+        worker: &str,
+        lease_expires_at: DateTime<Utc>,
+    ) -> sqlx::Result<Option<AutoVerifyQueueEntry>> {
+        query_as!(
+            AutoVerifyQueueEntry,
+            "UPDATE AutoVerifyQueue
+                SET leased_by = $1, lease_expires_at = $2, attempts = attempts + 1
+                WHERE solve_id = (
+                    SELECT solve_id FROM AutoVerifyQueue
+                        WHERE (leased_by IS NULL OR lease_expires_at < NOW())
+                            AND (next_attempt_at IS NULL OR next_attempt_at <= NOW())
+                            AND NOT needs_manual_review
+                        ORDER BY enqueued_at, solve_id
+                        LIMIT 1
+                        FOR UPDATE SKIP LOCKED
+                )
+                RETURNING *",
+            worker,
+            lease_expires_at,
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Removes a solve from the auto-verification queue after it has been
+    /// verified.
+    pub async fn remove_from_autoverify_queue(&self, solve_id: SolveId) -> sqlx::Result<()> {
+        query!(
+            "DELETE FROM AutoVerifyQueue WHERE solve_id = $1",
+            solve_id.0,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt to verify a solve and releases its lease so
+    /// that it is retried at `next_attempt_at`.
+    pub async fn retry_autoverify_later(
+        &self,
+        solve_id: SolveId,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> sqlx::Result<()> {
+        query!(
+            "UPDATE AutoVerifyQueue
+                SET last_error = $2, next_attempt_at = $3,
+                    leased_by = NULL, lease_expires_at = NULL
+                WHERE solve_id = $1",
+            solve_id.0,
+            error,
+            next_attempt_at,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a failed attempt to verify a solve and stops retrying it.
+    pub async fn give_up_autoverify(&self, solve_id: SolveId, error: &str) -> sqlx::Result<()> {
+        query!(
+            "UPDATE AutoVerifyQueue
+                SET last_error = $2, needs_manual_review = TRUE, next_attempt_at = NULL,
+                    leased_by = NULL, lease_expires_at = NULL
+                WHERE solve_id = $1",
+            solve_id.0,
+            error,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Adds a solve to the auto-verification queue as needing manual review,
+    /// without verifying it.
+    pub async fn hold_autoverify_for_review(
+        &self,
+        solve_id: SolveId,
+        reason: &str,
+    ) -> sqlx::Result<()> {
+        query!(
+            "INSERT INTO AutoVerifyQueue (solve_id, last_error, needs_manual_review)
+                VALUES ($1, $2, TRUE)
+                ON CONFLICT (solve_id) DO UPDATE
+                    SET last_error = $2, needs_manual_review = TRUE, next_attempt_at = NULL",
+            solve_id.0,
+            reason,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Releases every expired lease in the auto-verification queue.
+    ///
+    /// Leases that have not expired are left alone, since another instance
+    /// sharing the database may still be verifying those solves.
+    pub async fn release_expired_autoverify_leases(&self) -> sqlx::Result<u64> {
+        Ok(query!(
+            "UPDATE AutoVerifyQueue
+                SET leased_by = NULL, lease_expires_at = NULL
+                WHERE leased_by IS NOT NULL AND lease_expires_at < NOW()",
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected())
+    }
+}
@@ -0,0 +1,237 @@
+use itertools::Itertools;
+use serde::Serialize;
+use sqlx::{Postgres, Transaction, query, query_scalar};
+
+use crate::db::SolveId;
+use crate::{AppError, AppResult, AppState};
+
+/// Number of solves in an average.
+pub const AVERAGE_SIZE: usize = 5;
+
+/// Individual time in an average.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AverageComponent {
+    /// Time, or `None` if the solve was not completed (DNF).
+    pub speed_cs: Option<i32>,
+    /// Whether this time is excluded from the average for being the best or
+    /// worst.
+    pub dropped: bool,
+}
+
+/// Returns the average of `times_cs` with the best and worst times dropped,
+/// rounded to the nearest centisecond.
+///
+/// `None` in `times_cs` is a solve that was not completed (DNF), which counts
+/// as the worst time. Returns `None` if there are not [`AVERAGE_SIZE`] times or
+/// if more than one is a DNF.
+pub fn average_of(times_cs: &[Option<i32>]) -> Option<i32> {
+    if times_cs.len() != AVERAGE_SIZE {
+        return None;
+    }
+    let mut sorted = times_cs
+        .iter()
+        .map(|&t| t.map(i64::from).unwrap_or(i64::MAX))
+        .collect_vec();
+    sorted.sort_unstable();
+    let counting = &sorted[1..AVERAGE_SIZE - 1];
+    if counting.contains(&i64::MAX) {
+        return None;
+    }
+    let count = counting.len() as i64;
+    let sum: i64 = counting.iter().sum();
+    ((sum + count / 2) / count).try_into().ok()
+}
+
+/// Returns the components of an average in order, marking which ones are
+/// dropped. A DNF is always the worst time.
+pub fn average_components(times_cs: &[Option<i32>]) -> Vec<AverageComponent> {
+    let best = times_cs
+        .iter()
+        .position_min_by_key(|t| t.unwrap_or(i32::MAX));
+    let worst = times_cs
+        .iter()
+        .position_max_by_key(|t| t.unwrap_or(i32::MAX));
+    times_cs
+        .iter()
+        .enumerate()
+        .map(|(i, &speed_cs)| AverageComponent {
+            speed_cs,
+            dropped: Some(i) == best || (Some(i) == worst && best != worst),
+        })
+        .collect()
+}
+
+impl AppState {
+    /// Adds log files to a solve after the one stored on the solve itself.
+    pub async fn add_extra_log_files(
+        transaction: &mut Transaction<'_, Postgres>,
+        solve_id: SolveId,
+        log_files: Vec<(String, Vec<u8>)>,
+    ) -> sqlx::Result<()> {
+        for (file_name, contents) in log_files {
+            query!(
+                "INSERT INTO SolveLogFile (solve_id, file_name, contents) VALUES ($1, $2, $3)",
+                solve_id.0,
+                file_name,
+                contents,
+            )
+            .execute(&mut **transaction)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the names of the log files stored after the first, along with
+    /// their IDs.
+    pub async fn get_extra_log_file_names(
+        &self,
+        solve_id: SolveId,
+    ) -> sqlx::Result<Vec<(i32, String)>> {
+        Ok(query!(
+            "SELECT id, file_name FROM SolveLogFile WHERE solve_id = $1 ORDER BY id",
+            solve_id.0,
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.id, row.file_name))
+        .collect())
+    }
+
+    /// Returns the contents of a log file stored after the first.
+    pub async fn get_extra_log_file_contents(
+        &self,
+        solve_id: SolveId,
+        file_id: i32,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        query_scalar!(
+            "SELECT contents FROM SolveLogFile WHERE solve_id = $1 AND id = $2",
+            solve_id.0,
+            file_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Returns the contents of every log file for a solve, starting with the
+    /// one stored on the solve itself.
+    pub async fn get_all_log_file_contents(&self, solve_id: SolveId) -> sqlx::Result<Vec<Vec<u8>>> {
+        let mut ret = vec![];
+        if let Some(contents) = self.get_log_file_contents(solve_id, &self.pool).await? {
+            ret.push(contents);
+        }
+        ret.extend(
+            query_scalar!(
+                "SELECT contents FROM SolveLogFile WHERE solve_id = $1 ORDER BY id",
+                solve_id.0,
+            )
+            .fetch_all(&self.pool)
+            .await?,
+        );
+        Ok(ret)
+    }
+
+    /// Returns the times in an average, in the order they were done, with
+    /// `None` for each DNF.
+    pub async fn get_average_components(
+        &self,
+        solve_id: SolveId,
+    ) -> sqlx::Result<Vec<Option<i32>>> {
+        query_scalar!(
+            "SELECT speed_cs FROM AverageComponent WHERE solve_id = $1 ORDER BY position",
+            solve_id.0,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Replaces the times in an average, with `None` for each DNF. Returns an
+    /// error if there are not [`AVERAGE_SIZE`] times.
+    pub async fn set_average_components(
+        &self,
+        solve_id: SolveId,
+        times_cs: &[Option<i32>],
+    ) -> AppResult {
+        if times_cs.len() != AVERAGE_SIZE {
+            return Err(AppError::InvalidQuery(format!(
+                "average has {} solves instead of {AVERAGE_SIZE}",
+                times_cs.len(),
+            )));
+        }
+
+        let mut transaction = self.pool.begin().await?;
+        query!(
+            "DELETE FROM AverageComponent WHERE solve_id = $1",
+            solve_id.0,
+        )
+        .execute(&mut *transaction)
+        .await?;
+        for (position, &speed_cs) in (0..).zip(times_cs) {
+            query!(
+                "INSERT INTO AverageComponent (solve_id, position, speed_cs) VALUES ($1, $2, $3)",
+                solve_id.0,
+                position,
+                speed_cs,
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dropped(components: &[AverageComponent]) -> Vec<bool> {
+        components.iter().map(|c| c.dropped).collect()
+    }
+
+    #[test]
+    fn test_average_drops_best_and_worst() {
+        let times = [Some(1200), Some(900), Some(1500), Some(1000), Some(1100)];
+        assert_eq!(average_of(&times), Some(1100));
+        assert_eq!(
+            dropped(&average_components(&times)),
+            [false, true, true, false, false],
+        );
+
+        // (1001 + 1001 + 1002) / 3 = 1001.33
+        let times = [Some(1001), Some(1000), Some(1002), Some(1001), Some(1003)];
+        assert_eq!(average_of(&times), Some(1001));
+        // (1001 + 1002 + 1002) / 3 = 1001.67
+        let times = [Some(1001), Some(1000), Some(1002), Some(1002), Some(1003)];
+        assert_eq!(average_of(&times), Some(1002));
+    }
+
+    #[test]
+    fn test_average_with_ties() {
+        let times = [Some(1000); AVERAGE_SIZE];
+        assert_eq!(average_of(&times), Some(1000));
+        assert_eq!(
+            dropped(&average_components(&times)),
+            [true, false, false, false, true],
+        );
+    }
+
+    #[test]
+    fn test_average_with_dnf() {
+        let times = [Some(1200), None, Some(900), Some(1000), Some(1100)];
+        assert_eq!(average_of(&times), Some(1100));
+        let components = average_components(&times);
+        assert_eq!(dropped(&components), [false, true, true, false, false]);
+        assert_eq!(components[1].speed_cs, None);
+
+        let times = [Some(1200), None, Some(900), None, Some(1100)];
+        assert_eq!(average_of(&times), None);
+    }
+
+    #[test]
+    fn test_average_requires_five_solves() {
+        assert_eq!(average_of(&[Some(1000); 4]), None);
+        assert_eq!(average_of(&[Some(1000); 6]), None);
+        assert_eq!(average_of(&[]), None);
+    }
+}
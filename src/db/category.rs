@@ -1,6 +1,8 @@
 use crate::db::EventClass;
 
-use super::{FullSolve, ProgramQuery, PuzzleId, SolveFlags, Variant, VariantId, VariantQuery};
+use super::{
+    Event, FullSolve, ProgramQuery, PuzzleId, SolveFlags, Variant, VariantId, VariantQuery,
+};
 
 #[derive(serde::Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CategoryQuery {
@@ -84,6 +86,68 @@ impl CategoryQuery {
         ret
     }
 
+    /// Returns the specific category in which `solve` is ranked when it appears
+    /// in the results of this query.
+    pub fn category_of_solve(&self, solve: &FullSolve) -> Category {
+        match self {
+            CategoryQuery::Speed {
+                average,
+                blind,
+                filters,
+                macros,
+                one_handed,
+                variant: _,
+                program: _,
+            } => {
+                let default_filters = match &solve.variant {
+                    Some(v) => v.primary_filters,
+                    None => solve.puzzle.primary_filters,
+                };
+                let default_macros = match &solve.variant {
+                    Some(v) => v.primary_macros,
+                    None => solve.puzzle.primary_macros,
+                };
+                Category::Speed {
+                    average: *average,
+                    blind: *blind,
+                    filters: filters.unwrap_or(default_filters),
+                    macros: macros.unwrap_or(default_macros),
+                    one_handed: *one_handed,
+                    variant: solve.variant.clone(),
+                    material: solve.program.material,
+                }
+            }
+
+            CategoryQuery::Fmc { computer_assisted } => Category::Fmc {
+                computer_assisted: *computer_assisted,
+            },
+        }
+    }
+
+    /// Returns the event in which `solve` is ranked when it appears in the
+    /// results of this query.
+    pub fn event_of_solve(&self, solve: &FullSolve) -> Event {
+        Event {
+            puzzle: solve.puzzle.clone(),
+            category: self.category_of_solve(solve),
+        }
+    }
+
+    /// Returns the main page category in which `solve` is counted when it
+    /// appears in the results of this query.
+    pub fn main_page_category_of_solve(&self, solve: &FullSolve) -> MainPageCategory {
+        match self {
+            CategoryQuery::Speed { .. } => MainPageCategory::Speed {
+                puzzle: solve.puzzle.id,
+                variant: solve.variant.as_ref().map(|v| v.id),
+                material: solve.program.material,
+            },
+            CategoryQuery::Fmc { .. } => MainPageCategory::Fmc {
+                puzzle: solve.puzzle.id,
+            },
+        }
+    }
+
     pub(super) fn sql_order_fields(&self) -> &'static str {
         match self {
             CategoryQuery::Speed { .. } => FullSolve::SPEED_ORDER,
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+use sqlx::{query, query_as};
+
+use crate::db::{PublicUser, SolveId, User, UserId};
+use crate::traits::Linkable;
+use crate::{AppError, AppResult, AppState};
+
+/// Moderator reviewing a pending solve.
+#[derive(Serialize, Debug, Clone)]
+pub struct SolveClaim {
+    pub solve_id: SolveId,
+    pub moderator: PublicUser,
+    pub claimed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct SolveClaimRow {
+    solve_id: i32,
+    moderator_id: i32,
+    moderator_name: Option<String>,
+    claimed_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+impl From<SolveClaimRow> for SolveClaim {
+    fn from(row: SolveClaimRow) -> Self {
+        Self {
+            solve_id: SolveId(row.solve_id),
+            moderator: PublicUser {
+                id: UserId(row.moderator_id),
+                name: row.moderator_name,
+            },
+            claimed_at: row.claimed_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+impl AppState {
+    /// Claims a pending solve for review by a moderator, or extends their
+    /// existing claim. Returns an error if the solve is not pending review or
+    /// another moderator holds the claim.
+    pub async fn claim_solve(&self, editor: &User, solve_id: SolveId) -> AppResult<SolveClaim> {
+        if !editor.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        self.check_allow_moderator_actions()?;
+
+        let solve = self
+            .get_opt_solve(solve_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        if !solve.pending_review() {
+            return Err(AppError::SolveNotPending);
+        }
+
+        let expires_at = Utc::now() + TimeDelta::minutes(*crate::env::SOLVE_CLAIM_TIMEOUT_MINUTES);
+        let claimed = query!(
+            "INSERT INTO SolveClaim (solve_id, moderator_id, expires_at) VALUES ($1, $2, $3)
+                ON CONFLICT (solve_id) DO UPDATE
+                    SET moderator_id = $2, claimed_at = NOW(), expires_at = $3
+                    WHERE SolveClaim.moderator_id = $2 OR SolveClaim.expires_at < NOW()
+                RETURNING solve_id",
+            solve_id.0,
+            editor.id.0,
+            expires_at,
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .is_some();
+
+        let claim = self
+            .get_solve_claim(solve_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        if !claimed {
+            return Err(AppError::SolveClaimedByOtherModerator(
+                claim.moderator.display_name(),
+            ));
+        }
+
+        tracing::info!(editor_id = ?editor.id, ?solve_id, "Solve claimed.");
+        self.send_private_discord_update(format!(
+            ":raised_hand: {} claimed by {}",
+            solve_id.md_link(false),
+            editor.to_public().md_link(false),
+        ))
+        .await;
+
+        Ok(claim)
+    }
+
+    /// Releases a moderator's claim on a solve. Returns an error if another
+    /// moderator holds the claim.
+    pub async fn release_solve_claim(&self, editor: &User, solve_id: SolveId) -> AppResult {
+        if !editor.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        self.check_allow_moderator_actions()?;
+        self.check_solve_claim(editor, solve_id).await?;
+
+        if self.remove_solve_claim(editor, solve_id).await? {
+            tracing::info!(editor_id = ?editor.id, ?solve_id, "Solve claim released.");
+            self.send_private_discord_update(format!(
+                ":wave: {} released by {}",
+                solve_id.md_link(false),
+                editor.to_public().md_link(false),
+            ))
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Removes a moderator's claim on a solve without announcing it, and
+    /// returns whether they held one.
+    pub async fn remove_solve_claim(&self, editor: &User, solve_id: SolveId) -> sqlx::Result<bool> {
+        Ok(query!(
+            "DELETE FROM SolveClaim WHERE solve_id = $1 AND moderator_id = $2",
+            solve_id.0,
+            editor.id.0,
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+
+    /// Returns the unexpired claim on a solve, if there is one.
+    pub async fn get_solve_claim(&self, solve_id: SolveId) -> sqlx::Result<Option<SolveClaim>> {
+        Ok(query_as!(
+            SolveClaimRow,
+            "SELECT
+                    SolveClaim.solve_id,
+                    SolveClaim.moderator_id,
+                    UserAccount.name AS moderator_name,
+                    SolveClaim.claimed_at,
+                    SolveClaim.expires_at
+                FROM SolveClaim
+                JOIN UserAccount ON UserAccount.id = SolveClaim.moderator_id
+                WHERE SolveClaim.solve_id = $1 AND SolveClaim.expires_at > NOW()",
+            solve_id.0,
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(SolveClaim::from))
+    }
+
+    /// Returns every unexpired claim on a pending solve.
+    pub async fn get_pending_solve_claims(&self) -> sqlx::Result<HashMap<SolveId, SolveClaim>> {
+        Ok(query_as!(
+            SolveClaimRow,
+            "SELECT
+                    SolveClaim.solve_id,
+                    SolveClaim.moderator_id,
+                    UserAccount.name AS moderator_name,
+                    SolveClaim.claimed_at,
+                    SolveClaim.expires_at
+                FROM SolveClaim
+                JOIN UserAccount ON UserAccount.id = SolveClaim.moderator_id
+                WHERE SolveClaim.expires_at > NOW()
+                    AND SolveClaim.solve_id IN (SELECT id FROM PendingSolve)",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (SolveId(row.solve_id), SolveClaim::from(row)))
+        .collect())
+    }
+
+    /// Returns an error if a moderator other than `editor` holds an unexpired
+    /// claim on a solve. Dummy users, such as the autoverifier, ignore claims.
+    pub async fn check_solve_claim(&self, editor: &User, solve_id: SolveId) -> AppResult {
+        if editor.dummy {
+            return Ok(());
+        }
+        match self.get_solve_claim(solve_id).await? {
+            Some(claim) if claim.moderator.id != editor.id => Err(
+                AppError::SolveClaimedByOtherModerator(claim.moderator.display_name()),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes the claim on a solve if it no longer needs review.
+    pub async fn release_solve_claim_if_reviewed(&self, solve_id: SolveId) -> sqlx::Result<()> {
+        query!(
+            "DELETE FROM SolveClaim
+                WHERE solve_id = $1
+                    AND NOT EXISTS (SELECT 1 FROM PendingSolve WHERE id = $1)",
+            solve_id.0,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
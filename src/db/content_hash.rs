@@ -0,0 +1,248 @@
+use itertools::Itertools;
+use serde::Serialize;
+use sqlx::{query, query_as};
+
+use crate::AppState;
+use crate::db::{PublicUser, SolveId, UserId};
+use crate::traits::Linkable;
+
+/// Log file or scramble seed of a solve that was already seen on another
+/// solve.
+#[derive(Serialize, Debug, Clone)]
+pub struct DuplicateContent {
+    pub solve_id: SolveId,
+    /// `log_file` or `scramble_seed`.
+    pub kind: String,
+    pub other_solve_id: SolveId,
+    pub other_solver: PublicUser,
+    /// Whether both solves are by the same solver.
+    pub same_solver: bool,
+}
+impl DuplicateContent {
+    /// Returns a human-readable name for what is duplicated.
+    pub fn what(&self) -> &'static str {
+        match self.kind.as_str() {
+            "scramble_seed" => "scramble",
+            _ => "log file",
+        }
+    }
+
+    /// Returns a Markdown description of the duplicate, without the solve.
+    pub fn md_description(&self) -> String {
+        let what = self.what();
+        let whose = if self.same_solver {
+            "the same solver".to_string()
+        } else {
+            self.other_solver.md_link(false)
+        };
+        format!(
+            "same {what} as {} by {whose}",
+            self.other_solve_id.md_link(false),
+        )
+    }
+}
+
+/// Maximum length of a scramble seed that fits in `Solve.scramble_seed`.
+const MAX_SCRAMBLE_SEED_LEN: usize = 64;
+
+/// Returns the seed of each scramble in a Hyperspeedcube log file, in order.
+///
+/// Each solve in a log file has a `scramble` node with the seed as a `seed`
+/// property, such as `scramble "full" seed="0123abcd"`. Log files from other
+/// programs have no seeds.
+pub fn scramble_seeds(log_file_contents: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(log_file_contents)
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim_start().strip_prefix("scramble")?;
+            if !rest.starts_with(char::is_whitespace) {
+                return None;
+            }
+            let (_, seed) = rest.split_once("seed=\"")?;
+            let (seed, _) = seed.split_once('"')?;
+            Some(seed.to_string()).filter(|seed| !seed.is_empty())
+        })
+        .collect()
+}
+
+struct DuplicateContentRow {
+    solve_id: i32,
+    kind: String,
+    other_solve_id: i32,
+    other_solver_id: i32,
+    other_solver_name: Option<String>,
+    same_solver: bool,
+}
+impl From<DuplicateContentRow> for DuplicateContent {
+    fn from(row: DuplicateContentRow) -> Self {
+        Self {
+            solve_id: SolveId(row.solve_id),
+            kind: row.kind,
+            other_solve_id: SolveId(row.other_solve_id),
+            other_solver: PublicUser {
+                id: UserId(row.other_solver_id),
+                name: row.other_solver_name,
+            },
+            same_solver: row.same_solver,
+        }
+    }
+}
+
+impl AppState {
+    /// Records the hash of each log file and scramble seed of a solve, and
+    /// returns any of them that were newly recorded and already belong to
+    /// another solve.
+    ///
+    /// The seed of the first scramble in the log files is also stored as the
+    /// solve's `scramble_seed`.
+    ///
+    /// Hashes are never removed, so a log file that is later replaced is
+    /// still matched against future submissions.
+    pub async fn record_content_hashes(
+        &self,
+        solve_id: SolveId,
+    ) -> sqlx::Result<Vec<DuplicateContent>> {
+        let seeds = self
+            .get_all_log_file_contents(solve_id)
+            .await?
+            .iter()
+            .flat_map(|contents| scramble_seeds(contents))
+            .unique()
+            .collect_vec();
+        if let Some(seed) = seeds
+            .first()
+            .filter(|seed| seed.len() <= MAX_SCRAMBLE_SEED_LEN)
+        {
+            query!(
+                "UPDATE Solve SET scramble_seed = $2 WHERE id = $1",
+                solve_id.0,
+                seed,
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+
+        // Rows inserted by `New` are not visible to the rest of the query, so
+        // `Other` only contains hashes recorded before this call.
+        let rows = query_as!(
+            DuplicateContentRow,
+            r#"WITH New AS (
+                INSERT INTO ContentHash (kind, hash, solve_id)
+                    SELECT 'log_file', sha256(log_file_contents), id FROM Solve
+                        WHERE id = $1 AND octet_length(log_file_contents) > 0
+                    UNION
+                    SELECT 'log_file', sha256(contents), solve_id FROM SolveLogFile
+                        WHERE solve_id = $1 AND octet_length(contents) > 0
+                    UNION
+                    SELECT 'scramble_seed', sha256(convert_to(scramble_seed, 'UTF8')), id FROM Solve
+                        WHERE id = $1 AND scramble_seed <> ''
+                    UNION
+                    SELECT 'scramble_seed', sha256(convert_to(seed, 'UTF8')), $1 FROM UNNEST($2::TEXT[]) AS seed
+                    ON CONFLICT DO NOTHING
+                    RETURNING kind, hash, solve_id
+            )
+            SELECT DISTINCT
+                    New.solve_id AS "solve_id!",
+                    New.kind AS "kind!",
+                    Other.solve_id AS "other_solve_id!",
+                    OtherSolve.solver_id AS "other_solver_id!",
+                    UserAccount.name AS other_solver_name,
+                    OtherSolve.solver_id = Solve.solver_id AS "same_solver!"
+                FROM New
+                JOIN ContentHash AS Other
+                    ON Other.kind = New.kind AND Other.hash = New.hash
+                    AND Other.solve_id <> New.solve_id
+                JOIN Solve ON Solve.id = New.solve_id
+                JOIN Solve AS OtherSolve ON OtherSolve.id = Other.solve_id
+                JOIN UserAccount ON UserAccount.id = OtherSolve.solver_id
+                ORDER BY New.kind, Other.solve_id"#,
+            solve_id.0,
+            &seeds,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(DuplicateContent::from).collect())
+    }
+
+    /// Returns every log file or scramble seed of a pending solve that also
+    /// belongs to another solve.
+    pub async fn get_pending_duplicate_content(&self) -> sqlx::Result<Vec<DuplicateContent>> {
+        let rows = query_as!(
+            DuplicateContentRow,
+            r#"SELECT DISTINCT
+                    ContentHash.solve_id,
+                    ContentHash.kind,
+                    Other.solve_id AS other_solve_id,
+                    OtherSolve.solver_id AS other_solver_id,
+                    UserAccount.name AS other_solver_name,
+                    OtherSolve.solver_id = Solve.solver_id AS "same_solver!"
+                FROM ContentHash
+                JOIN ContentHash AS Other
+                    ON Other.kind = ContentHash.kind AND Other.hash = ContentHash.hash
+                    AND Other.solve_id <> ContentHash.solve_id
+                JOIN Solve ON Solve.id = ContentHash.solve_id
+                JOIN Solve AS OtherSolve ON OtherSolve.id = Other.solve_id
+                JOIN UserAccount ON UserAccount.id = OtherSolve.solver_id
+                WHERE ContentHash.solve_id IN (SELECT id FROM PendingSolve)
+                ORDER BY ContentHash.solve_id, ContentHash.kind, Other.solve_id"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(DuplicateContent::from).collect())
+    }
+
+    /// Records the content hashes of a solve and alerts the private Discord
+    /// channel if its log file or scramble has been seen before.
+    ///
+    /// Errors are logged rather than returned so that they never prevent a
+    /// submission.
+    pub async fn check_for_duplicate_content(&self, solve_id: SolveId) {
+        let duplicates = match self.record_content_hashes(solve_id).await {
+            Ok(duplicates) => duplicates,
+            Err(err) => {
+                tracing::warn!(%err, ?solve_id, "Failed to record content hashes");
+                return;
+            }
+        };
+        if duplicates.is_empty() {
+            return;
+        }
+
+        tracing::warn!(?solve_id, ?duplicates, "Duplicate content submitted");
+        self.send_private_discord_update(format!(
+            ":warning: {} has the {}",
+            solve_id.md_link(false),
+            duplicates
+                .iter()
+                .map(|duplicate| duplicate.md_description())
+                .join(", and the "),
+        ))
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scramble_seeds() {
+        let log_file = br#"
+            solve {
+                puzzle id="ft_hypercube:3" version="1.0.0"
+                scramble "full" time="2025-01-01T00:00:00Z" seed="0123abcd" {
+                    twists "R U"
+                }
+            }
+            solve {
+                scramble_note seed="not a scramble"
+                scramble "full" seed="4567ef01"
+            }
+            solve {
+                scramble "full"
+            }
+        "#;
+        assert_eq!(scramble_seeds(log_file), ["0123abcd", "4567ef01"]);
+        assert!(scramble_seeds(b"[]").is_empty());
+    }
+}
@@ -1,27 +1,49 @@
+mod appeal;
 mod audit_log;
 mod audit_log_event;
+mod autoverify_policy;
+mod autoverify_queue;
+mod average;
 mod category;
+mod claim;
+mod content_hash;
 mod event;
 mod profile;
 mod program;
 mod puzzle;
+mod record_approval;
+mod rejection_reason;
+mod reverify;
 mod score;
+mod search;
 mod setup;
 mod solve;
 pub mod token;
 mod user;
 mod variant;
+mod webhook;
 
+pub use appeal::SolveAppeal;
 pub use audit_log::RenderedAuditLogEntry;
 pub use audit_log_event::{AuditLogEvent, UpdatedObject};
+pub use autoverify_policy::{AutoVerifyPolicy, AutoVerifyPolicyData, AutoVerifyPolicyEntry};
+pub use autoverify_queue::AutoVerifyQueueEntry;
+pub use average::{AVERAGE_SIZE, AverageComponent, average_components, average_of};
 pub use category::{Category, CategoryQuery, MainPageCategory};
+pub use claim::SolveClaim;
+pub use content_hash::DuplicateContent;
 pub use event::{Event, EventClass};
 pub use program::{Program, ProgramData, ProgramId, ProgramQuery};
 pub use puzzle::{Puzzle, PuzzleData, PuzzleId};
+pub use record_approval::{RecordApproval, RecordSignOff};
+pub use rejection_reason::RejectionReason;
+pub use reverify::UNKNOWN_VERIFIER_VERSION;
 pub use score::ScoreQuery;
-pub use solve::{FullSolve, RankedFullSolve, SolveDbFields, SolveFlags, SolveId};
+pub use search::{SearchFacet, SolveSearchQuery, SolveSearchResults, VerificationState};
+pub use solve::{FullSolve, RankedFullSolve, SolveDbFields, SolveFlags, SolveId, VerifyOutcome};
 pub use user::{OptionalDiscordId, PublicUser, User, UserData, UserId};
 pub use variant::{CombinedVariant, Variant, VariantData, VariantId, VariantQuery};
+pub use webhook::{Webhook, WebhookData, WebhookDelivery, WebhookId};
 
 /// Authorization for editing an entry in the datbase.
 #[derive(Debug, Clone, PartialEq, Eq)]
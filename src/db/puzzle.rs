@@ -3,6 +3,7 @@ use sqlx::{query, query_as};
 
 use crate::db::{AuditLogEvent, User};
 use crate::traits::Linkable;
+use crate::verifier::VerifierRunner;
 use crate::{AppError, AppResult, AppState};
 
 id_struct!(PuzzleId, Puzzle);
@@ -175,6 +176,15 @@ impl AppState {
         Ok(PuzzleId(puzzle_id))
     }
 
+    /// Returns all entries in the legacy `HscPuzzle` table, which maps HSC
+    /// puzzle IDs to leaderboard puzzles.
+    pub async fn get_legacy_hsc_puzzle_ids(&self) -> sqlx::Result<Vec<(String, PuzzleId)>> {
+        query!("SELECT hsc_id, puzzle_id FROM HscPuzzle")
+            .map(|row| (row.hsc_id, PuzzleId(row.puzzle_id)))
+            .fetch_all(&self.pool)
+            .await
+    }
+
     pub async fn get_puzzle_with_hsc_id(&self, hsc_puzzle_id: &str) -> AppResult<Option<PuzzleId>> {
         Ok(self
             .hsc_puzzle_metadata(hsc_puzzle_id, &mut self.pool.begin().await?)
@@ -209,6 +219,18 @@ impl AppState {
         }
     }
 
+    /// Same as [`Self::get_or_create_puzzle_with_hsc_id()`], but returns the
+    /// `PuzzleData` that would be added instead of creating a new puzzle.
+    pub async fn find_puzzle_with_hsc_id(
+        &self,
+        hsc_puzzle_id: &str,
+    ) -> AppResult<Result<PuzzleId, PuzzleData>> {
+        // The transaction is rolled back when dropped.
+        let mut transaction = self.pool.begin().await?;
+        self.hsc_puzzle_metadata(hsc_puzzle_id, &mut transaction)
+            .await
+    }
+
     /// If the puzzle already exists, returns its ID wrapped in `Ok()`.
     ///
     /// Otherwise, returns `PuzzleData` to add.
@@ -222,10 +244,8 @@ impl AppState {
         // Get puzzle metadata
         let puzzle_metadatas: Vec<hyperspeedcube_cli_types::puzzle_info::PuzzleListMetadata> =
             serde_json::from_slice(
-                &async_process::Command::new(&*crate::env::HSC2_PATH)
-                    .arg("puzzle")
-                    .arg(hsc_puzzle_id)
-                    .output()
+                &VerifierRunner::hsc2()
+                    .puzzle_info(hsc_puzzle_id)
                     .await?
                     .stdout,
             )?;
@@ -0,0 +1,178 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, query_as};
+
+use crate::db::{AuditLogEvent, EventClass, FullSolve, PublicUser, SolveId, User, UserId};
+use crate::traits::Linkable;
+use crate::{AppError, AppResult, AppState};
+
+/// First moderator approval of a solve that would displace a world record.
+#[derive(Serialize, Debug, Clone)]
+pub struct RecordApproval {
+    pub solve_id: SolveId,
+    /// [`EventClass::long_name()`] of the record.
+    pub event_class: String,
+    pub moderator: PublicUser,
+    pub approved_at: DateTime<Utc>,
+}
+
+struct RecordApprovalRow {
+    solve_id: i32,
+    event_class: String,
+    moderator_id: i32,
+    moderator_name: Option<String>,
+    approved_at: DateTime<Utc>,
+}
+impl From<RecordApprovalRow> for RecordApproval {
+    fn from(row: RecordApprovalRow) -> Self {
+        Self {
+            solve_id: SolveId(row.solve_id),
+            event_class: row.event_class,
+            moderator: PublicUser {
+                id: UserId(row.moderator_id),
+                name: row.moderator_name,
+            },
+            approved_at: row.approved_at,
+        }
+    }
+}
+
+/// Result of a moderator accepting a solve that might displace a world
+/// record.
+#[derive(Debug, Clone)]
+pub enum RecordSignOff {
+    /// The solve can be accepted now.
+    NotRequired,
+    /// The acceptance was recorded as the first approval, and the solve must
+    /// not be accepted until a second moderator confirms it.
+    AwaitingSecondModerator,
+    /// A second moderator confirmed the first moderator's approval, so the
+    /// solve can be accepted now.
+    Confirmed(PublicUser),
+}
+
+impl AppState {
+    /// Handles a moderator accepting a solve in an event class.
+    ///
+    /// Solves that would displace a world record must be accepted by two
+    /// different moderators. The first acceptance is recorded as an
+    /// approval, and the second confirms it. Dummy users, such as the
+    /// autoverifier, can give the first approval but never confirm one, so a
+    /// record accepted by the autoverifier still waits for a moderator.
+    pub async fn sign_off_record(
+        &self,
+        editor: &User,
+        solve: &FullSolve,
+        event_class: EventClass,
+        audit_log_comment: &str,
+    ) -> AppResult<RecordSignOff> {
+        let record = match event_class {
+            EventClass::Speed => self.speed_record_event(solve).await?,
+            EventClass::Fmc => self.fmc_record_event(solve).await?,
+        };
+        let Some((event, Some(displaced_wr))) = record else {
+            // An earlier approval may be stale if the record has changed.
+            self.clear_record_approval(solve.id, event_class).await?;
+            return Ok(RecordSignOff::NotRequired);
+        };
+
+        // Another moderator may approve the solve between reading and
+        // inserting the approval, in which case this acceptance confirms it.
+        loop {
+            match self.get_record_approval(solve.id, event_class).await? {
+                // The solve is already waiting for a moderator.
+                Some(_) if editor.dummy => return Ok(RecordSignOff::AwaitingSecondModerator),
+                Some(approval) if approval.moderator.id == editor.id => {
+                    return Err(AppError::RecordNeedsSecondModerator);
+                }
+                Some(approval) => {
+                    self.clear_record_approval(solve.id, event_class).await?;
+                    return Ok(RecordSignOff::Confirmed(approval.moderator));
+                }
+                None => (),
+            }
+
+            let mut transaction = self.pool.begin().await?;
+            let inserted = query!(
+                "INSERT INTO RecordApproval (solve_id, event_class, moderator_id)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (solve_id, event_class) DO NOTHING
+                    RETURNING solve_id",
+                solve.id.0,
+                event_class.long_name(),
+                editor.id.0,
+            )
+            .fetch_optional(&mut *transaction)
+            .await?;
+            if inserted.is_none() {
+                continue;
+            }
+            let log_event = AuditLogEvent::RecordApproved {
+                event_class: event_class.long_name().to_string(),
+                comment: Some(audit_log_comment.trim().to_string()).filter(|s| !s.is_empty()),
+            };
+            Self::add_solve_log_entry(&mut transaction, editor, solve.id, log_event).await?;
+            transaction.commit().await?;
+
+            // Let the second moderator review the solve.
+            self.remove_solve_claim(editor, solve.id).await?;
+
+            tracing::info!(editor_id = ?editor.id.0, solve_id = ?solve.id, ?event_class, "Approved record.");
+
+            self.send_private_discord_update(format!(
+                ":trophy: {} would displace the {} record for {} ({}). \
+                 Approved by {}; a second moderator must accept it too.",
+                solve.markdown_with_puzzle_and_solver_name(),
+                event_class.long_name(),
+                event.md_link(false),
+                displaced_wr.md_link(false),
+                editor.to_public().md_link(false),
+            ))
+            .await;
+
+            return Ok(RecordSignOff::AwaitingSecondModerator);
+        }
+    }
+
+    /// Returns the pending record approval for a solve in an event class, if
+    /// there is one.
+    pub async fn get_record_approval(
+        &self,
+        solve_id: SolveId,
+        event_class: EventClass,
+    ) -> sqlx::Result<Option<RecordApproval>> {
+        Ok(query_as!(
+            RecordApprovalRow,
+            "SELECT
+                    RecordApproval.solve_id,
+                    RecordApproval.event_class,
+                    RecordApproval.moderator_id,
+                    UserAccount.name AS moderator_name,
+                    RecordApproval.approved_at
+                FROM RecordApproval
+                JOIN UserAccount ON UserAccount.id = RecordApproval.moderator_id
+                WHERE RecordApproval.solve_id = $1 AND RecordApproval.event_class = $2",
+            solve_id.0,
+            event_class.long_name(),
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(RecordApproval::from))
+    }
+
+    /// Removes the pending record approval for a solve in an event class.
+    pub async fn clear_record_approval(
+        &self,
+        solve_id: SolveId,
+        event_class: EventClass,
+    ) -> sqlx::Result<()> {
+        query!(
+            "DELETE FROM RecordApproval WHERE solve_id = $1 AND event_class = $2",
+            solve_id.0,
+            event_class.long_name(),
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
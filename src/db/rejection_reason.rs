@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// Reason that a moderator can pick when rejecting a solve.
+#[derive(
+    Serialize, Deserialize, poise::ChoiceParameter, Debug, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionReason {
+    #[name = "Inspection exceeded"]
+    InspectionExceeded,
+    #[name = "Video missing"]
+    VideoMissing,
+    #[name = "Video incomplete or unclear"]
+    VideoIncomplete,
+    #[name = "Filters used in no-filter category"]
+    FiltersUsed,
+    #[name = "Macros used in no-macro category"]
+    MacrosUsed,
+    #[name = "Wrong puzzle or category"]
+    WrongCategory,
+    #[name = "Log file invalid or incomplete"]
+    InvalidLogFile,
+    #[name = "Time or move count does not match evidence"]
+    ScoreMismatch,
+    #[name = "Duplicate submission"]
+    Duplicate,
+    #[name = "Other"]
+    Other,
+}
+
+impl RejectionReason {
+    /// Every rejection reason, in the order they are offered to moderators.
+    pub const ALL: [Self; 10] = [
+        Self::InspectionExceeded,
+        Self::VideoMissing,
+        Self::VideoIncomplete,
+        Self::FiltersUsed,
+        Self::MacrosUsed,
+        Self::WrongCategory,
+        Self::InvalidLogFile,
+        Self::ScoreMismatch,
+        Self::Duplicate,
+        Self::Other,
+    ];
+
+    /// Returns the identifier used in forms and the audit log.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::InspectionExceeded => "inspection_exceeded",
+            Self::VideoMissing => "video_missing",
+            Self::VideoIncomplete => "video_incomplete",
+            Self::FiltersUsed => "filters_used",
+            Self::MacrosUsed => "macros_used",
+            Self::WrongCategory => "wrong_category",
+            Self::InvalidLogFile => "invalid_log_file",
+            Self::ScoreMismatch => "score_mismatch",
+            Self::Duplicate => "duplicate",
+            Self::Other => "other",
+        }
+    }
+
+    /// Returns the reason with an identifier from [`Self::id()`].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|reason| reason.id() == id)
+    }
+
+    /// Returns the reason with an identifier from [`Self::id()`] or a
+    /// description from [`Self::description()`], ignoring case and
+    /// surrounding whitespace.
+    pub fn from_id_or_description(s: &str) -> Option<Self> {
+        let s = s.trim();
+        Self::ALL.into_iter().find(|reason| {
+            reason.id().eq_ignore_ascii_case(s) || reason.description().eq_ignore_ascii_case(s)
+        })
+    }
+
+    /// Returns a human-readable description of the reason.
+    pub fn description(self) -> &'static str {
+        poise::ChoiceParameter::name(&self)
+    }
+
+    /// Returns every rejection reason as JSON for a `<select>` element.
+    pub fn options_json() -> serde_json::Value {
+        Self::ALL
+            .iter()
+            .map(|reason| serde_json::json!({ "id": reason.id(), "name": reason.description() }))
+            .collect()
+    }
+}
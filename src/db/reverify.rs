@@ -0,0 +1,91 @@
+use sqlx::{query, query_scalar};
+
+use crate::AppState;
+use crate::db::{PuzzleId, SolveId};
+
+/// Verifier version that matches solves verified before versions were
+/// recorded.
+pub const UNKNOWN_VERIFIER_VERSION: &str = "unknown";
+
+impl AppState {
+    /// Records the version of the verifier that produced a solve's
+    /// `auto_verify_output`.
+    pub async fn set_auto_verifier_version(
+        &self,
+        solve_id: SolveId,
+        verifier_version: &str,
+    ) -> sqlx::Result<()> {
+        query!(
+            "UPDATE Solve SET auto_verifier_version = $1 WHERE id = $2",
+            verifier_version,
+            solve_id.0,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Replaces a solve's `auto_verify_output` without changing anything else
+    /// about the solve.
+    pub async fn set_auto_verify_output(
+        &self,
+        solve_id: SolveId,
+        auto_verify_output: serde_json::Value,
+        verifier_version: &str,
+    ) -> sqlx::Result<()> {
+        query!(
+            "UPDATE Solve
+                SET auto_verify_output = $1, auto_verifier_version = $2
+                WHERE id = $3",
+            auto_verify_output,
+            verifier_version,
+            solve_id.0,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the version of the verifier that last auto-verified a solve.
+    pub async fn get_auto_verifier_version(
+        &self,
+        solve_id: SolveId,
+    ) -> sqlx::Result<Option<String>> {
+        Ok(query_scalar!(
+            "SELECT auto_verifier_version FROM Solve WHERE id = $1",
+            solve_id.0,
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .flatten())
+    }
+
+    /// Returns every solve that has been auto-verified, optionally only those
+    /// verified by a particular verifier version or of a particular puzzle.
+    ///
+    /// [`UNKNOWN_VERIFIER_VERSION`] matches solves verified before versions
+    /// were recorded.
+    pub async fn get_solves_to_reverify(
+        &self,
+        verifier_version: Option<&str>,
+        puzzle_id: Option<PuzzleId>,
+    ) -> sqlx::Result<Vec<SolveId>> {
+        Ok(query_scalar!(
+            "SELECT id FROM Solve
+                WHERE auto_verify_output IS NOT NULL
+                    AND log_file_contents IS NOT NULL
+                    AND ($1::TEXT IS NULL
+                        OR auto_verifier_version IS NOT DISTINCT FROM NULLIF($1, $2))
+                    AND ($3::INTEGER IS NULL OR puzzle_id = $3)
+                ORDER BY id",
+            verifier_version,
+            UNKNOWN_VERIFIER_VERSION,
+            puzzle_id.map(|id| id.0),
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(SolveId)
+        .collect())
+    }
+}
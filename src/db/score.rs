@@ -1,11 +1,237 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use itertools::Itertools;
+
+use super::{CategoryQuery, ProgramQuery, UserId, VariantQuery};
+
 #[derive(serde::Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ScoreQuery {
     /// Distinct puzzles
     Distinct,
-    // /// Sum of ranks
-    // Sor,
-    // /// Parallel sum of ranks
-    // Parallel,
-    // /// Kinch rank
-    // Kinch,
+    /// Sum of ranks
+    Sor,
+    /// Parallel sum of ranks
+    Parallel,
+    /// Kinch rank
+    Kinch,
+}
+impl ScoreQuery {
+    /// Returns the categories whose leaderboards are combined for rank-based
+    /// scores. Each combination of puzzle, variant, and materialness within one
+    /// of these categories is a separate event.
+    pub fn ranked_categories() -> Vec<CategoryQuery> {
+        let speed = |average, blind, one_handed| CategoryQuery::Speed {
+            average,
+            blind,
+            filters: None,
+            macros: None,
+            one_handed,
+            variant: VariantQuery::All,
+            program: ProgramQuery::All,
+        };
+        vec![
+            speed(false, false, false),
+            speed(true, false, false),
+            speed(false, true, false),
+            speed(false, false, true),
+            CategoryQuery::Fmc {
+                computer_assisted: false,
+            },
+            CategoryQuery::Fmc {
+                computer_assisted: true,
+            },
+        ]
+    }
+}
+
+/// Results of every solver in a single event, used for computing rank-based
+/// scores.
+#[derive(Debug, Default, Clone)]
+pub struct EventResults {
+    /// Number of solvers with a result in the event.
+    pub participants: i64,
+    /// Best result in the event (time in centiseconds or move count).
+    pub best: Option<i32>,
+    /// Rank and result of each solver in the event.
+    pub solvers: HashMap<UserId, (i64, i32)>,
+}
+
+/// Groups results into events by key and ranks the solvers in each event.
+///
+/// Each result is `(event, solver, result)`, where lower results are better.
+/// Only the best result of each solver in an event counts, and solvers with
+/// equal results share a rank.
+pub fn rank_events<K: Eq + Hash>(
+    results: impl IntoIterator<Item = (K, UserId, i32)>,
+) -> Vec<EventResults> {
+    let mut bests = HashMap::<K, HashMap<UserId, i32>>::new();
+    for (event, solver, result) in results {
+        let best = bests
+            .entry(event)
+            .or_default()
+            .entry(solver)
+            .or_insert(result);
+        *best = std::cmp::min(*best, result);
+    }
+
+    bests
+        .into_values()
+        .map(|solver_bests| {
+            let sorted = solver_bests
+                .into_iter()
+                .sorted_by_key(|&(solver, result)| (result, solver))
+                .collect_vec();
+            let mut solvers = HashMap::with_capacity(sorted.len());
+            let mut rank = 0;
+            let mut last_result = None;
+            for (i, &(solver, result)) in sorted.iter().enumerate() {
+                if last_result != Some(result) {
+                    rank = i as i64 + 1;
+                    last_result = Some(result);
+                }
+                solvers.insert(solver, (rank, result));
+            }
+            EventResults {
+                participants: sorted.len() as i64,
+                best: sorted.first().map(|&(_, result)| result),
+                solvers,
+            }
+        })
+        .collect()
+}
+
+/// Returns the sum of a solver's ranks across all events. Events in which the
+/// solver does not have a result count as one rank below last place.
+///
+/// Lower is better.
+pub fn sum_of_ranks(events: &[EventResults], solver: UserId) -> f64 {
+    events
+        .iter()
+        .map(|event| match event.solvers.get(&solver) {
+            Some(&(rank, _)) => rank,
+            None => event.participants + 1,
+        })
+        .sum::<i64>() as f64
+}
+
+/// Returns the "parallel" sum of a solver's ranks, computed like resistors in
+/// parallel: `1 / (1/r1 + 1/r2 + ...)`. Events in which the solver does not
+/// have a result are ignored.
+///
+/// Lower is better.
+pub fn parallel_sum_of_ranks(events: &[EventResults], solver: UserId) -> f64 {
+    let inverse_sum: f64 = events
+        .iter()
+        .filter_map(|event| event.solvers.get(&solver))
+        .map(|&(rank, _)| 1.0 / rank as f64)
+        .sum();
+    1.0 / inverse_sum
+}
+
+/// Returns the Kinch score of a solver, which is the average across all events
+/// of `100 * record / personal_best`. Events in which the solver does not have
+/// a result count as 0.
+///
+/// Higher is better.
+pub fn kinch_score(events: &[EventResults], solver: UserId) -> f64 {
+    if events.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = events
+        .iter()
+        .map(|event| match (event.best, event.solvers.get(&solver)) {
+            (Some(best), Some(&(_, result))) if result > 0 => 100.0 * best as f64 / result as f64,
+            _ => 0.0,
+        })
+        .sum();
+    total / events.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{MainPageCategory, PuzzleId};
+
+    const A: UserId = UserId(1);
+    const B: UserId = UserId(2);
+    const C: UserId = UserId(3);
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}",
+        );
+    }
+
+    /// Event 1 has a tie for first place and event 2 has no result from `B`.
+    fn two_events() -> Vec<EventResults> {
+        let mut events = rank_events([
+            (1, A, 100),
+            (1, B, 100),
+            (1, C, 120),
+            (2, A, 50),
+            (2, C, 40),
+        ]);
+        events.sort_by_key(|event| event.participants);
+        events.reverse();
+        events
+    }
+
+    #[test]
+    fn test_rank_events_with_tie() {
+        let events = two_events();
+        assert_eq!(events[0].participants, 3);
+        assert_eq!(events[0].best, Some(100));
+        assert_eq!(events[0].solvers[&A], (1, 100));
+        assert_eq!(events[0].solvers[&B], (1, 100));
+        assert_eq!(events[0].solvers[&C], (3, 120));
+        assert_eq!(events[1].participants, 2);
+        assert_eq!(events[1].solvers[&C], (1, 40));
+        assert_eq!(events[1].solvers[&A], (2, 50));
+    }
+
+    #[test]
+    fn test_sum_of_ranks() {
+        let events = two_events();
+        assert_close(sum_of_ranks(&events, A), 1.0 + 2.0);
+        assert_close(sum_of_ranks(&events, B), 1.0 + 3.0);
+        assert_close(sum_of_ranks(&events, C), 3.0 + 1.0);
+    }
+
+    #[test]
+    fn test_parallel_sum_of_ranks() {
+        let events = two_events();
+        assert_close(parallel_sum_of_ranks(&events, A), 2.0 / 3.0);
+        assert_close(parallel_sum_of_ranks(&events, B), 1.0);
+        assert_close(parallel_sum_of_ranks(&events, C), 0.75);
+    }
+
+    #[test]
+    fn test_kinch_score() {
+        let events = two_events();
+        assert_close(kinch_score(&events, A), (100.0 + 80.0) / 2.0);
+        assert_close(kinch_score(&events, B), (100.0 + 0.0) / 2.0);
+        assert_close(
+            kinch_score(&events, C),
+            (100.0 * 100.0 / 120.0 + 100.0) / 2.0,
+        );
+    }
+
+    #[test]
+    fn test_fmc_variants_are_one_event() {
+        // FMC events are per puzzle, so results in two variants of the same
+        // puzzle are ranked together.
+        let fmc = MainPageCategory::Fmc {
+            puzzle: PuzzleId(1),
+        };
+        let events = rank_events([(fmc.clone(), A, 30), (fmc.clone(), A, 25), (fmc, B, 28)]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].participants, 2);
+        assert_eq!(events[0].best, Some(25));
+        assert_eq!(events[0].solvers[&A], (1, 25));
+        assert_eq!(events[0].solvers[&B], (2, 28));
+        assert_close(sum_of_ranks(&events, A), 1.0);
+        assert_close(kinch_score(&events, B), 100.0 * 25.0 / 28.0);
+    }
 }
@@ -0,0 +1,252 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Postgres, QueryBuilder, Row};
+
+use crate::AppState;
+use crate::db::{FullSolve, PuzzleId, User};
+
+/// Verification state of a solve.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationState {
+    /// At least one of speed or fewest-moves is verified.
+    Verified,
+    /// Some part of the solve has not been reviewed yet.
+    Pending,
+    /// Nothing is verified and some part was rejected.
+    Rejected,
+}
+impl VerificationState {
+    pub const ALL: &[Self] = &[Self::Verified, Self::Pending, Self::Rejected];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Verified => "verified",
+            Self::Pending => "pending",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+/// Filters for a solve search. Every filter is optional.
+#[derive(Debug, Default, Clone)]
+pub struct SolveSearchQuery {
+    /// Full-text search of solver notes, plus moderator notes if the viewer is
+    /// a moderator.
+    pub text: Option<String>,
+    /// Substring of the solver's name.
+    pub solver: Option<String>,
+    /// Substring of the puzzle's name.
+    pub puzzle: Option<String>,
+    pub puzzle_id: Option<PuzzleId>,
+    /// Program abbreviation.
+    pub program: Option<String>,
+    /// Variant name or abbreviation.
+    pub variant: Option<String>,
+
+    pub average: Option<bool>,
+    pub blind: Option<bool>,
+    pub filters: Option<bool>,
+    pub macros: Option<bool>,
+    pub one_handed: Option<bool>,
+    pub computer_assisted: Option<bool>,
+
+    /// Inclusive lower bound on solve date.
+    pub solved_after: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on solve date.
+    pub solved_before: Option<DateTime<Utc>>,
+    pub state: Option<VerificationState>,
+
+    /// Number of solves to skip.
+    pub offset: i64,
+    /// Maximum number of solves to return.
+    pub limit: i64,
+}
+
+/// Number of matching solves for a single facet value.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct SearchFacet {
+    /// ID of the object, if there is one.
+    pub id: Option<i32>,
+    pub name: String,
+    pub count: i64,
+}
+
+/// Page of results for a solve search.
+#[derive(Debug, Clone)]
+pub struct SolveSearchResults {
+    /// Total number of matching solves, across all pages.
+    pub total: i64,
+    pub solves: Vec<FullSolve>,
+    /// Number of matching solves on each puzzle.
+    pub puzzles: Vec<SearchFacet>,
+    /// Number of matching solves in each program.
+    pub programs: Vec<SearchFacet>,
+}
+
+impl AppState {
+    /// Searches solves that are visible to `viewer`, newest first.
+    pub async fn search_solves(
+        &self,
+        query: &SolveSearchQuery,
+        viewer: Option<&User>,
+    ) -> sqlx::Result<SolveSearchResults> {
+        let mut q = QueryBuilder::new("SELECT COUNT(*)");
+        Self::sql_from_solve_search(&mut q, query, viewer);
+        let total = q.build_query_scalar::<i64>().fetch_one(&self.pool).await?;
+
+        let mut q = QueryBuilder::new("SELECT *");
+        Self::sql_from_solve_search(&mut q, query, viewer);
+        q.push(" ORDER BY upload_date DESC, id DESC");
+        q.push(" LIMIT ").push_bind(query.limit);
+        q.push(" OFFSET ").push_bind(query.offset);
+        let solves = q
+            .build()
+            .try_map(|row| FullSolve::from_row(&row))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut q =
+            QueryBuilder::new("SELECT puzzle_id AS id, puzzle_name AS name, COUNT(*) AS count");
+        Self::sql_from_solve_search(&mut q, query, viewer);
+        q.push(" GROUP BY puzzle_id, puzzle_name ORDER BY count DESC, name");
+        let puzzles = Self::fetch_search_facets(q, &self.pool).await?;
+
+        let mut q =
+            QueryBuilder::new("SELECT program_id AS id, program_abbr AS name, COUNT(*) AS count");
+        Self::sql_from_solve_search(&mut q, query, viewer);
+        q.push(" GROUP BY program_id, program_abbr ORDER BY count DESC, name");
+        let programs = Self::fetch_search_facets(q, &self.pool).await?;
+
+        Ok(SolveSearchResults {
+            total,
+            solves,
+            puzzles,
+            programs,
+        })
+    }
+
+    async fn fetch_search_facets(
+        mut q: QueryBuilder<'_, Postgres>,
+        pool: &sqlx::PgPool,
+    ) -> sqlx::Result<Vec<SearchFacet>> {
+        q.build()
+            .try_map(|row| {
+                Ok(SearchFacet {
+                    id: row.try_get("id")?,
+                    name: row.try_get("name")?,
+                    count: row.try_get("count")?,
+                })
+            })
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Pushes a `FROM` and `WHERE` clause selecting solves that match `query`
+    /// and are visible to `viewer`.
+    fn sql_from_solve_search<'q>(
+        q: &mut QueryBuilder<'q, Postgres>,
+        query: &'q SolveSearchQuery,
+        viewer: Option<&User>,
+    ) {
+        let is_moderator = viewer.is_some_and(|u| u.moderator);
+
+        q.push(" FROM InlinedSolve WHERE TRUE");
+
+        if !is_moderator {
+            q.push(" AND (speed_verified IS TRUE OR fmc_verified IS TRUE");
+            if let Some(viewer) = viewer {
+                q.push(" OR solver_id = ").push_bind(viewer.id.0);
+            }
+            q.push(")");
+        }
+
+        if let Some(text) = &query.text {
+            // These expressions match the indexes on `Solve`.
+            q.push(" AND (to_tsvector('simple', solver_notes) @@ websearch_to_tsquery('simple', ")
+                .push_bind(text)
+                .push(")");
+            if is_moderator {
+                q.push(
+                    " OR to_tsvector('simple', moderator_notes) @@ websearch_to_tsquery('simple', ",
+                )
+                .push_bind(text)
+                .push(")");
+            }
+            q.push(")");
+        }
+
+        if let Some(solver) = &query.solver {
+            q.push(" AND COALESCE(solver_name, 'user #' || solver_id) ILIKE ")
+                .push_bind(like_pattern(solver));
+        }
+        if let Some(puzzle) = &query.puzzle {
+            q.push(" AND puzzle_name ILIKE ")
+                .push_bind(like_pattern(puzzle));
+        }
+        if let Some(puzzle_id) = query.puzzle_id {
+            q.push(" AND puzzle_id = ").push_bind(puzzle_id.0);
+        }
+        if let Some(program) = &query.program {
+            q.push(" AND LOWER(program_abbr) = LOWER(")
+                .push_bind(program)
+                .push(")");
+        }
+        if let Some(variant) = &query.variant {
+            q.push(" AND (LOWER(variant_name) = LOWER(")
+                .push_bind(variant)
+                .push(") OR LOWER(variant_abbr) = LOWER(")
+                .push_bind(variant)
+                .push("))");
+        }
+
+        for (column, value) in [
+            ("average", query.average),
+            ("blind", query.blind),
+            ("filters", query.filters),
+            ("macros", query.macros),
+            ("one_handed", query.one_handed),
+            ("computer_assisted", query.computer_assisted),
+        ] {
+            if let Some(value) = value {
+                q.push(format!(" AND {column} = ")).push_bind(value);
+            }
+        }
+
+        if let Some(solved_after) = query.solved_after {
+            q.push(" AND solve_date >= ").push_bind(solved_after);
+        }
+        if let Some(solved_before) = query.solved_before {
+            q.push(" AND solve_date < ").push_bind(solved_before);
+        }
+
+        match query.state {
+            None => (),
+            Some(VerificationState::Verified) => {
+                q.push(" AND (speed_verified IS TRUE OR fmc_verified IS TRUE)");
+            }
+            Some(VerificationState::Pending) => {
+                // Same condition as `PendingSolve`
+                q.push(
+                    " AND ((speed_cs > 0 AND speed_verified IS NULL)
+                        OR (move_count > 0 AND fmc_verified IS NULL)
+                        OR (speed_verified IS NULL AND fmc_verified IS NULL))",
+                );
+            }
+            Some(VerificationState::Rejected) => {
+                q.push(
+                    " AND speed_verified IS NOT TRUE AND fmc_verified IS NOT TRUE
+                        AND (speed_verified IS FALSE OR fmc_verified IS FALSE)",
+                );
+            }
+        }
+    }
+}
+
+/// Returns an `ILIKE` pattern that matches strings containing `s`.
+fn like_pattern(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
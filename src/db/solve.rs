@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 use chrono::{DateTime, Utc};
@@ -606,6 +607,17 @@ impl FullSolve {
     }
 }
 
+/// Result of a moderator changing the verification status of a solve in an
+/// event class.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    /// The solve has the requested verification status.
+    Applied,
+    /// The acceptance was recorded as the first approval of a record, and the
+    /// solve is still waiting for a second moderator.
+    AwaitingSecondModerator,
+}
+
 impl AppState {
     pub async fn get_opt_solve(&self, id: SolveId) -> sqlx::Result<Option<FullSolve>> {
         query_as!(
@@ -621,12 +633,18 @@ impl AppState {
         self.get_opt_solve(id).await?.ok_or(AppError::InvalidSolve)
     }
 
+    /// Pushes a `FROM` and `WHERE` clause selecting solves in `category`.
+    ///
+    /// If `as_of` is set, only solves that were solved, uploaded, and (if
+    /// `require_verified` is set) verified strictly before that time are
+    /// included. Verification status is reconstructed from the audit log.
     fn sql_from_verified_solves_in_category<'q>(
         &self,
         q: &mut QueryBuilder<'q, Postgres>,
         puzzle: Option<PuzzleId>,
         category: &'q CategoryQuery,
         require_verified: bool,
+        as_of: Option<DateTime<Utc>>,
     ) {
         match category {
             CategoryQuery::Speed {
@@ -639,11 +657,15 @@ impl AppState {
                 program,
             } => {
                 q.push(" FROM InlinedSolve WHERE speed_cs IS NOT NULL");
-                if require_verified {
-                    q.push(" AND speed_verified IS TRUE");
-                } else {
-                    q.push(" AND speed_verified IS NOT FALSE");
-                }
+                match (require_verified, as_of) {
+                    (true, Some(as_of)) => q
+                        .push(" AND speed_verified_as_of(id, ")
+                        .push_bind(as_of)
+                        .push(")"),
+                    (true, None) => q.push(" AND speed_verified IS TRUE"),
+                    (false, _) => q.push(" AND speed_verified IS NOT FALSE"),
+                };
+                Self::sql_solved_and_uploaded_before(q, as_of);
                 if let Some(puzzle) = puzzle {
                     q.push(" AND puzzle_id = ").push_bind(puzzle.0);
                 }
@@ -679,12 +701,20 @@ impl AppState {
                 };
             }
             CategoryQuery::Fmc { computer_assisted } => {
-                q.push(" FROM VerifiedFmcSolve WHERE move_count IS NOT NULL");
-                if require_verified {
-                    q.push(" AND fmc_verified IS TRUE");
-                } else {
-                    q.push(" AND fmc_verified IS NOT FALSE");
-                }
+                match as_of {
+                    // `VerifiedFmcSolve` only has solves that are verified now.
+                    Some(_) => q.push(" FROM InlinedSolve WHERE move_count IS NOT NULL"),
+                    None => q.push(" FROM VerifiedFmcSolve WHERE move_count IS NOT NULL"),
+                };
+                match (require_verified, as_of) {
+                    (true, Some(as_of)) => q
+                        .push(" AND fmc_verified_as_of(id, ")
+                        .push_bind(as_of)
+                        .push(")"),
+                    (true, None) => q.push(" AND fmc_verified IS TRUE"),
+                    (false, _) => q.push(" AND fmc_verified IS NOT FALSE"),
+                };
+                Self::sql_solved_and_uploaded_before(q, as_of);
                 if let Some(puzzle) = puzzle {
                     q.push(" AND puzzle_id = ").push_bind(puzzle.0);
                 }
@@ -694,12 +724,23 @@ impl AppState {
         }
     }
 
+    fn sql_solved_and_uploaded_before(
+        q: &mut QueryBuilder<'_, Postgres>,
+        as_of: Option<DateTime<Utc>>,
+    ) {
+        if let Some(as_of) = as_of {
+            q.push(" AND solve_date < ").push_bind(as_of);
+            q.push(" AND upload_date < ").push_bind(as_of);
+        }
+    }
+
     #[allow(clippy::useless_format)]
     fn sql_select_ranked_leaderboards_from_category<'q>(
         &self,
         q: &mut QueryBuilder<'q, Postgres>,
         puzzle: Option<PuzzleId>,
         category: &'q CategoryQuery,
+        as_of: Option<DateTime<Utc>>,
     ) {
         let score = category.sql_order_fields();
         let partitioning = FullSolve::CATEGORY_PARTITIONING;
@@ -713,7 +754,7 @@ impl AppState {
         q.push(format!("     SELECT"));
         q.push(format!("         DISTINCT ON (solver_id, {partitioning})"));
         q.push(format!("         *"));
-        self.sql_from_verified_solves_in_category(q, puzzle, category, true);
+        self.sql_from_verified_solves_in_category(q, puzzle, category, true, as_of);
         q.push(format!("     ORDER BY solver_id, {partitioning}, {score}"));
         q.push("         ) as s");
     }
@@ -721,44 +762,166 @@ impl AppState {
     pub async fn get_all_puzzles_counts(
         &self,
         query: &CategoryQuery,
+        as_of: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Vec<(MainPageCategory, i64)>> {
         let partitioning = FullSolve::CATEGORY_PARTITIONING;
         let mut q = QueryBuilder::new(format!(
             "SELECT {partitioning}, COUNT(DISTINCT solver_id) as count",
         ));
-        self.sql_from_verified_solves_in_category(&mut q, None, query, true);
+        self.sql_from_verified_solves_in_category(&mut q, None, query, true, as_of);
         q.push(format!(" GROUP BY {partitioning}"));
 
         q.build()
             .try_map(|row| {
-                let main_page_category = match query {
-                    CategoryQuery::Speed { .. } => MainPageCategory::Speed {
-                        puzzle: PuzzleId(row.try_get("puzzle_id")?),
-                        variant: row.try_get::<Option<_>, _>("variant_id")?.map(VariantId),
-                        material: row.try_get("program_material")?,
-                    },
-                    CategoryQuery::Fmc { .. } => MainPageCategory::Fmc {
-                        puzzle: PuzzleId(row.try_get("puzzle_id")?),
-                    },
-                };
+                let main_page_category = Self::main_page_category_of_row(query, &row)?;
                 Ok((main_page_category, row.try_get("count")?))
             })
             .fetch_all(&self.pool)
             .await
     }
 
+    /// Returns the main page category of a row with `puzzle_id`, `variant_id`,
+    /// and `program_material` columns in the results of `query`.
+    fn main_page_category_of_row(
+        query: &CategoryQuery,
+        row: &PgRow,
+    ) -> sqlx::Result<MainPageCategory> {
+        Ok(match query {
+            CategoryQuery::Speed { .. } => MainPageCategory::Speed {
+                puzzle: PuzzleId(row.try_get("puzzle_id")?),
+                variant: row.try_get::<Option<_>, _>("variant_id")?.map(VariantId),
+                material: row.try_get("program_material")?,
+            },
+            CategoryQuery::Fmc { .. } => MainPageCategory::Fmc {
+                puzzle: PuzzleId(row.try_get("puzzle_id")?),
+            },
+        })
+    }
+
     pub async fn get_score_leaderboard(
         &self,
         score: ScoreQuery,
+        as_of: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Vec<(i64, PublicUser, String)>> {
         match score {
-            ScoreQuery::Distinct => self.get_distinct_puzzles_leaderboard().await,
+            ScoreQuery::Distinct => self.get_distinct_puzzles_leaderboard(as_of).await,
+            ScoreQuery::Sor => {
+                self.get_rank_based_score_leaderboard(score::sum_of_ranks, false, 0, as_of)
+                    .await
+            }
+            ScoreQuery::Parallel => {
+                self.get_rank_based_score_leaderboard(score::parallel_sum_of_ranks, false, 3, as_of)
+                    .await
+            }
+            ScoreQuery::Kinch => {
+                self.get_rank_based_score_leaderboard(score::kinch_score, true, 2, as_of)
+                    .await
+            }
         }
     }
 
+    /// Returns a leaderboard of solvers based on their ranks in every event of
+    /// [`ScoreQuery::ranked_categories()`].
+    ///
+    /// `precision` is the number of decimal places to display. Solvers whose
+    /// scores are equal at that precision are tied.
+    async fn get_rank_based_score_leaderboard(
+        &self,
+        score_fn: fn(&[score::EventResults], UserId) -> f64,
+        higher_is_better: bool,
+        precision: usize,
+        as_of: Option<DateTime<Utc>>,
+    ) -> sqlx::Result<Vec<(i64, PublicUser, String)>> {
+        let categories = ScoreQuery::ranked_categories();
+
+        let mut results = vec![];
+        let mut solvers = BTreeMap::<UserId, PublicUser>::new();
+        for (i, category) in categories.iter().enumerate() {
+            let mut q = QueryBuilder::new(
+                "SELECT solver_id, solver_name, puzzle_id, variant_id, program_material, \
+                 speed_cs, move_count",
+            );
+            self.sql_from_verified_solves_in_category(&mut q, None, category, true, as_of);
+            let rows = q.build().fetch_all(&self.pool).await?;
+
+            for row in rows {
+                let result: Option<i32> = match category {
+                    CategoryQuery::Speed { .. } => row.try_get("speed_cs")?,
+                    CategoryQuery::Fmc { .. } => row.try_get("move_count")?,
+                };
+                let Some(result) = result else { continue };
+                let solver = PublicUser {
+                    id: UserId(row.try_get("solver_id")?),
+                    name: row.try_get("solver_name")?,
+                };
+                let event = (i, Self::main_page_category_of_row(category, &row)?);
+                results.push((event, solver.id, result));
+                solvers.insert(solver.id, solver);
+            }
+        }
+        let events = score::rank_events(results);
+
+        // Round scores so that ties are consistent with the displayed value.
+        let scale = 10_f64.powi(precision as i32);
+        let scored_solvers = solvers
+            .into_values()
+            .map(|solver| {
+                let score = score_fn(&events, solver.id);
+                let key = (score * scale).round() as i64;
+                (if higher_is_better { -key } else { key }, score, solver)
+            })
+            .sorted_by_key(|(key, _, solver)| (*key, solver.id))
+            .collect_vec();
+
+        let mut ret = Vec::with_capacity(scored_solvers.len());
+        let mut rank = 0;
+        let mut last_key = None;
+        for (i, (key, score, solver)) in scored_solvers.into_iter().enumerate() {
+            if last_key != Some(key) {
+                rank = i as i64 + 1;
+                last_key = Some(key);
+            }
+            ret.push((rank, solver, format!("{score:.precision$}")));
+        }
+        Ok(ret)
+    }
+
     pub async fn get_distinct_puzzles_leaderboard(
         &self,
+        as_of: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Vec<(i64, PublicUser, String)>> {
+        if let Some(as_of) = as_of {
+            let mut q = QueryBuilder::new(
+                "SELECT
+                    solver_id, solver_name,
+                    COUNT(DISTINCT puzzle_id) AS score,
+                    RANK() OVER (ORDER BY COUNT(DISTINCT puzzle_id) DESC) as rank
+                    FROM InlinedSolve
+                    WHERE solve_date < ",
+            );
+            q.push_bind(as_of);
+            q.push(" AND upload_date < ").push_bind(as_of);
+            q.push(" AND (speed_verified_as_of(id, ").push_bind(as_of);
+            q.push(") OR fmc_verified_as_of(id, ").push_bind(as_of);
+            q.push("))");
+            q.push(" GROUP BY solver_id, solver_name");
+            q.push(" ORDER BY rank ASC, solver_id ASC");
+            return q
+                .build()
+                .try_map(|row| {
+                    Ok((
+                        row.try_get("rank")?,
+                        PublicUser {
+                            id: UserId(row.try_get("solver_id")?),
+                            name: row.try_get("solver_name")?,
+                        },
+                        row.try_get::<i64, _>("score")?.to_string(),
+                    ))
+                })
+                .fetch_all(&self.pool)
+                .await;
+        }
+
         query!(
             "SELECT
                 solver_id, solver_name,
@@ -791,9 +954,10 @@ impl AppState {
         &self,
         puzzle: &Puzzle,
         category: &CategoryQuery,
+        as_of: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Vec<RankedFullSolve>> {
         let mut q = QueryBuilder::default();
-        self.sql_select_ranked_leaderboards_from_category(&mut q, Some(puzzle.id), category);
+        self.sql_select_ranked_leaderboards_from_category(&mut q, Some(puzzle.id), category, as_of);
         q.build_query_as::<RankedFullSolve>()
             .fetch_all(&self.pool)
             .await
@@ -804,10 +968,11 @@ impl AppState {
     pub async fn get_all_puzzles_leaderboard(
         &self,
         query: &CategoryQuery,
+        as_of: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Vec<(Event, FullSolve)>> {
         let mut q =
             QueryBuilder::new("SELECT DISTINCT ON (puzzle_id, variant_id, program_material) *");
-        self.sql_from_verified_solves_in_category(&mut q, None, query, true);
+        self.sql_from_verified_solves_in_category(&mut q, None, query, true, as_of);
         q.push(format!(
             " ORDER BY puzzle_id, variant_id, program_material, {}",
             match query {
@@ -819,38 +984,7 @@ impl AppState {
         q.build()
             .try_map(|row| {
                 let solve = FullSolve::from_row(&row)?;
-                let event = Event {
-                    puzzle: solve.puzzle.clone(),
-                    category: match query {
-                        CategoryQuery::Speed {
-                            average,
-                            blind,
-                            filters,
-                            macros,
-                            one_handed,
-                            variant: _,
-                            program: _,
-                        } => Category::Speed {
-                            average: *average,
-                            blind: *blind,
-                            filters: filters.unwrap_or(match &solve.variant {
-                                Some(v) => v.primary_filters,
-                                None => solve.puzzle.primary_filters,
-                            }),
-                            macros: macros.unwrap_or(match &solve.variant {
-                                Some(v) => v.primary_macros,
-                                None => solve.puzzle.primary_macros,
-                            }),
-                            one_handed: *one_handed,
-                            variant: solve.variant.clone(),
-                            material: solve.program.material,
-                        },
-
-                        CategoryQuery::Fmc { computer_assisted } => Category::Fmc {
-                            computer_assisted: *computer_assisted,
-                        },
-                    },
-                };
+                let event = query.event_of_solve(&solve);
                 Ok((event, solve))
             })
             .fetch_all(&self.pool)
@@ -893,9 +1027,16 @@ impl AppState {
         &self,
         puzzle: &Puzzle,
         category_query: &CategoryQuery,
+        as_of: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Vec<FullSolve>> {
         let mut q = QueryBuilder::new("SELECT *");
-        self.sql_from_verified_solves_in_category(&mut q, Some(puzzle.id), category_query, true);
+        self.sql_from_verified_solves_in_category(
+            &mut q,
+            Some(puzzle.id),
+            category_query,
+            true,
+            as_of,
+        );
         q.push(" ORDER BY solve_date, upload_date, id");
         q.build()
             .try_map(|row| FullSolve::from_row(&row))
@@ -907,9 +1048,10 @@ impl AppState {
         &self,
         puzzle: &Puzzle,
         category_query: &CategoryQuery,
+        as_of: Option<DateTime<Utc>>,
     ) -> sqlx::Result<Vec<FullSolve>> {
         let all_solves = self
-            .get_solve_history(puzzle, category_query)
+            .get_solve_history(puzzle, category_query, as_of)
             .await?
             .into_iter();
         let mut ret = match category_query {
@@ -943,7 +1085,7 @@ impl AppState {
     ) -> sqlx::Result<Vec<(MainPageCategory, RankedFullSolve)>> {
         let mut q = QueryBuilder::default();
         q.push(" SELECT * FROM (");
-        self.sql_select_ranked_leaderboards_from_category(&mut q, None, category);
+        self.sql_select_ranked_leaderboards_from_category(&mut q, None, category, None);
         q.push("     ) as ss");
         q.push("     WHERE solver_id = ").push_bind(user_id.0);
         Ok(q.build_query_as::<RankedFullSolve>()
@@ -951,22 +1093,165 @@ impl AppState {
             .await?
             .into_iter()
             .map(|ranked_solve| {
-                let RankedFullSolve { solve, .. } = &ranked_solve;
-                let main_page_category = match category {
-                    CategoryQuery::Speed { .. } => MainPageCategory::Speed {
-                        puzzle: solve.puzzle.id,
-                        variant: solve.variant.as_ref().map(|v| v.id),
-                        material: solve.program.material,
-                    },
-                    CategoryQuery::Fmc { .. } => MainPageCategory::Fmc {
-                        puzzle: solve.puzzle.id,
-                    },
-                };
+                let main_page_category = category.main_page_category_of_solve(&ranked_solve.solve);
                 (main_page_category, ranked_solve)
             })
             .collect())
     }
 
+    /// Returns the `limit` most recently verified solves that set or tied a
+    /// record in an event matching `category` when they were solved, for a
+    /// single puzzle or for all puzzles.
+    pub async fn get_recent_records(
+        &self,
+        puzzle: Option<PuzzleId>,
+        category: &CategoryQuery,
+        limit: i64,
+    ) -> sqlx::Result<Vec<(Event, FullSolve)>> {
+        let (result, partitioning) = match category {
+            CategoryQuery::Speed { .. } => ("speed_cs", FullSolve::CATEGORY_PARTITIONING),
+            CategoryQuery::Fmc { .. } => ("move_count", "puzzle_id"),
+        };
+
+        let mut q = QueryBuilder::new("SELECT * FROM (");
+        q.push(format!(
+            "SELECT *, MIN({result}) OVER (
+                PARTITION BY {partitioning}
+                ORDER BY solve_date, upload_date, id
+                ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+            ) AS previous_record"
+        ));
+        self.sql_from_verified_solves_in_category(&mut q, puzzle, category, true, None);
+        q.push(format!(
+            ") AS s
+            WHERE previous_record IS NULL OR {result} <= previous_record
+            ORDER BY COALESCE(
+                (SELECT verified_at FROM Solve WHERE Solve.id = s.id),
+                upload_date
+            ) DESC
+            LIMIT "
+        ));
+        q.push_bind(limit);
+        Ok(q.build_query_as::<FullSolve>()
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|solve| (category.event_of_solve(&solve), solve))
+            .collect())
+    }
+
+    /// Returns the personal best progression of a solver in every event
+    /// matching `category`: each verified solve that improved on their previous
+    /// best, in chronological order, along with the rank that it held on the
+    /// leaderboard when it was solved.
+    pub async fn get_solver_pb_history(
+        &self,
+        user_id: UserId,
+        category: &CategoryQuery,
+    ) -> sqlx::Result<Vec<(MainPageCategory, Vec<RankedFullSolve>)>> {
+        let mut q = QueryBuilder::new("SELECT *");
+        self.sql_from_verified_solves_in_category(&mut q, None, category, true, None);
+        q.push(" AND solver_id = ").push_bind(user_id.0);
+        q.push(" ORDER BY solve_date, upload_date, id");
+        let solver_solves = q
+            .build_query_as::<FullSolve>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut progressions = HashMap::<MainPageCategory, Vec<(i32, FullSolve)>>::new();
+        for solve in solver_solves {
+            let result = match category {
+                CategoryQuery::Speed { .. } => solve.speed_cs,
+                CategoryQuery::Fmc { .. } => solve.move_count,
+            };
+            let Some(result) = result else { continue };
+
+            let progression = progressions
+                .entry(category.main_page_category_of_solve(&solve))
+                .or_default();
+            if progression.last().is_none_or(|&(best, _)| result < best) {
+                progression.push((result, solve));
+            }
+        }
+
+        let (solve_ids, results) = progressions
+            .values()
+            .flatten()
+            .map(|(result, solve)| (solve.id.0, *result))
+            .unzip();
+        let ranks = self
+            .count_solvers_ahead_of_each(user_id, category, solve_ids, results)
+            .await?;
+
+        Ok(progressions
+            .into_iter()
+            .map(|(main_page_category, progression)| {
+                let ranked = progression
+                    .into_iter()
+                    .map(|(_, solve)| RankedFullSolve {
+                        rank: 1 + ranks.get(&solve.id.0).copied().unwrap_or(0),
+                        solve,
+                    })
+                    .collect();
+                (main_page_category, ranked)
+            })
+            .collect())
+    }
+
+    /// Returns, for each solve in `solve_ids` with the matching result in
+    /// `results`, the number of other solvers who had a result at least as
+    /// good in the same event when that solve was solved. Earlier solves with
+    /// the same result rank higher.
+    async fn count_solvers_ahead_of_each(
+        &self,
+        user_id: UserId,
+        category: &CategoryQuery,
+        solve_ids: Vec<i32>,
+        results: Vec<i32>,
+    ) -> sqlx::Result<HashMap<i32, i64>> {
+        let mut q = QueryBuilder::new(
+            "WITH Progression AS (
+                SELECT
+                    p.id, p.result, s.puzzle_id, s.variant_id, s.program_material,
+                    s.solve_date, s.upload_date
+                FROM UNNEST(",
+        );
+        q.push_bind(solve_ids)
+            .push("::INTEGER[], ")
+            .push_bind(results)
+            .push("::INTEGER[]) AS p(id, result)");
+        q.push(" JOIN InlinedSolve AS s ON s.id = p.id");
+        q.push("), Other AS (SELECT *");
+        self.sql_from_verified_solves_in_category(&mut q, None, category, true, None);
+        q.push(" AND solver_id <> ").push_bind(user_id.0);
+        q.push(
+            ")
+            SELECT Progression.id, COUNT(DISTINCT Other.solver_id)
+                FROM Progression
+                LEFT JOIN Other ON Other.puzzle_id = Progression.puzzle_id",
+        );
+        match category {
+            CategoryQuery::Speed { .. } => {
+                q.push(" AND Other.variant_id IS NOT DISTINCT FROM Progression.variant_id");
+                q.push(" AND Other.program_material = Progression.program_material");
+                q.push(" AND Other.speed_cs <= Progression.result");
+            }
+            CategoryQuery::Fmc { .. } => {
+                q.push(" AND Other.move_count <= Progression.result");
+            }
+        }
+        q.push(
+            " AND (Other.solve_date, Other.upload_date, Other.id)
+                    < (Progression.solve_date, Progression.upload_date, Progression.id)
+                GROUP BY Progression.id",
+        );
+        Ok(q.build_query_as::<(i32, i64)>()
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .collect())
+    }
+
     pub async fn get_solver_submissions(&self, user_id: UserId) -> sqlx::Result<Vec<FullSolve>> {
         query_as!(
             InlinedSolve,
@@ -978,6 +1263,55 @@ impl AppState {
         .await
     }
 
+    /// Returns the most recently verified solves of a solver, newest first,
+    /// along with the time at which a result of each was last accepted.
+    pub async fn get_recently_verified_solves(
+        &self,
+        user_id: UserId,
+        limit: i64,
+    ) -> sqlx::Result<Vec<(DateTime<Utc>, FullSolve)>> {
+        let mut q = QueryBuilder::new(
+            "SELECT InlinedSolve.*, Solve.verified_at
+                FROM Solve JOIN InlinedSolve ON InlinedSolve.id = Solve.id
+                WHERE Solve.verified_at IS NOT NULL
+                    AND (Solve.speed_verified OR Solve.fmc_verified)
+                    AND Solve.solver_id = ",
+        );
+        q.push_bind(user_id.0);
+        q.push(" ORDER BY Solve.verified_at DESC, Solve.id DESC LIMIT ")
+            .push_bind(limit);
+        q.build()
+            .try_map(|row: PgRow| Ok((row.try_get("verified_at")?, FullSolve::from_row(&row)?)))
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Returns the time at which a result of each solve was last accepted.
+    /// Solves with no accepted result are omitted.
+    pub async fn get_verification_times(
+        &self,
+        solve_ids: &[SolveId],
+    ) -> sqlx::Result<HashMap<SolveId, DateTime<Utc>>> {
+        let ids = solve_ids.iter().map(|id| id.0).collect_vec();
+        Ok(
+            query!("SELECT id, verified_at FROM Solve WHERE id = ANY($1)", &ids,)
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .filter_map(|row| Some((SolveId(row.id), row.verified_at?)))
+                .collect(),
+        )
+    }
+
+    /// Returns every solve with a verified speed or fewest-moves result, sorted
+    /// by ID.
+    pub async fn get_all_verified_solves(&self) -> sqlx::Result<Vec<FullSolve>> {
+        query_as!(InlinedSolve, "SELECT * FROM VerifiedSolve ORDER BY id")
+            .try_map(FullSolve::try_from)
+            .fetch_all(&self.pool)
+            .await
+    }
+
     pub async fn get_pending_submissions(&self) -> sqlx::Result<Vec<FullSolve>> {
         query_as!(InlinedSolve, "SELECT * FROM PendingSolve")
             .try_map(FullSolve::try_from)
@@ -1073,11 +1407,15 @@ impl AppState {
         }
     }
 
+    /// Adds a new solve. If `notify` is `true`, Discord and webhooks are
+    /// alerted of the new solve.
     pub async fn add_solve_external(
         &self,
         editor: &User,
         mut data: SolveDbFields,
+        extra_log_files: Vec<(String, Vec<u8>)>,
         will_be_auto_verified: bool,
+        notify: bool,
     ) -> AppResult<SolveId> {
         let auth = if editor.moderator {
             EditAuthorization::Moderator
@@ -1171,6 +1509,8 @@ impl AppState {
 
         let solve_id = SolveId(solve_id);
 
+        Self::add_extra_log_files(&mut transaction, solve_id, extra_log_files).await?;
+
         let stored_data = fetch_log_fields_for_solve!(&mut *transaction, solve_id).await?;
 
         let fields = fields_map!(
@@ -1208,8 +1548,11 @@ impl AppState {
         transaction.commit().await?;
 
         tracing::info!(editor_id = ?editor.id, solve = ?solve_id, ?data, "Manual solve submission added.");
-        self.alert_discord_of_solve(editor, solve_id, false, will_be_auto_verified)
-            .await;
+        self.check_for_duplicate_content(solve_id).await;
+        if notify {
+            self.alert_discord_of_solve(editor, solve_id, false, will_be_auto_verified)
+                .await;
+        }
 
         Ok(solve_id)
     }
@@ -1423,6 +1766,9 @@ impl AppState {
         if !editor.moderator {
             self.alert_discord_of_solve(editor, id, true, false).await;
         }
+        if changed_log_file {
+            self.check_for_duplicate_content(id).await;
+        }
 
         tracing::info!(editor_id = ?editor.id, solve_id = ?id, ?new_data, "Solve updated.");
 
@@ -1435,19 +1781,61 @@ impl AppState {
         solve_id: SolveId,
         verified: Option<bool>,
         audit_log_comment: &str,
-    ) -> AppResult {
+    ) -> AppResult<VerifyOutcome> {
+        self.verify_speed_with_reason(editor, solve_id, verified, None, audit_log_comment)
+            .await
+    }
+
+    /// Same as [`Self::verify_speed()`], but records why the solve was
+    /// rejected. `rejection_reason` is ignored unless `verified` is
+    /// `Some(false)`.
+    pub async fn verify_speed_with_reason(
+        &self,
+        editor: &User,
+        solve_id: SolveId,
+        verified: Option<bool>,
+        rejection_reason: Option<RejectionReason>,
+        audit_log_comment: &str,
+    ) -> AppResult<VerifyOutcome> {
         if !editor.moderator {
             return Err(AppError::NotAuthorized);
         }
 
         self.check_allow_moderator_actions()?;
+        self.check_solve_claim(editor, solve_id).await?;
 
         let solve = self.get_solve(solve_id).await?;
         if verified.is_some() && solve.speed_cs.is_none() {
             return Err(AppError::Other("Not a speed solve".to_string()));
         }
         if verified == solve.speed_verified {
-            return Ok(()); // no change
+            return Ok(VerifyOutcome::Applied); // no change
+        }
+
+        let mut audit_log_comment = audit_log_comment.trim().to_string();
+        if verified == Some(true) {
+            match self
+                .sign_off_record(editor, &solve, EventClass::Speed, &audit_log_comment)
+                .await?
+            {
+                RecordSignOff::NotRequired => (),
+                RecordSignOff::AwaitingSecondModerator => {
+                    return Ok(VerifyOutcome::AwaitingSecondModerator);
+                }
+                RecordSignOff::Confirmed(first_moderator) => {
+                    let confirmation = format!(
+                        "Confirmed record approved by {}",
+                        first_moderator.display_name(),
+                    );
+                    audit_log_comment = [confirmation, audit_log_comment]
+                        .into_iter()
+                        .filter(|s| !s.is_empty())
+                        .join("\n");
+                }
+            }
+        } else {
+            self.clear_record_approval(solve_id, EventClass::Speed)
+                .await?;
         }
 
         let mut transaction = self.pool.begin().await?;
@@ -1460,7 +1848,8 @@ impl AppState {
             "UPDATE Solve
                 SET
                     speed_verified_by = $1,
-                    speed_verified = $2
+                    speed_verified = $2,
+                    verified_at = CASE WHEN $2 THEN NOW() ELSE verified_at END
                 WHERE id = $3
                 RETURNING id
             ",
@@ -1478,6 +1867,7 @@ impl AppState {
         let event = AuditLogEvent::SpeedVerified {
             old: old_stored_data.speed_verified,
             new: new_stored_data.speed_verified,
+            reason: rejection_reason.filter(|_| verified == Some(false)),
             comment: Some(audit_log_comment.trim().to_string()).filter(|s| !s.is_empty()),
         };
         Self::add_solve_log_entry(&mut transaction, editor, solve_id, event).await?;
@@ -1486,6 +1876,9 @@ impl AppState {
 
         tracing::info!(editor_id = ?editor.id.0, ?solve_id, ?verified, "Updated solve speed verification.");
 
+        self.release_solve_claim_if_reviewed(solve_id).await?;
+        self.resolve_appeal_if_reviewed(solve_id).await?;
+
         if !editor.dummy {
             self.alert_discord_of_manual_verification(editor, solve_id, EventClass::Speed)
                 .await;
@@ -1495,7 +1888,7 @@ impl AppState {
             self.alert_discord_to_speed_record(solve_id).await;
         }
 
-        Ok(())
+        Ok(VerifyOutcome::Applied)
     }
 
     pub async fn verify_fmc(
@@ -1504,19 +1897,61 @@ impl AppState {
         solve_id: SolveId,
         verified: Option<bool>,
         audit_log_comment: &str,
-    ) -> AppResult {
+    ) -> AppResult<VerifyOutcome> {
+        self.verify_fmc_with_reason(editor, solve_id, verified, None, audit_log_comment)
+            .await
+    }
+
+    /// Same as [`Self::verify_fmc()`], but records why the solve was
+    /// rejected. `rejection_reason` is ignored unless `verified` is
+    /// `Some(false)`.
+    pub async fn verify_fmc_with_reason(
+        &self,
+        editor: &User,
+        solve_id: SolveId,
+        verified: Option<bool>,
+        rejection_reason: Option<RejectionReason>,
+        audit_log_comment: &str,
+    ) -> AppResult<VerifyOutcome> {
         if !editor.moderator {
             return Err(AppError::NotAuthorized);
         }
 
         self.check_allow_moderator_actions()?;
+        self.check_solve_claim(editor, solve_id).await?;
 
         let solve = self.get_solve(solve_id).await?;
         if verified.is_some() && solve.move_count.is_none() {
             return Err(AppError::Other("Not a fewest-moves solve".to_string()));
         }
         if verified == solve.fmc_verified {
-            return Ok(()); // no change
+            return Ok(VerifyOutcome::Applied); // no change
+        }
+
+        let mut audit_log_comment = audit_log_comment.trim().to_string();
+        if verified == Some(true) {
+            match self
+                .sign_off_record(editor, &solve, EventClass::Fmc, &audit_log_comment)
+                .await?
+            {
+                RecordSignOff::NotRequired => (),
+                RecordSignOff::AwaitingSecondModerator => {
+                    return Ok(VerifyOutcome::AwaitingSecondModerator);
+                }
+                RecordSignOff::Confirmed(first_moderator) => {
+                    let confirmation = format!(
+                        "Confirmed record approved by {}",
+                        first_moderator.display_name(),
+                    );
+                    audit_log_comment = [confirmation, audit_log_comment]
+                        .into_iter()
+                        .filter(|s| !s.is_empty())
+                        .join("\n");
+                }
+            }
+        } else {
+            self.clear_record_approval(solve_id, EventClass::Fmc)
+                .await?;
         }
 
         let mut transaction = self.pool.begin().await?;
@@ -1529,7 +1964,8 @@ impl AppState {
             "UPDATE Solve
                 SET
                     fmc_verified_by = $1,
-                    fmc_verified = $2
+                    fmc_verified = $2,
+                    verified_at = CASE WHEN $2 THEN NOW() ELSE verified_at END
                 WHERE id = $3
                 RETURNING id
             ",
@@ -1547,6 +1983,7 @@ impl AppState {
         let event = AuditLogEvent::FmcVerified {
             old: old_stored_data.fmc_verified,
             new: new_stored_data.fmc_verified,
+            reason: rejection_reason.filter(|_| verified == Some(false)),
             comment: Some(audit_log_comment.trim().to_string()).filter(|s| !s.is_empty()),
         };
         Self::add_solve_log_entry(&mut transaction, editor, solve_id, event).await?;
@@ -1555,6 +1992,9 @@ impl AppState {
 
         tracing::info!(editor_id = ?editor.id.0, ?solve_id, ?verified, "Updated solve FMC verification.");
 
+        self.release_solve_claim_if_reviewed(solve_id).await?;
+        self.resolve_appeal_if_reviewed(solve_id).await?;
+
         if !editor.dummy {
             self.alert_discord_of_manual_verification(editor, solve_id, EventClass::Fmc)
                 .await;
@@ -1564,7 +2004,7 @@ impl AppState {
             self.alert_discord_to_fmc_record(solve_id).await;
         }
 
-        Ok(())
+        Ok(VerifyOutcome::Applied)
     }
 
     pub async fn get_log_file_contents(
@@ -1585,7 +2025,13 @@ impl AppState {
         require_verified: bool,
     ) -> sqlx::Result<Option<FullSolve>> {
         let mut q = QueryBuilder::new("SELECT *");
-        self.sql_from_verified_solves_in_category(&mut q, Some(puzzle), category, require_verified);
+        self.sql_from_verified_solves_in_category(
+            &mut q,
+            Some(puzzle),
+            category,
+            require_verified,
+            None,
+        );
         if let Some(solver) = solver {
             q.push(" AND solver_id = ").push_bind(solver.0);
         }
@@ -244,7 +244,6 @@ impl AppState {
     pub async fn get_cli_dummy_user(&self) -> Result<User, AppError> {
         self.get_dummy_user_from_name("CLI").await
     }
-    #[allow(unused)]
     pub async fn get_migration_dummy_user(&self) -> Result<User, AppError> {
         self.get_dummy_user_from_name("Migration").await
     }
@@ -0,0 +1,282 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{query, query_as};
+
+use crate::db::{AuditLogEvent, User};
+use crate::webhooks::{WebhookEvent, WebhookMessage};
+use crate::{AppError, AppResult, AppState};
+
+/// Number of delivery attempts to show on the webhooks page.
+const RECENT_DELIVERIES_COUNT: i64 = 100;
+/// Number of days to keep delivery attempts for.
+const DELIVERY_RETENTION_DAYS: i64 = 30;
+
+/// Length of a randomly generated webhook secret.
+const GENERATED_SECRET_LEN: usize = 32;
+
+id_struct!(WebhookId, Webhook);
+/// Outgoing webhook that is sent solve lifecycle events.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Webhook {
+    pub id: WebhookId,
+    /// Human-friendly name. (e.g., "Stats site")
+    pub name: String,
+    /// URL that payloads are POSTed to.
+    pub url: String,
+    /// Secret shared with the receiver, used to sign payloads. It is only
+    /// shown once, when it is generated, so it is never serialized.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// Names of events to send. If empty, all events are sent.
+    pub events: Vec<String>,
+    pub enabled: bool,
+}
+impl Webhook {
+    /// Returns whether the webhook should be sent `event`.
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.iter().any(|e| e == event.name()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookData {
+    pub name: String,
+    pub url: String,
+    /// New secret, or `None` to keep the existing secret (or generate one for
+    /// a new webhook).
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+    pub enabled: bool,
+}
+impl WebhookData {
+    fn validate(&self) -> AppResult {
+        if !self.url.starts_with("https://") && !self.url.starts_with("http://") {
+            return Err(AppError::InvalidQuery(format!(
+                "invalid webhook URL {:?}",
+                self.url,
+            )));
+        }
+        if let Some(event) = self
+            .events
+            .iter()
+            .find(|e| WebhookEvent::from_name(e).is_none())
+        {
+            return Err(AppError::InvalidQuery(format!(
+                "unknown webhook event {event:?}",
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Attempt to deliver a payload to a webhook.
+#[derive(Serialize, Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub webhook_name: String,
+    /// Identifier shared by all attempts to deliver the same payload.
+    pub delivery_id: String,
+    pub event: String,
+    pub solve_id: Option<i32>,
+    /// Attempt number, starting at 1.
+    pub attempt: i32,
+    pub timestamp: DateTime<Utc>,
+    /// HTTP status code of the response, if there was one.
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub success: bool,
+}
+
+impl AppState {
+    /// Returns all webhooks, sorted by ID.
+    pub async fn get_all_webhooks(&self) -> sqlx::Result<Vec<Webhook>> {
+        query_as!(Webhook, "SELECT * FROM Webhook ORDER BY id")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Returns the most recent webhook delivery attempts, newest first.
+    pub async fn get_recent_webhook_deliveries(&self) -> sqlx::Result<Vec<WebhookDelivery>> {
+        query_as!(
+            WebhookDelivery,
+            "SELECT WebhookDelivery.*, Webhook.name AS webhook_name
+                FROM WebhookDelivery
+                JOIN Webhook ON Webhook.id = WebhookDelivery.webhook_id
+                ORDER BY WebhookDelivery.timestamp DESC, WebhookDelivery.id DESC
+                LIMIT $1",
+            RECENT_DELIVERIES_COUNT,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Records an attempt to deliver a message to a webhook.
+    pub async fn add_webhook_delivery(
+        &self,
+        webhook_id: WebhookId,
+        message: &WebhookMessage,
+        attempt: i32,
+        status_code: Option<i32>,
+        error: Option<&str>,
+    ) -> sqlx::Result<()> {
+        query!(
+            "INSERT INTO WebhookDelivery
+                (webhook_id, delivery_id, event, solve_id, attempt, status_code, error, success)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            webhook_id.0,
+            message.delivery_id,
+            message.event.name(),
+            message.solve_id.0,
+            attempt,
+            status_code,
+            error,
+            error.is_none(),
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes delivery attempts older than [`DELIVERY_RETENTION_DAYS`].
+    pub async fn prune_webhook_deliveries(&self) -> sqlx::Result<u64> {
+        Ok(query!(
+            "DELETE FROM WebhookDelivery WHERE timestamp < $1",
+            Utc::now() - TimeDelta::days(DELIVERY_RETENTION_DAYS),
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected())
+    }
+
+    /// Updates an existing webhook.
+    pub async fn update_webhook(
+        &self,
+        editor: &User,
+        id: WebhookId,
+        new_data: WebhookData,
+        audit_log_comment: &str,
+    ) -> AppResult {
+        if !editor.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        new_data.validate()?;
+
+        let WebhookData {
+            name,
+            url,
+            secret,
+            events,
+            enabled,
+        } = new_data.clone();
+
+        let mut transaction = self.pool.begin().await?;
+
+        let old_data = query_as!(Webhook, "SELECT * FROM Webhook WHERE id = $1", id.0)
+            .fetch_one(&mut *transaction)
+            .await?;
+
+        query!(
+            "UPDATE Webhook
+                SET name = $1, url = $2, secret = COALESCE($3, secret), events = $4, enabled = $5
+                WHERE id = $6
+                RETURNING id",
+            name,
+            url,
+            secret,
+            &events,
+            enabled,
+            id.0,
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let mut fields = changed_fields_map!(old_data, new_data, [name, url, events, enabled]);
+        if secret.is_some_and(|s| s != old_data.secret) {
+            // Never write the secret itself to the audit log
+            fields.insert(
+                "secret".to_string(),
+                ["(hidden)".to_string(), "(hidden)".to_string()],
+            );
+        }
+        let event = AuditLogEvent::Updated {
+            object: Some(updated_object!(Webhook, old_data)),
+            fields,
+            comment: Some(audit_log_comment.trim().to_string()).filter(|s| !s.is_empty()),
+        };
+        Self::add_general_log_entry(&mut transaction, editor, event).await?;
+
+        transaction.commit().await?;
+
+        tracing::info!(editor_id = ?editor.id.0, ?id, %url, ?events, enabled, "Updated webhook");
+        let editor_name = editor.to_public().display_name();
+        let domain_name = &*crate::env::DOMAIN_NAME;
+        let msg = format!(
+            "**{editor_name}** updated webhook **{name}**. \
+             See [all webhooks](<{domain_name}/webhooks>) \
+             or [audit log](<{domain_name}/audit-log/general>)."
+        );
+        self.send_private_discord_update(msg).await;
+
+        Ok(())
+    }
+
+    /// Adds a new webhook to the database. Returns its ID and its secret.
+    pub async fn add_webhook(
+        &self,
+        editor: &User,
+        data: WebhookData,
+    ) -> AppResult<(WebhookId, String)> {
+        if !editor.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        data.validate()?;
+
+        let WebhookData {
+            name,
+            url,
+            secret,
+            events,
+            enabled,
+        } = data.clone();
+        let secret = secret.unwrap_or_else(|| crate::util::random_b64_string(GENERATED_SECRET_LEN));
+
+        let mut transaction = self.pool.begin().await?;
+
+        let webhook_id = query!(
+            "INSERT INTO Webhook (name, url, secret, events, enabled)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id",
+            name,
+            url,
+            secret,
+            &events,
+            enabled,
+        )
+        .fetch_one(&mut *transaction)
+        .await?
+        .id;
+
+        let event = AuditLogEvent::Added {
+            object: Some(updated_object!(Webhook, webhook_id, data)),
+            fields: fields_map!(data, [name, url, events, enabled]),
+        };
+        Self::add_general_log_entry(&mut transaction, editor, event).await?;
+
+        transaction.commit().await?;
+
+        tracing::info!(editor_id = ?editor.id.0, ?webhook_id, %url, ?events, enabled, "Added webhook");
+        let editor_name = editor.to_public().display_name();
+        let domain_name = &*crate::env::DOMAIN_NAME;
+        let msg = format!(
+            "**{editor_name}** added a new webhook **{name}**. \
+             See [all webhooks](<{domain_name}/webhooks>) \
+             or [audit log](<{domain_name}/audit-log/general>)."
+        );
+        self.send_private_discord_update(msg).await;
+
+        Ok((WebhookId(webhook_id), secret))
+    }
+}
@@ -1,3 +1,5 @@
+use crate::db::PuzzleId;
+use crate::reverify::ReverifyFilter;
 use crate::{AppResult, PoiseCtx};
 
 /// Display leaderboards version info
@@ -103,3 +105,31 @@ pub async fn update(ctx: PoiseCtx<'_>) -> AppResult {
 
     Ok(())
 }
+
+/// Re-verify auto-verified solves with the current verifier in the background
+///
+/// Optionally only re-verifies solves last verified by a particular verifier
+/// version ("unknown" for solves verified before versions were recorded) or
+/// of a particular puzzle.
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn reverify(
+    ctx: PoiseCtx<'_>,
+    from_version: Option<String>,
+    puzzle_id: Option<PuzzleId>,
+) -> AppResult {
+    let filter = ReverifyFilter {
+        verifier_version: from_version,
+        puzzle_id,
+    };
+    let requested_by = format!("Discord user {}", ctx.author().name);
+    if ctx.data().start_reverify_campaign(filter, requested_by) {
+        ctx.reply(
+            "Re-verifying solves in the background. \
+             The report will be posted in the private updates channel.",
+        )
+        .await?;
+    } else {
+        ctx.reply("Re-verification is already running").await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,330 @@
+//! Public Discord commands for looking up leaderboards.
+
+use itertools::Itertools;
+
+use crate::db::{CategoryQuery, FullSolve, ProgramQuery, Puzzle, User, UserId, VariantQuery};
+use crate::discord::notify::MdSolveInEvent;
+use crate::html::leaderboards::LeaderboardEvent;
+use crate::html::leaderboards::per_puzzle::PuzzleLeaderboardTable;
+use crate::traits::{Linkable, PoiseCtx};
+use crate::{AppError, AppResult, AppState, sy};
+
+/// Maximum number of solves shown by `/leaderboard`.
+const LEADERBOARD_LENGTH: usize = 10;
+
+/// Events that can be named in a category, with their names in `/pb`.
+const EVENTS: [(&str, LeaderboardEvent, &str); 6] = [
+    ("single", LeaderboardEvent::Single, "Single"),
+    ("avg", LeaderboardEvent::Avg, "Average"),
+    ("bld", LeaderboardEvent::Bld, "Blind"),
+    ("oh", LeaderboardEvent::Oh, "One-handed"),
+    ("fmc", LeaderboardEvent::Fmc, "Fewest-moves"),
+    (
+        "fmcca",
+        LeaderboardEvent::FmcCa,
+        "Computer-assisted fewest-moves",
+    ),
+];
+
+/// Show the world record on a puzzle
+#[poise::command(slash_command)]
+pub async fn wr(
+    ctx: PoiseCtx<'_>,
+    #[description = "Puzzle name"]
+    #[autocomplete = "autocomplete_puzzle"]
+    puzzle: String,
+    #[description = "Event, variant, and program, such as \"avg phys\" or \"fmc\""]
+    #[autocomplete = "autocomplete_category"]
+    category: Option<String>,
+) -> AppResult {
+    let state = ctx.data();
+    let puzzle = find_puzzle(state, &puzzle).await?;
+    let category_query = parse_category(state, &puzzle, category.as_deref()).await?;
+    let leaderboard = state
+        .get_event_leaderboard(&puzzle, &category_query, None)
+        .await?;
+
+    let records = leaderboard
+        .iter()
+        .take_while(|ranked| ranked.rank == 1)
+        .map(|ranked| &ranked.solve)
+        .collect_vec();
+    let mut embed = leaderboard_embed(&puzzle, &category_query, records.first().copied());
+    embed = embed.description(if records.is_empty() {
+        "No solves yet".to_string()
+    } else {
+        records
+            .iter()
+            .map(|solve| {
+                format!(
+                    "{} by {} on {}",
+                    md_score(solve, &category_query, true),
+                    solve.solver.md_link(false),
+                    solve.solve_date.date_naive(),
+                )
+            })
+            .join("\n")
+    });
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show the top of a puzzle leaderboard
+#[poise::command(slash_command)]
+pub async fn leaderboard(
+    ctx: PoiseCtx<'_>,
+    #[description = "Puzzle name"]
+    #[autocomplete = "autocomplete_puzzle"]
+    puzzle: String,
+    #[description = "Event, variant, and program, such as \"avg phys\" or \"fmc\""]
+    #[autocomplete = "autocomplete_category"]
+    category: Option<String>,
+) -> AppResult {
+    let state = ctx.data();
+    let puzzle = find_puzzle(state, &puzzle).await?;
+    let category_query = parse_category(state, &puzzle, category.as_deref()).await?;
+    let leaderboard = state
+        .get_event_leaderboard(&puzzle, &category_query, None)
+        .await?;
+
+    let first = leaderboard.first().map(|ranked| &ranked.solve);
+    let mut embed = leaderboard_embed(&puzzle, &category_query, first);
+    embed = embed.description(if leaderboard.is_empty() {
+        "No solves yet".to_string()
+    } else {
+        leaderboard
+            .iter()
+            .take(LEADERBOARD_LENGTH)
+            .map(|ranked| {
+                format!(
+                    "`{:>2}.` {} {}",
+                    ranked.rank,
+                    md_score(&ranked.solve, &category_query, false),
+                    ranked.solve.solver.md_link(false),
+                )
+            })
+            .join("\n")
+    });
+    if !leaderboard.is_empty() {
+        embed = embed.footer(sy::CreateEmbedFooter::new(format!(
+            "{} solvers",
+            leaderboard.len(),
+        )));
+    }
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Show a solver's personal bests on a puzzle
+#[poise::command(slash_command)]
+pub async fn pb(
+    ctx: PoiseCtx<'_>,
+    #[description = "Solver name, user ID, or Discord mention"]
+    #[autocomplete = "autocomplete_solver"]
+    solver: String,
+    #[description = "Puzzle name"]
+    #[autocomplete = "autocomplete_puzzle"]
+    puzzle: String,
+) -> AppResult {
+    let state = ctx.data();
+    let solver = find_solver(state, &solver).await?.to_public();
+    let puzzle = find_puzzle(state, &puzzle).await?;
+
+    let mut embed = sy::CreateEmbed::new()
+        .title(format!("{} on {}", solver.display_name(), puzzle.name))
+        .url(solver.absolute_url());
+    let mut any_pbs = false;
+    for (_, event, name) in EVENTS {
+        let category_query = puzzle_category_query(&puzzle, Some(event), None, None)?;
+        let pb = state
+            .pb_in_category(Some(solver.id), puzzle.id, &category_query, true)
+            .await?;
+        if let Some(solve) = pb {
+            embed = embed.field(name, md_score(&solve, &category_query, false), true);
+            any_pbs = true;
+        }
+    }
+    if !any_pbs {
+        embed = embed.description("No verified solves");
+    }
+    ctx.send(poise::CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+/// Returns an embed titled with the leaderboard for a category, without a
+/// description.
+fn leaderboard_embed(
+    puzzle: &Puzzle,
+    category_query: &CategoryQuery,
+    example_solve: Option<&FullSolve>,
+) -> sy::CreateEmbed {
+    let title = match example_solve {
+        Some(solve) => category_query.event_of_solve(solve).name(),
+        None => puzzle.name.clone(),
+    };
+    sy::CreateEmbed::new()
+        .title(title)
+        .url(puzzle.absolute_url() + &category_query.url_query_params(true))
+}
+
+/// Returns a Markdown link to a solve showing its score in a category.
+fn md_score(solve: &FullSolve, category_query: &CategoryQuery, bold: bool) -> String {
+    let event = category_query.event_of_solve(solve);
+    MdSolveInEvent(solve, event.category.class()).md_link(bold)
+}
+
+/// Returns the puzzle with a name, ignoring case.
+async fn find_puzzle(state: &AppState, name: &str) -> AppResult<Puzzle> {
+    let name = name.trim();
+    state
+        .get_all_puzzles()
+        .await?
+        .into_iter()
+        .find(|puzzle| puzzle.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| AppError::InvalidQuery(format!("no puzzle named {name:?}")))
+}
+
+/// Returns the user with a leaderboard name (ignoring case), a user ID such as
+/// `#12`, or a Discord mention.
+async fn find_solver(state: &AppState, solver: &str) -> AppResult<User> {
+    let solver = solver.trim();
+    if let Ok(id) = solver.parse::<UserId>() {
+        return state.get_user(id).await;
+    }
+    if let Some(discord_id) = solver
+        .strip_prefix("<@")
+        .and_then(|s| s.strip_suffix('>'))
+        .and_then(|s| s.trim_start_matches('!').parse().ok())
+    {
+        return state
+            .get_opt_user_from_discord_id(discord_id)
+            .await?
+            .ok_or(AppError::UserDoesNotExist);
+    }
+    state
+        .get_all_users()
+        .await?
+        .into_iter()
+        .find(|user| {
+            user.name
+                .as_ref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(solver))
+        })
+        .ok_or(AppError::UserDoesNotExist)
+}
+
+/// Parses a category such as `avg phys` or `fmc` into a query for the
+/// leaderboard of `puzzle`.
+///
+/// A category is a space-separated list of an event from [`EVENTS`], a
+/// variant abbreviation, and a program abbreviation, `material`, or
+/// `virtual`, each of which is optional.
+async fn parse_category(
+    state: &AppState,
+    puzzle: &Puzzle,
+    category: Option<&str>,
+) -> AppResult<CategoryQuery> {
+    let variants = state.get_all_variants().await?;
+    let programs = state.get_all_programs().await?;
+
+    let mut event = None;
+    let mut variant = None;
+    let mut program = None;
+    for word in category.unwrap_or_default().split_whitespace() {
+        if let Some(&(_, e, _)) = EVENTS
+            .iter()
+            .find(|(id, _, _)| id.eq_ignore_ascii_case(word))
+        {
+            event = Some(e);
+        } else if let Some(v) = variants.iter().find(|v| v.abbr.eq_ignore_ascii_case(word)) {
+            variant = Some(VariantQuery::Named(v.abbr.clone()));
+        } else if let Some(p) = programs.iter().find(|p| p.abbr.eq_ignore_ascii_case(word)) {
+            program = Some(ProgramQuery::Programs(vec![p.abbr.clone()]));
+        } else if word.eq_ignore_ascii_case("material") {
+            program = Some(ProgramQuery::Material);
+        } else if word.eq_ignore_ascii_case("virtual") {
+            program = Some(ProgramQuery::Virtual);
+        } else {
+            return Err(AppError::InvalidQuery(format!(
+                "unknown event, variant, or program {word:?}"
+            )));
+        }
+    }
+
+    puzzle_category_query(puzzle, event, variant, program)
+}
+
+/// Returns the query for the leaderboard of `puzzle` that the website shows
+/// for an event, variant, and program.
+fn puzzle_category_query(
+    puzzle: &Puzzle,
+    event: Option<LeaderboardEvent>,
+    variant: Option<VariantQuery>,
+    program: Option<ProgramQuery>,
+) -> AppResult<CategoryQuery> {
+    PuzzleLeaderboardTable {
+        id: puzzle.id,
+        event,
+        filters: None,
+        macros: None,
+        variant,
+        program,
+        history: false,
+        as_of: None,
+    }
+    .category_query()
+}
+
+async fn autocomplete_puzzle(
+    ctx: PoiseCtx<'_>,
+    partial: &str,
+) -> impl Iterator<Item = sy::AutocompleteChoice> {
+    let partial = partial.trim().to_lowercase();
+    let puzzles = ctx.data().get_all_puzzles().await.unwrap_or_default();
+    puzzles
+        .into_iter()
+        .filter(move |puzzle| puzzle.name.to_lowercase().contains(&partial))
+        .map(|puzzle| sy::AutocompleteChoice::new(puzzle.name.clone(), puzzle.name))
+}
+
+async fn autocomplete_solver(
+    ctx: PoiseCtx<'_>,
+    partial: &str,
+) -> impl Iterator<Item = sy::AutocompleteChoice> {
+    let partial = partial.trim().to_lowercase();
+    let users = ctx.data().get_all_users().await.unwrap_or_default();
+    users
+        .into_iter()
+        .filter(|user| !user.dummy)
+        .filter_map(|user| Some((user.id, user.name?)))
+        .filter(move |(_, name)| name.to_lowercase().contains(&partial))
+        .map(|(id, name)| sy::AutocompleteChoice::new(format!("{name} ({id})"), id.to_string()))
+}
+
+async fn autocomplete_category(
+    ctx: PoiseCtx<'_>,
+    partial: &str,
+) -> impl Iterator<Item = sy::AutocompleteChoice> {
+    let state = ctx.data();
+    let variants = state.get_all_variants().await.unwrap_or_default();
+    let programs = state.get_all_programs().await.unwrap_or_default();
+
+    let mut categories = vec![];
+    for (id, event, _) in EVENTS {
+        categories.push(id.to_string());
+        if !matches!(event, LeaderboardEvent::Fmc | LeaderboardEvent::FmcCa) {
+            for variant in &variants {
+                categories.push(format!("{id} {}", variant.abbr));
+            }
+        }
+    }
+    for program in &programs {
+        categories.push(format!("single {}", program.abbr));
+    }
+
+    let partial = partial.trim().to_lowercase();
+    categories
+        .into_iter()
+        .filter(move |category| category.to_lowercase().contains(&partial))
+        .map(|category| sy::AutocompleteChoice::new(category.clone(), category))
+}
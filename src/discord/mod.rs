@@ -1,6 +1,7 @@
 use futures::StreamExt;
 
 pub mod admin;
+pub mod lookup;
 pub mod notify;
 pub mod panic;
 pub mod user;
@@ -1,6 +1,7 @@
 use crate::db::{Category, Event, EventClass, FullSolve, SolveId, User};
 use crate::traits::Linkable;
-use crate::{AppResult, AppState};
+use crate::webhooks::{ApiRecord, WebhookEvent};
+use crate::{AppResult, AppState, sy};
 
 pub struct MdSolveTime<'a>(pub &'a FullSolve);
 impl Linkable for MdSolveTime<'_> {
@@ -46,11 +47,16 @@ impl Linkable for MdSolveInEvent<'_> {
 
 impl AppState {
     pub async fn send_private_discord_update(&self, message: String) {
+        self.send_private_discord_message(sy::CreateMessage::new().content(message))
+            .await;
+    }
+
+    async fn send_private_discord_message(&self, message: sy::CreateMessage) {
         let Ok(discord) = self.try_discord() else {
             return;
         };
         let result = crate::env::PRIVATE_UPDATES_CHANNEL_ID
-            .say(discord, message)
+            .send_message(discord, message)
             .await;
 
         if let Err(err) = result {
@@ -96,15 +102,32 @@ impl AppState {
         } else {
             "Solve submitted for manual verification"
         };
+        if !updated {
+            self.send_webhooks(WebhookEvent::Submitted, &solve, None, None)
+                .await;
+        }
+
         let solve_markdown = solve.markdown_with_puzzle_and_solver_name();
         let by_whom = if editor.id == solve.solver.id {
             String::new()
         } else {
             format!(" by {}", editor.to_public().md_link(false))
         };
+        let claim = match self.get_solve_claim(solve_id).await {
+            Ok(Some(claim)) => format!(" (claimed by {})", claim.moderator.md_link(false)),
+            _ => String::new(),
+        };
 
-        self.send_private_discord_update(format!("{emoji} {event}: {solve_markdown}{by_whom}"))
-            .await;
+        // Moderators can verify the solve directly from the message.
+        let buttons = if will_be_auto_verified {
+            vec![]
+        } else {
+            crate::discord::verify::verification_buttons(&solve)
+        };
+        let message = sy::CreateMessage::new()
+            .content(format!("{emoji} {event}: {solve_markdown}{by_whom}{claim}"))
+            .components(buttons);
+        self.send_private_discord_message(message).await;
     }
 
     pub async fn alert_discord_of_manual_verification(
@@ -128,12 +151,23 @@ impl AppState {
             EventClass::Speed => solve.speed_verified,
             EventClass::Fmc => solve.fmc_verified,
         };
-        let (emoji, verbed) = match status {
-            Some(true) => (":ballot_box_with_check:", "accepted"),
-            Some(false) => (":x:", "rejected"),
-            None => (":new_moon_with_face:", "unverified"),
+        let (emoji, verbed, webhook_event) = match status {
+            Some(true) => (
+                ":ballot_box_with_check:",
+                "accepted",
+                WebhookEvent::Verified,
+            ),
+            Some(false) => (":x:", "rejected", WebhookEvent::Rejected),
+            None => (
+                ":new_moon_with_face:",
+                "unverified",
+                WebhookEvent::Unverified,
+            ),
         };
 
+        self.send_webhooks(webhook_event, &solve, Some(event_class), None)
+            .await;
+
         let editor_name = editor.to_public().display_name();
 
         self.send_private_discord_update(format!(
@@ -153,6 +187,9 @@ impl AppState {
             return;
         };
 
+        self.send_webhooks(WebhookEvent::Autoverified, &solve, None, None)
+            .await;
+
         let solve_markdown = solve.markdown_with_puzzle_and_solver_name();
 
         let needs_manual_review = solve.pending_review();
@@ -172,56 +209,78 @@ impl AppState {
             .await;
     }
 
-    pub async fn alert_discord_to_speed_record(&self, solve_id: SolveId) {
-        // async block to mimic try block
-        let send_result = async {
-            let discord = self.try_discord()?;
-
-            let solve = self.get_solve(solve_id).await?;
-
-            let mut wr_event = None;
-            let mut displaced_wr = None;
-
-            let event = solve.speed_event();
-
-            let mut primary_event = event.clone();
-            if let Category::Speed {
-                filters, macros, ..
-            } = &mut primary_event.category
-            {
-                *filters = solve.puzzle.primary_filters;
-                *macros = solve.puzzle.primary_macros;
-            }
+    /// Returns the event in which a speedsolve would be a world record, along
+    /// with the record that it would displace. The primary category is
+    /// preferred if the solve counts for it.
+    pub async fn speed_record_event(
+        &self,
+        solve: &FullSolve,
+    ) -> sqlx::Result<Option<(Event, Option<FullSolve>)>> {
+        let event = solve.speed_event();
+
+        let mut primary_event = event.clone();
+        if let Category::Speed {
+            filters, macros, ..
+        } = &mut primary_event.category
+        {
+            *filters = solve.puzzle.primary_filters;
+            *macros = solve.puzzle.primary_macros;
+        }
 
-            // Prefer reporting for the primary category
-            if solve.counts_for_primary_speed_category() {
-                if let Some(old_wr) = self.world_record_excluding(&primary_event, &solve).await? {
-                    if solve.speed_cs <= old_wr.speed_cs {
-                        wr_event = Some(&primary_event);
-                        displaced_wr = Some(old_wr);
-                    }
-                } else {
-                    wr_event = Some(&primary_event);
+        // Prefer reporting for the primary category
+        if solve.counts_for_primary_speed_category() {
+            match self.world_record_excluding(&primary_event, solve).await? {
+                Some(old_wr) if solve.speed_cs <= old_wr.speed_cs => {
+                    return Ok(Some((primary_event, Some(old_wr))));
                 }
+                Some(_) => (),
+                None => return Ok(Some((primary_event, None))),
             }
-            // If it's not a WR in the primary category, try reporting for its
-            // own category
-            if wr_event.is_none() {
-                if let Some(old_wr) = self.world_record_excluding(&event, &solve).await? {
-                    if solve.speed_cs <= old_wr.speed_cs {
-                        wr_event = Some(&event);
-                        displaced_wr = Some(old_wr);
-                    }
-                } else {
-                    wr_event = Some(&event);
-                }
+        }
+        // If it's not a WR in the primary category, try reporting for its
+        // own category
+        match self.world_record_excluding(&event, solve).await? {
+            Some(old_wr) if solve.speed_cs <= old_wr.speed_cs => Ok(Some((event, Some(old_wr)))),
+            Some(_) => Ok(None),
+            None => Ok(Some((event, None))),
+        }
+    }
+
+    /// Returns the event in which a fewest-moves solve would be a world
+    /// record, along with the record that it would displace.
+    pub async fn fmc_record_event(
+        &self,
+        solve: &FullSolve,
+    ) -> sqlx::Result<Option<(Event, Option<FullSolve>)>> {
+        let event = solve.fmc_event();
+        match self.world_record_excluding(&event, solve).await? {
+            Some(old_wr) if solve.move_count <= old_wr.move_count => {
+                Ok(Some((event, Some(old_wr))))
             }
+            Some(_) => Ok(None),
+            None => Ok(Some((event, None))),
+        }
+    }
 
-            let Some(wr_event) = wr_event else {
+    pub async fn alert_discord_to_speed_record(&self, solve_id: SolveId) {
+        // async block to mimic try block
+        let send_result = async {
+            let solve = self.get_solve(solve_id).await?;
+
+            let Some((wr_event, displaced_wr)) = self.speed_record_event(&solve).await? else {
                 return Ok(()); // not a world record; nothing to report
             };
 
-            let msg = build_wr_msg(&solve, displaced_wr.as_ref(), wr_event);
+            self.send_webhooks(
+                WebhookEvent::Record,
+                &solve,
+                Some(EventClass::Speed),
+                Some(ApiRecord::new(&wr_event, displaced_wr.as_ref())),
+            )
+            .await;
+
+            let discord = self.try_discord()?;
+            let msg = build_wr_msg(&solve, displaced_wr.as_ref(), &wr_event);
 
             crate::env::PUBLIC_UPDATES_CHANNEL_ID
                 .say(discord, msg)
@@ -239,22 +298,21 @@ impl AppState {
     pub async fn alert_discord_to_fmc_record(&self, solve_id: SolveId) {
         // async block to mimic try block
         let send_result: AppResult = async {
-            let discord = self.try_discord()?;
-
             let solve = self.get_solve(solve_id).await?;
 
-            let mut displaced_wr = None;
-
-            let event = solve.fmc_event();
+            let Some((event, displaced_wr)) = self.fmc_record_event(&solve).await? else {
+                return Ok(()); // not a world record; nothing to report
+            };
 
-            if let Some(old_wr) = self.world_record_excluding(&event, &solve).await? {
-                if solve.move_count <= old_wr.move_count {
-                    displaced_wr = Some(old_wr);
-                } else {
-                    return Ok(()); // not a world record; nothing to report
-                }
-            }
+            self.send_webhooks(
+                WebhookEvent::Record,
+                &solve,
+                Some(EventClass::Fmc),
+                Some(ApiRecord::new(&event, displaced_wr.as_ref())),
+            )
+            .await;
 
+            let discord = self.try_discord()?;
             let msg = build_wr_msg(&solve, displaced_wr.as_ref(), &event);
             crate::env::PUBLIC_UPDATES_CHANNEL_ID
                 .say(discord, msg)
@@ -1,6 +1,10 @@
-use crate::AppResult;
-use crate::db::SolveId;
+//! Discord commands and buttons for verifying solves.
+
+use itertools::Itertools;
+
+use crate::db::{EventClass, FullSolve, RejectionReason, SolveId, User, VerifyOutcome};
 use crate::traits::{Linkable, PoiseCtx, PoiseCtxExt};
+use crate::{AppError, AppResult, AppState, sy};
 
 #[poise::command(slash_command, subcommands("accept_speed", "accept_fmc"))]
 pub async fn accept(_ctx: PoiseCtx<'_>) -> AppResult {
@@ -9,12 +13,12 @@ pub async fn accept(_ctx: PoiseCtx<'_>) -> AppResult {
 /// Accept a speed submission
 #[poise::command(slash_command, rename = "speed")]
 async fn accept_speed(ctx: PoiseCtx<'_>, solve_id: SolveId, comment: Option<String>) -> AppResult {
-    speed_verify(ctx, solve_id, Some(true), "Accepted", comment).await
+    speed_verify(ctx, solve_id, Some(true), None, "Accepted", comment).await
 }
 /// Accept an FMC submission
 #[poise::command(slash_command, rename = "fmc")]
 async fn accept_fmc(ctx: PoiseCtx<'_>, solve_id: SolveId, comment: Option<String>) -> AppResult {
-    fmc_verify(ctx, solve_id, Some(true), "Accepted", comment).await
+    fmc_verify(ctx, solve_id, Some(true), None, "Accepted", comment).await
 }
 
 #[poise::command(slash_command, subcommands("reject_speed", "reject_fmc"))]
@@ -23,13 +27,23 @@ pub async fn reject(_ctx: PoiseCtx<'_>) -> AppResult {
 }
 /// Reject a speed submission
 #[poise::command(slash_command, rename = "speed")]
-async fn reject_speed(ctx: PoiseCtx<'_>, solve_id: SolveId, comment: Option<String>) -> AppResult {
-    speed_verify(ctx, solve_id, Some(false), "Rejected", comment).await
+async fn reject_speed(
+    ctx: PoiseCtx<'_>,
+    solve_id: SolveId,
+    reason: Option<RejectionReason>,
+    comment: Option<String>,
+) -> AppResult {
+    speed_verify(ctx, solve_id, Some(false), reason, "Rejected", comment).await
 }
 /// Reject an FMC submission
 #[poise::command(slash_command, rename = "fmc")]
-async fn reject_fmc(ctx: PoiseCtx<'_>, solve_id: SolveId, comment: Option<String>) -> AppResult {
-    fmc_verify(ctx, solve_id, Some(false), "Rejected", comment).await
+async fn reject_fmc(
+    ctx: PoiseCtx<'_>,
+    solve_id: SolveId,
+    reason: Option<RejectionReason>,
+    comment: Option<String>,
+) -> AppResult {
+    fmc_verify(ctx, solve_id, Some(false), reason, "Rejected", comment).await
 }
 
 #[poise::command(slash_command, subcommands("unverify_speed", "unverify_fmc"))]
@@ -43,53 +57,443 @@ async fn unverify_speed(
     solve_id: SolveId,
     comment: Option<String>,
 ) -> AppResult {
-    speed_verify(ctx, solve_id, None, "Unverified", comment).await
+    speed_verify(ctx, solve_id, None, None, "Unverified", comment).await
 }
 /// Unverify an FMC submission
 #[poise::command(slash_command, rename = "fmc")]
 async fn unverify_fmc(ctx: PoiseCtx<'_>, solve_id: SolveId, comment: Option<String>) -> AppResult {
-    fmc_verify(ctx, solve_id, None, "Unverified", comment).await
+    fmc_verify(ctx, solve_id, None, None, "Unverified", comment).await
 }
 
 async fn speed_verify(
     ctx: PoiseCtx<'_>,
     solve_id: SolveId,
     verify: Option<bool>,
+    rejection_reason: Option<RejectionReason>,
     verbed: &str,
     comment: Option<String>,
 ) -> AppResult {
-    let editor = ctx.author_user().await?;
-    let state = ctx.data();
-    let solve = state.get_solve(solve_id).await?;
-    state
-        .verify_speed(&editor, solve_id, verify, &comment.unwrap_or_default())
-        .await?;
-    ctx.say(format!(
-        "{verbed} speed {} by {}",
-        solve_id.md_link(false),
-        solve.solver.md_link(false)
-    ))
-    .await?;
-    Ok(())
+    verify_and_reply(
+        ctx,
+        solve_id,
+        EventClass::Speed,
+        verify,
+        rejection_reason,
+        verbed,
+        comment,
+    )
+    .await
 }
 async fn fmc_verify(
     ctx: PoiseCtx<'_>,
     solve_id: SolveId,
     verify: Option<bool>,
+    rejection_reason: Option<RejectionReason>,
+    verbed: &str,
+    comment: Option<String>,
+) -> AppResult {
+    verify_and_reply(
+        ctx,
+        solve_id,
+        EventClass::Fmc,
+        verify,
+        rejection_reason,
+        verbed,
+        comment,
+    )
+    .await
+}
+
+async fn verify_and_reply(
+    ctx: PoiseCtx<'_>,
+    solve_id: SolveId,
+    event_class: EventClass,
+    verify: Option<bool>,
+    rejection_reason: Option<RejectionReason>,
     verbed: &str,
     comment: Option<String>,
 ) -> AppResult {
     let editor = ctx.author_user().await?;
     let state = ctx.data();
     let solve = state.get_solve(solve_id).await?;
-    state
-        .verify_fmc(&editor, solve_id, verify, &comment.unwrap_or_default())
+    let comment = comment.unwrap_or_default();
+    let outcome = verify_solve(
+        state,
+        &editor,
+        &solve,
+        event_class,
+        verify,
+        rejection_reason,
+        &comment,
+    )
+    .await?;
+    if outcome == VerifyOutcome::AwaitingSecondModerator {
+        ctx.say(format!(
+            "Approved {} record {} by {}. A second moderator must accept it too.",
+            event_class.long_name(),
+            solve_id.md_link(false),
+            solve.solver.md_link(false),
+        ))
         .await?;
+        return Ok(());
+    }
     ctx.say(format!(
-        "{verbed} fewest-moves {} by {}",
+        "{verbed} {} {} by {}",
+        event_class.long_name(),
         solve_id.md_link(false),
         solve.solver.md_link(false),
     ))
     .await?;
     Ok(())
 }
+
+/// Accepts, rejects, or unverifies a solve in an event class.
+async fn verify_solve(
+    state: &AppState,
+    editor: &User,
+    solve: &FullSolve,
+    event_class: EventClass,
+    verify: Option<bool>,
+    rejection_reason: Option<RejectionReason>,
+    comment: &str,
+) -> AppResult<VerifyOutcome> {
+    match event_class {
+        EventClass::Speed => {
+            state
+                .verify_speed_with_reason(editor, solve.id, verify, rejection_reason, comment)
+                .await
+        }
+        EventClass::Fmc => {
+            state
+                .verify_fmc_with_reason(editor, solve.id, verify, rejection_reason, comment)
+                .await
+        }
+    }
+}
+
+/// Accept or reject button on a solve alert in the private updates channel.
+///
+/// The custom ID of the button, and of the modal that asks for a rejection
+/// reason, is `verify:{accept|reject}:{speed|fmc}:{solve_id}`.
+#[derive(Debug, Copy, Clone)]
+struct VerifyButton {
+    solve_id: SolveId,
+    event_class: EventClass,
+    accept: bool,
+}
+impl VerifyButton {
+    fn custom_id(self) -> String {
+        let action = if self.accept { "accept" } else { "reject" };
+        let event_class = match self.event_class {
+            EventClass::Speed => "speed",
+            EventClass::Fmc => "fmc",
+        };
+        format!("verify:{action}:{event_class}:{}", self.solve_id.0)
+    }
+
+    fn from_custom_id(custom_id: &str) -> Option<Self> {
+        let mut parts = custom_id.strip_prefix("verify:")?.split(':');
+        let accept = match parts.next()? {
+            "accept" => true,
+            "reject" => false,
+            _ => return None,
+        };
+        let event_class = match parts.next()? {
+            "speed" => EventClass::Speed,
+            "fmc" => EventClass::Fmc,
+            _ => return None,
+        };
+        let solve_id = SolveId(parts.next()?.parse().ok()?);
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            solve_id,
+            event_class,
+            accept,
+        })
+    }
+
+    fn event_class_name(self) -> &'static str {
+        match self.event_class {
+            EventClass::Speed => "speed",
+            EventClass::Fmc => "FMC",
+        }
+    }
+
+    /// Label that starts the outcome line for the event class in the
+    /// original message.
+    fn outcome_label(self) -> &'static str {
+        match self.event_class {
+            EventClass::Speed => "**Speed:**",
+            EventClass::Fmc => "**FMC:**",
+        }
+    }
+
+    fn build(self) -> sy::CreateButton {
+        let (verb, style) = match self.accept {
+            true => ("Accept", sy::ButtonStyle::Success),
+            false => ("Reject", sy::ButtonStyle::Danger),
+        };
+        sy::CreateButton::new(self.custom_id())
+            .label(format!("{verb} {}", self.event_class_name()))
+            .style(style)
+    }
+}
+
+/// Returns Accept and Reject buttons for each event class of a solve that is
+/// waiting for verification.
+pub fn verification_buttons(solve: &FullSolve) -> Vec<sy::CreateActionRow> {
+    let mut buttons = vec![];
+    let pending = [
+        (
+            EventClass::Speed,
+            solve.speed_cs.is_some() && solve.speed_verified.is_none(),
+        ),
+        (
+            EventClass::Fmc,
+            solve.move_count.is_some() && solve.fmc_verified.is_none(),
+        ),
+    ];
+    for (event_class, is_pending) in pending {
+        if is_pending {
+            for accept in [true, false] {
+                let button = VerifyButton {
+                    solve_id: solve.id,
+                    event_class,
+                    accept,
+                };
+                buttons.push(button.build());
+            }
+        }
+    }
+    if buttons.is_empty() {
+        vec![]
+    } else {
+        vec![sy::CreateActionRow::Buttons(buttons)]
+    }
+}
+
+/// Handles a press of a button from [`verification_buttons()`], or the
+/// submission of the modal that asks for a rejection reason.
+///
+/// Accepting a solve or submitting a rejection acknowledges the interaction
+/// right away, since verifying may take longer than Discord waits for a
+/// response, and then edits the original message to show the outcome. Errors
+/// are reported only to the moderator.
+pub async fn handle_interaction(
+    ctx: &sy::Context,
+    state: &AppState,
+    interaction: &sy::Interaction,
+) -> AppResult {
+    match interaction {
+        sy::Interaction::Component(component) => {
+            let Some(button) = VerifyButton::from_custom_id(&component.data.custom_id) else {
+                return Ok(());
+            };
+            if !button.accept {
+                let modal = sy::CreateInteractionResponse::Modal(rejection_modal(button));
+                component.create_response(ctx, modal).await?;
+                return Ok(());
+            }
+            component
+                .create_response(ctx, sy::CreateInteractionResponse::Acknowledge)
+                .await?;
+            let original_content = &component.message.content;
+            match decide(state, component.user.id, original_content, button, None, "").await {
+                Ok(edit) => {
+                    component.edit_response(ctx, edit).await?;
+                }
+                Err(err) => {
+                    component.create_followup(ctx, error_followup(&err)).await?;
+                }
+            }
+        }
+        sy::Interaction::Modal(modal) => {
+            let Some(button) = VerifyButton::from_custom_id(&modal.data.custom_id) else {
+                return Ok(());
+            };
+            let input = |custom_id: &str| {
+                modal
+                    .data
+                    .components
+                    .iter()
+                    .flat_map(|row| &row.components)
+                    .find_map(|component| match component {
+                        sy::ActionRowComponent::InputText(input)
+                            if input.custom_id == custom_id =>
+                        {
+                            input.value.clone()
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or_default()
+            };
+            let reason_text = input("reason");
+            let mut comment = input("comment");
+            // Keep reasons that aren't in the catalogue in the comment.
+            let reason = match RejectionReason::from_id_or_description(&reason_text) {
+                Some(reason) => Some(reason),
+                None if reason_text.trim().is_empty() => None,
+                None => {
+                    comment = format!("{}\n{comment}", reason_text.trim());
+                    Some(RejectionReason::Other)
+                }
+            };
+            let original_content = modal
+                .message
+                .as_ref()
+                .map(|message| message.content.clone())
+                .unwrap_or_default();
+            modal
+                .create_response(ctx, sy::CreateInteractionResponse::Acknowledge)
+                .await?;
+            let result = decide(
+                state,
+                modal.user.id,
+                &original_content,
+                button,
+                reason,
+                &comment,
+            )
+            .await;
+            match result {
+                Ok(edit) => {
+                    modal.edit_response(ctx, edit).await?;
+                }
+                Err(err) => {
+                    modal.create_followup(ctx, error_followup(&err)).await?;
+                }
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Returns the modal that asks for a reason when a moderator presses a Reject
+/// button.
+fn rejection_modal(button: VerifyButton) -> sy::CreateModal {
+    let reason_input = sy::CreateInputText::new(sy::InputTextStyle::Short, "Reason", "reason")
+        .placeholder(RejectionReason::VideoMissing.description())
+        .required(false);
+    let comment_input =
+        sy::CreateInputText::new(sy::InputTextStyle::Paragraph, "Comment", "comment")
+            .required(false);
+    sy::CreateModal::new(
+        button.custom_id(),
+        format!(
+            "Reject {} solve #{}",
+            button.event_class_name(),
+            button.solve_id.0
+        ),
+    )
+    .components(vec![
+        sy::CreateActionRow::InputText(reason_input),
+        sy::CreateActionRow::InputText(comment_input),
+    ])
+}
+
+/// Returns the message that reports an error only to the moderator who
+/// pressed a button.
+fn error_followup(err: &AppError) -> sy::CreateInteractionResponseFollowup {
+    sy::CreateInteractionResponseFollowup::new()
+        .content(err.message())
+        .ephemeral(true)
+}
+
+/// Verifies a solve on behalf of the Discord user who pressed a button, and
+/// returns the edit to the original message that shows the outcome.
+async fn decide(
+    state: &AppState,
+    discord_user_id: sy::UserId,
+    original_content: &str,
+    button: VerifyButton,
+    rejection_reason: Option<RejectionReason>,
+    comment: &str,
+) -> AppResult<sy::EditInteractionResponse> {
+    let editor = state
+        .get_opt_user_from_discord_id(discord_user_id.get())
+        .await?
+        .ok_or(AppError::NotLoggedIn)?;
+    let solve = state.get_solve(button.solve_id).await?;
+    let verify = Some(button.accept);
+    let verify_outcome = verify_solve(
+        state,
+        &editor,
+        &solve,
+        button.event_class,
+        verify,
+        rejection_reason,
+        comment,
+    )
+    .await?;
+
+    let label = button.outcome_label();
+    let moderator = editor.to_public().md_link(false);
+    let outcome = if verify_outcome == VerifyOutcome::AwaitingSecondModerator {
+        format!(
+            ":trophy: {label} {moderator} approved the record. \
+             A second moderator must accept it too.",
+        )
+    } else if button.accept {
+        format!(":ballot_box_with_check: {label} accepted by {moderator}")
+    } else {
+        let reason = rejection_reason
+            .map(|reason| format!(" ({})", reason.description()))
+            .unwrap_or_default();
+        format!(":x: {label} rejected by {moderator}{reason}")
+    };
+
+    let solve = state.get_solve(button.solve_id).await?;
+    Ok(sy::EditInteractionResponse::new()
+        .content(replace_outcome_line(original_content, label, &outcome))
+        .components(verification_buttons(&solve)))
+}
+
+/// Returns `content` with the outcome line that contains `label` replaced by
+/// `outcome`, so that each event class shows only its latest decision.
+fn replace_outcome_line(content: &str, label: &str, outcome: &str) -> String {
+    content
+        .lines()
+        .filter(|line| !line.contains(label))
+        .chain([outcome])
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_outcome_line() {
+        let alert = "Solve #1 needs review";
+        let accepted = replace_outcome_line(alert, "**Speed:**", "**Speed:** accepted");
+        assert_eq!(accepted, "Solve #1 needs review\n**Speed:** accepted");
+
+        let both = replace_outcome_line(&accepted, "**FMC:**", "**FMC:** rejected");
+        assert_eq!(
+            both,
+            "Solve #1 needs review\n**Speed:** accepted\n**FMC:** rejected",
+        );
+
+        let replaced = replace_outcome_line(&both, "**Speed:**", "**Speed:** approved");
+        assert_eq!(
+            replaced,
+            "Solve #1 needs review\n**FMC:** rejected\n**Speed:** approved",
+        );
+    }
+
+    #[test]
+    fn test_verify_button_custom_id() {
+        let button = VerifyButton {
+            solve_id: SolveId(42),
+            event_class: EventClass::Fmc,
+            accept: false,
+        };
+        let parsed = VerifyButton::from_custom_id(&button.custom_id()).expect("invalid custom ID");
+        assert_eq!(parsed.solve_id, SolveId(42));
+        assert!(!parsed.accept);
+        assert!(VerifyButton::from_custom_id("verify:accept:speed:42:1").is_none());
+    }
+}
@@ -13,6 +13,39 @@ where
         .unwrap_or_else(|_| panic!("invalid value for {name} environment variable"))
 }
 
+fn parse_env_var_or<T: std::str::FromStr>(name: &str, default: T) -> T
+where
+    T::Err: std::fmt::Debug,
+{
+    match dotenvy::var(name) {
+        Ok(s) => s
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid value for {name} environment variable")),
+        Err(_) => default,
+    }
+}
+
+/// Parses a comma-separated list of `ABBR=path` pairs, with each program
+/// abbreviation converted to uppercase.
+fn parse_verifier_list(s: &str) -> Result<Vec<(String, String)>, String> {
+    let mut ret: Vec<(String, String)> = vec![];
+    for pair in s.split(',').filter(|pair| !pair.trim().is_empty()) {
+        let (abbr, path) = pair
+            .split_once('=')
+            .map(|(abbr, path)| (abbr.trim().to_uppercase(), path.trim()))
+            .filter(|(abbr, path)| !abbr.is_empty() && !path.is_empty())
+            .ok_or_else(|| format!("expected ABBR=path, got {:?}", pair.trim()))?;
+        if abbr == "HSC2" {
+            return Err("HSC2 verifier is set by HSC2_PATH".to_string());
+        }
+        if ret.iter().any(|(existing, _)| *existing == abbr) {
+            return Err(format!("duplicate verifier for {abbr}"));
+        }
+        ret.push((abbr, path.to_string()));
+    }
+    Ok(ret)
+}
+
 lazy_static! {
     /// Logging configuration.
     pub static ref RUST_LOG: String = get_env_var("RUST_LOG");
@@ -53,4 +86,41 @@ lazy_static! {
 
     /// Path to Hyperspeedcube 2 executable.
     pub static ref HSC2_PATH: String = get_env_var("HSC2_PATH");
+    /// Verifiers for programs other than Hyperspeedcube 2, as a
+    /// comma-separated list of `ABBR=path` pairs. Each executable must accept
+    /// the same arguments and write the same output as Hyperspeedcube 2.
+    /// Solves from programs without a verifier are left for manual review.
+    /// Example: `MC4D=/opt/mc4d-verify/mc4d-verify`
+    pub static ref EXTRA_VERIFIERS: Vec<(String, String)> =
+        parse_verifier_list(&dotenvy::var("EXTRA_VERIFIERS").unwrap_or_default())
+            .unwrap_or_else(|e| panic!("invalid value for EXTRA_VERIFIERS environment variable: {e}"));
+    /// Number of solves to auto-verify concurrently. Defaults to 2.
+    pub static ref AUTOVERIFY_WORKERS: usize = parse_env_var_or("AUTOVERIFY_WORKERS", 2);
+
+    /// Number of minutes after which a moderator's claim on a pending solve
+    /// expires. Defaults to 30.
+    pub static ref SOLVE_CLAIM_TIMEOUT_MINUTES: i64 = parse_env_var_or("SOLVE_CLAIM_TIMEOUT_MINUTES", 30);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verifier_list() {
+        assert_eq!(parse_verifier_list(""), Ok(vec![]));
+        assert_eq!(
+            parse_verifier_list(" mc4d = /opt/mc4d-verify , MPU=/opt/mpu-verify,"),
+            Ok(vec![
+                ("MC4D".to_string(), "/opt/mc4d-verify".to_string()),
+                ("MPU".to_string(), "/opt/mpu-verify".to_string()),
+            ]),
+        );
+
+        assert!(parse_verifier_list("MC4D").is_err());
+        assert!(parse_verifier_list("MC4D=").is_err());
+        assert!(parse_verifier_list("=/opt/mc4d-verify").is_err());
+        assert!(parse_verifier_list("hsc2=/opt/hsc").is_err());
+        assert!(parse_verifier_list("MC4D=/a,mc4d=/b").is_err());
+    }
 }
@@ -22,7 +22,7 @@ pub enum AppError {
     JsonError(serde_json::Error),
     UserDoesNotExist,
     AuthenticationTimeout,
-    SolveVerificationTimeout,
+    SolveVerificationTimeout(String),
     InvalidOtp,
     InvalidToken,
     DiscordMemberNotFound,
@@ -40,7 +40,12 @@ pub enum AppError {
     FailedCaptcha,
     TemporarilyBlocked,
     VerificationFailed(String),
+    NoVerifierForProgram(String),
     PuzzleIsNotLeaderboardEligible(String),
+    SolveClaimedByOtherModerator(String),
+    SolveNotPending,
+    RecordNeedsSecondModerator,
+    CannotAppeal(String),
 
     #[allow(dead_code)]
     Other(String),
@@ -61,7 +66,12 @@ impl AppError {
             Self::JsonError(err) => format!("JSON error: {err}"),
             Self::UserDoesNotExist => "User does not exist".to_string(),
             Self::AuthenticationTimeout => "User took too long to authenticate".to_string(),
-            Self::SolveVerificationTimeout => "Solve took too long to verify".to_string(),
+            Self::SolveVerificationTimeout(stderr) if stderr.is_empty() => {
+                "Solve took too long to verify".to_string()
+            }
+            Self::SolveVerificationTimeout(stderr) => {
+                format!("Solve took too long to verify. stderr:\n{stderr}")
+            }
             Self::InvalidOtp => "Invalid OTP or device code".to_string(),
             Self::InvalidToken => "Invalid token".to_string(),
             Self::DiscordMemberNotFound => "Discord member not found".to_string(),
@@ -79,9 +89,20 @@ impl AppError {
             Self::FailedCaptcha => "Failed captcha".to_string(),
             Self::TemporarilyBlocked => "Functionality is temporarily disabled".to_string(),
             Self::VerificationFailed(reason) => format!("Verification failed: {reason}"),
+            Self::NoVerifierForProgram(abbr) => {
+                format!("No verifier for program {abbr}; solve needs manual review")
+            }
             Self::PuzzleIsNotLeaderboardEligible(hsc_id) => {
                 format!("Puzzle {hsc_id:?} is not leaderboard-eligible")
             }
+            Self::SolveClaimedByOtherModerator(name) => {
+                format!("Solve is being reviewed by {name}")
+            }
+            Self::SolveNotPending => "Solve is not pending review".to_string(),
+            Self::RecordNeedsSecondModerator => {
+                "New records must be accepted by a second moderator".to_string()
+            }
+            Self::CannotAppeal(reason) => format!("Cannot appeal: {reason}"),
 
             Self::Other(msg) => msg.to_string(),
         }
@@ -99,7 +120,7 @@ impl AppError {
             Self::JsonError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::UserDoesNotExist => StatusCode::UNAUTHORIZED,
             Self::AuthenticationTimeout => StatusCode::UNAUTHORIZED,
-            Self::SolveVerificationTimeout => StatusCode::BAD_REQUEST,
+            Self::SolveVerificationTimeout(_) => StatusCode::BAD_REQUEST,
             Self::InvalidOtp => StatusCode::UNAUTHORIZED,
             Self::InvalidToken => StatusCode::UNAUTHORIZED,
             Self::DiscordMemberNotFound => StatusCode::BAD_REQUEST,
@@ -117,7 +138,12 @@ impl AppError {
             Self::FailedCaptcha => StatusCode::BAD_REQUEST,
             Self::TemporarilyBlocked => StatusCode::SERVICE_UNAVAILABLE,
             Self::VerificationFailed(_) => StatusCode::INTERNAL_SERVER_ERROR, // should never be visible
+            Self::NoVerifierForProgram(_) => StatusCode::BAD_REQUEST,
             Self::PuzzleIsNotLeaderboardEligible(_) => StatusCode::BAD_REQUEST,
+            Self::SolveClaimedByOtherModerator(_) => StatusCode::CONFLICT,
+            Self::SolveNotPending => StatusCode::CONFLICT,
+            Self::RecordNeedsSecondModerator => StatusCode::CONFLICT,
+            Self::CannotAppeal(_) => StatusCode::BAD_REQUEST,
 
             Self::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
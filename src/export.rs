@@ -0,0 +1,309 @@
+//! Bulk export of verified solves.
+//!
+//! The column names in this module are part of the public API, so they must
+//! not be renamed or removed. See `docs/api.md` for documentation.
+
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+
+use crate::db::{FullSolve, User};
+use crate::traits::{Linkable, RequestBody};
+use crate::{AppError, AppState};
+
+/// Column names, in order, and the value of each column for a solve.
+const COLUMNS: &[(&str, fn(&ExportedSolve) -> String)] = &[
+    ("id", |s| s.id.to_string()),
+    ("url", |s| s.url.clone()),
+    ("solve_date", |s| s.solve_date.to_rfc3339()),
+    ("upload_date", |s| s.upload_date.to_rfc3339()),
+    ("puzzle_id", |s| s.puzzle_id.to_string()),
+    ("puzzle_name", |s| s.puzzle_name.clone()),
+    ("variant_id", |s| opt(&s.variant_id)),
+    ("variant_name", |s| opt(&s.variant_name)),
+    ("program_id", |s| s.program_id.to_string()),
+    ("program_abbr", |s| s.program_abbr.clone()),
+    ("program_material", |s| s.program_material.to_string()),
+    ("average", |s| s.average.to_string()),
+    ("blind", |s| s.blind.to_string()),
+    ("filters", |s| s.filters.to_string()),
+    ("macros", |s| s.macros.to_string()),
+    ("one_handed", |s| s.one_handed.to_string()),
+    ("computer_assisted", |s| s.computer_assisted.to_string()),
+    ("speed_cs", |s| opt(&s.speed_cs)),
+    ("memo_cs", |s| opt(&s.memo_cs)),
+    ("speed_verified", |s| opt(&s.speed_verified)),
+    ("move_count", |s| opt(&s.move_count)),
+    ("fmc_verified", |s| opt(&s.fmc_verified)),
+    ("has_log_file", |s| s.has_log_file.to_string()),
+    ("video_url", |s| opt(&s.video_url)),
+    ("solver_id", |s| s.solver_id.to_string()),
+    ("solver_name", |s| s.solver_name.clone()),
+    ("solver_notes", |s| opt(&s.solver_notes)),
+];
+
+/// Characters that make spreadsheet programs treat a cell as a formula.
+pub const FORMULA_PREFIXES: [char; 4] = ['=', '+', '-', '@'];
+
+fn opt<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+/// Publicly viewable data for a solve, flattened into a single record.
+///
+/// Speed and fewest-moves results are omitted unless they are visible to
+/// logged-out users.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ExportedSolve {
+    pub id: i32,
+    pub url: String,
+    pub solve_date: DateTime<Utc>,
+    pub upload_date: DateTime<Utc>,
+
+    pub puzzle_id: i32,
+    pub puzzle_name: String,
+    pub variant_id: Option<i32>,
+    pub variant_name: Option<String>,
+    pub program_id: i32,
+    pub program_abbr: String,
+    pub program_material: bool,
+
+    pub average: bool,
+    pub blind: bool,
+    pub filters: bool,
+    pub macros: bool,
+    pub one_handed: bool,
+    pub computer_assisted: bool,
+
+    pub speed_cs: Option<i32>,
+    pub memo_cs: Option<i32>,
+    pub speed_verified: Option<bool>,
+    pub move_count: Option<i32>,
+    pub fmc_verified: Option<bool>,
+
+    pub has_log_file: bool,
+    pub video_url: Option<String>,
+
+    pub solver_id: i32,
+    pub solver_name: String,
+    pub solver_notes: Option<String>,
+}
+impl From<&FullSolve> for ExportedSolve {
+    fn from(solve: &FullSolve) -> Self {
+        let show_speed = solve.can_view_speed(None);
+        let show_fmc = solve.can_view_fmc(None);
+        Self {
+            id: solve.id.0,
+            url: solve.absolute_url(),
+            solve_date: solve.solve_date,
+            upload_date: solve.upload_date,
+
+            puzzle_id: solve.puzzle.id.0,
+            puzzle_name: solve.puzzle.name.clone(),
+            variant_id: solve.variant.as_ref().map(|v| v.id.0),
+            variant_name: solve.variant.as_ref().map(|v| v.name.clone()),
+            program_id: solve.program.id.0,
+            program_abbr: solve.program.abbr.clone(),
+            program_material: solve.program.material,
+
+            average: solve.flags.average,
+            blind: solve.flags.blind,
+            filters: solve.flags.filters,
+            macros: solve.flags.macros,
+            one_handed: solve.flags.one_handed,
+            computer_assisted: solve.flags.computer_assisted,
+
+            speed_cs: solve.speed_cs.filter(|_| show_speed),
+            memo_cs: solve.memo_cs.filter(|_| show_speed),
+            speed_verified: solve.speed_verified.filter(|_| show_speed),
+            move_count: solve.move_count.filter(|_| show_fmc),
+            fmc_verified: solve.fmc_verified.filter(|_| show_fmc),
+
+            has_log_file: solve.log_file_name.is_some(),
+            video_url: solve.video_url.clone(),
+
+            solver_id: solve.solver.id.0,
+            solver_name: solve.solver.display_name(),
+            solver_notes: solve.solver_notes.clone().filter(|s| !s.is_empty()),
+        }
+    }
+}
+/// Returns every publicly viewable verified solve, sorted by ID.
+pub async fn exported_solves(state: &AppState) -> Result<Vec<ExportedSolve>, AppError> {
+    Ok(state
+        .get_all_verified_solves()
+        .await?
+        .iter()
+        .map(ExportedSolve::from)
+        .collect())
+}
+
+/// Returns solves as CSV with a header row, as specified in RFC 4180.
+pub fn solves_to_csv(solves: &[ExportedSolve]) -> String {
+    let header = COLUMNS
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect_vec();
+    let records = solves
+        .iter()
+        .map(|solve| COLUMNS.iter().map(|(_, value)| value(solve)).collect_vec());
+    std::iter::once(header)
+        .chain(records)
+        .map(|fields| fields.iter().map(|field| escape_csv(field)).join(",") + "\r\n")
+        .collect()
+}
+
+/// Quotes a field if needed, and prefixes it with `'` if it would otherwise be
+/// interpreted as a formula by spreadsheet programs.
+fn escape_csv(field: &str) -> String {
+    let field = if field.starts_with(FORMULA_PREFIXES) {
+        format!("'{field}")
+    } else {
+        field.to_string()
+    };
+    if field.contains(['"', ',', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Response containing a downloadable file.
+pub struct FileResponse {
+    pub content_type: &'static str,
+    pub file_name: &'static str,
+    pub contents: String,
+}
+
+impl IntoResponse for FileResponse {
+    fn into_response(self) -> Response {
+        (
+            [
+                (header::CONTENT_TYPE, self.content_type.to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", self.file_name),
+                ),
+            ],
+            self.contents,
+        )
+            .into_response()
+    }
+}
+
+/// CSV export of all verified solves.
+#[derive(serde::Deserialize)]
+pub struct SolvesCsvExport {}
+
+impl RequestBody for SolvesCsvExport {
+    type Response = FileResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        _user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        Ok(FileResponse {
+            content_type: "text/csv; charset=utf-8",
+            file_name: "solves.csv",
+            contents: solves_to_csv(&exported_solves(&state).await?),
+        })
+    }
+}
+
+/// JSON export of all verified solves.
+#[derive(serde::Deserialize)]
+pub struct SolvesJsonExport {}
+
+impl RequestBody for SolvesJsonExport {
+    type Response = FileResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        _user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        Ok(FileResponse {
+            content_type: "application/json",
+            file_name: "solves.json",
+            contents: serde_json::to_string(&exported_solves(&state).await?)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn exported_solve() -> ExportedSolve {
+        let date = Utc
+            .with_ymd_and_hms(2024, 5, 6, 12, 0, 0)
+            .single()
+            .expect("invalid date");
+        ExportedSolve {
+            id: 7,
+            url: "https://example.com/solve?id=7".to_string(),
+            solve_date: date,
+            upload_date: date,
+
+            puzzle_id: 2,
+            puzzle_name: "3×3×3×3".to_string(),
+            variant_id: None,
+            variant_name: None,
+            program_id: 2,
+            program_abbr: "HSC2".to_string(),
+            program_material: false,
+
+            average: false,
+            blind: false,
+            filters: true,
+            macros: false,
+            one_handed: false,
+            computer_assisted: false,
+
+            speed_cs: Some(8350),
+            memo_cs: None,
+            speed_verified: Some(true),
+            move_count: None,
+            fmc_verified: None,
+
+            has_log_file: true,
+            video_url: None,
+
+            solver_id: 4,
+            solver_name: "=HYPERLINK(\"x\")".to_string(),
+            solver_notes: Some("fast, I think\nmaybe".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_escape_csv() {
+        assert_eq!(escape_csv("plain"), "plain");
+        assert_eq!(escape_csv(""), "");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv("line\r\nbreak"), "\"line\r\nbreak\"");
+        for formula in ["=1+1", "+1", "-1", "@SUM(A1)"] {
+            assert_eq!(escape_csv(formula), format!("'{formula}"));
+        }
+        assert_eq!(escape_csv("=a,b"), "\"'=a,b\"");
+    }
+
+    #[test]
+    fn test_solves_to_csv() {
+        let csv = solves_to_csv(&[exported_solve()]);
+        let lines = csv.split_terminator("\r\n").collect_vec();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], COLUMNS.iter().map(|(name, _)| name).join(","));
+        assert_eq!(
+            lines[1],
+            "7,https://example.com/solve?id=7,2024-05-06T12:00:00+00:00,\
+             2024-05-06T12:00:00+00:00,2,3×3×3×3,,,2,HSC2,false,false,false,true,false,\
+             false,false,8350,,true,,,true,,4,\"'=HYPERLINK(\"\"x\"\")\",\
+             \"fast, I think\nmaybe\"",
+        );
+    }
+}
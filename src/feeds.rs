@@ -0,0 +1,273 @@
+//! Atom feeds of new records and verified solves.
+
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+
+use crate::db::{Event, EventClass, FullSolve, PuzzleId, ScoreQuery, User, UserId};
+use crate::discord::notify::MdSolveInEvent;
+use crate::traits::{Linkable, RequestBody};
+use crate::util::escape_xml;
+use crate::{AppError, AppState};
+
+/// Maximum number of entries in a feed.
+const FEED_LENGTH: usize = 50;
+
+/// Atom feed.
+pub struct AtomFeed {
+    /// Relative URL of the feed itself.
+    pub relative_url: String,
+    pub title: String,
+    /// Page that the feed is about.
+    pub alternate_url: String,
+    pub entries: Vec<AtomEntry>,
+}
+
+pub struct AtomEntry {
+    /// Globally unique identifier for the entry.
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub updated: DateTime<Utc>,
+    pub author: String,
+    pub summary: String,
+}
+
+impl AtomFeed {
+    /// Returns a feed with the newest entries first, truncated to
+    /// [`FEED_LENGTH`].
+    fn new(
+        relative_url: String,
+        title: String,
+        alternate_url: String,
+        entries: impl IntoIterator<Item = AtomEntry>,
+    ) -> Self {
+        Self {
+            relative_url,
+            title,
+            alternate_url,
+            entries: entries
+                .into_iter()
+                .sorted_by_key(|entry| std::cmp::Reverse(entry.updated))
+                .take(FEED_LENGTH)
+                .collect(),
+        }
+    }
+
+    pub fn to_xml(&self) -> String {
+        let feed_url = crate::env::DOMAIN_NAME.clone() + &self.relative_url;
+        let updated = self
+            .entries
+            .iter()
+            .map(|entry| entry.updated)
+            .max()
+            .unwrap_or_default();
+
+        let mut xml = String::new();
+        xml += r#"<?xml version="1.0" encoding="utf-8"?>"#;
+        xml += r#"<feed xmlns="http://www.w3.org/2005/Atom">"#;
+        xml += &format!("<id>{}</id>", escape_xml(&feed_url));
+        xml += &format!("<title>{}</title>", escape_xml(&self.title));
+        xml += &format!("<updated>{}</updated>", updated.to_rfc3339());
+        xml += &format!(r#"<link rel="self" href="{}"/>"#, escape_xml(&feed_url));
+        xml += &format!(
+            r#"<link rel="alternate" type="text/html" href="{}"/>"#,
+            escape_xml(&self.alternate_url),
+        );
+        for entry in &self.entries {
+            xml += "<entry>";
+            xml += &format!("<id>{}</id>", escape_xml(&entry.id));
+            xml += &format!("<title>{}</title>", escape_xml(&entry.title));
+            xml += &format!("<updated>{}</updated>", entry.updated.to_rfc3339());
+            xml += &format!(
+                r#"<link rel="alternate" type="text/html" href="{}"/>"#,
+                escape_xml(&entry.url),
+            );
+            xml += &format!(
+                "<author><name>{}</name></author>",
+                escape_xml(&entry.author),
+            );
+            xml += &format!("<summary>{}</summary>", escape_xml(&entry.summary));
+            xml += "</entry>";
+        }
+        xml += "</feed>";
+        xml
+    }
+}
+
+impl IntoResponse for AtomFeed {
+    fn into_response(self) -> Response {
+        (
+            [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+            self.to_xml(),
+        )
+            .into_response()
+    }
+}
+
+/// Returns a feed entry for a solve that set or tied a record and was
+/// verified at `verified_at`.
+fn record_entry(event: &Event, solve: &FullSolve, verified_at: DateTime<Utc>) -> AtomEntry {
+    let event_class = event.category.class();
+    let result = MdSolveInEvent(solve, event_class).md_text();
+    let solver_name = solve.solver.display_name();
+    AtomEntry {
+        id: format!(
+            "{}#{}-record",
+            solve.absolute_url(),
+            event_class.long_name()
+        ),
+        title: format!("{solver_name} set a {result} record for {}", event.name()),
+        url: solve.absolute_url(),
+        updated: verified_at,
+        author: solver_name,
+        summary: format!(
+            "{} record of {result} for {} by {}, solved on {}",
+            capitalize(event_class.long_name()),
+            event.name(),
+            solve.solver.display_name(),
+            solve.solve_date.format("%Y-%m-%d"),
+        ),
+    }
+}
+
+/// Returns a feed entry for a solve that was verified at `verified_at`.
+fn solve_entry(solve: &FullSolve, verified_at: DateTime<Utc>) -> AtomEntry {
+    let event = solve.primary_event();
+    let solver_name = solve.solver.display_name();
+    let results = [
+        (solve.speed_verified == Some(true)).then(|| MdSolveInEvent(solve, EventClass::Speed)),
+        (solve.fmc_verified == Some(true)).then(|| MdSolveInEvent(solve, EventClass::Fmc)),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|result| result.md_text())
+    .join(" / ");
+    AtomEntry {
+        id: solve.absolute_url(),
+        title: format!("{solver_name}: {results} {}", event.name()),
+        url: solve.absolute_url(),
+        updated: verified_at,
+        author: solver_name,
+        summary: format!(
+            "{results} {} by {}, solved on {}",
+            event.name(),
+            solve.solver.display_name(),
+            solve.solve_date.format("%Y-%m-%d"),
+        ),
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Returns feed entries for the most recent records in all ranked categories.
+async fn record_entries(
+    state: &AppState,
+    puzzle: Option<PuzzleId>,
+) -> Result<Vec<AtomEntry>, AppError> {
+    let mut records = vec![];
+    for category in ScoreQuery::ranked_categories() {
+        records.extend(
+            state
+                .get_recent_records(puzzle, &category, FEED_LENGTH as i64)
+                .await?,
+        );
+    }
+    let solve_ids = records.iter().map(|(_, solve)| solve.id).collect_vec();
+    let verification_times = state.get_verification_times(&solve_ids).await?;
+    Ok(records
+        .iter()
+        .map(|(event, solve)| {
+            let verified_at = verification_times
+                .get(&solve.id)
+                .copied()
+                .unwrap_or(solve.upload_date);
+            record_entry(event, solve, verified_at)
+        })
+        .collect())
+}
+
+/// Feed of new records on all puzzles.
+#[derive(serde::Deserialize)]
+pub struct RecordsFeed {}
+
+impl RequestBody for RecordsFeed {
+    type Response = AtomFeed;
+
+    async fn request(
+        self,
+        state: AppState,
+        _user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        Ok(AtomFeed::new(
+            "/feeds/records.atom".to_string(),
+            "Hypercubing Leaderboards: records".to_string(),
+            crate::env::DOMAIN_NAME.clone() + "/",
+            record_entries(&state, None).await?,
+        ))
+    }
+}
+
+/// Feed of new records on a single puzzle.
+#[derive(serde::Deserialize)]
+pub struct PuzzleFeed {
+    id: PuzzleId,
+}
+
+impl RequestBody for PuzzleFeed {
+    type Response = AtomFeed;
+
+    async fn request(
+        self,
+        state: AppState,
+        _user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let puzzle = state.get_puzzle(self.id).await?.ok_or(AppError::NotFound)?;
+        Ok(AtomFeed::new(
+            format!("/feeds/puzzle.atom?id={}", puzzle.id.0),
+            format!("Hypercubing Leaderboards: {} records", puzzle.name),
+            puzzle.absolute_url(),
+            record_entries(&state, Some(puzzle.id)).await?,
+        ))
+    }
+}
+
+/// Feed of verified solves by a single solver.
+#[derive(serde::Deserialize)]
+pub struct SolverFeed {
+    id: UserId,
+}
+
+impl RequestBody for SolverFeed {
+    type Response = AtomFeed;
+
+    async fn request(
+        self,
+        state: AppState,
+        _user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let solver = state
+            .get_opt_user(self.id)
+            .await?
+            .ok_or(AppError::NotFound)?
+            .to_public();
+        let solves = state
+            .get_recently_verified_solves(solver.id, FEED_LENGTH as i64)
+            .await?;
+        Ok(AtomFeed::new(
+            format!("/feeds/solver.atom?id={}", solver.id.0),
+            format!("Hypercubing Leaderboards: {}", solver.display_name()),
+            solver.absolute_url(),
+            solves
+                .iter()
+                .map(|(verified_at, solve)| solve_entry(solve, *verified_at)),
+        ))
+    }
+}
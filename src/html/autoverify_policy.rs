@@ -0,0 +1,53 @@
+use axum::response::IntoResponse;
+
+use crate::db::{AutoVerifyPolicyEntry, Puzzle, User};
+use crate::traits::RequestBody;
+use crate::{AppError, AppState};
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct AutoVerifyPolicyPage {}
+
+pub struct AutoVerifyPolicyPageResponse {
+    user: Option<User>,
+    policies: Vec<AutoVerifyPolicyEntry>,
+    /// Puzzles that are auto-verified, for reference.
+    autoverifiable_puzzles: Vec<Puzzle>,
+}
+
+impl RequestBody for AutoVerifyPolicyPage {
+    type Response = AutoVerifyPolicyPageResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        if !user.as_ref().ok_or(AppError::NotLoggedIn)?.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        Ok(AutoVerifyPolicyPageResponse {
+            user,
+            policies: state.get_all_autoverify_policies().await?,
+            autoverifiable_puzzles: state
+                .get_all_puzzles()
+                .await?
+                .into_iter()
+                .filter(|puzzle| puzzle.autoverifiable)
+                .collect(),
+        })
+    }
+}
+
+impl IntoResponse for AutoVerifyPolicyPageResponse {
+    fn into_response(self) -> axum::response::Response {
+        crate::render_html_template(
+            "autoverify-policy.html",
+            &self.user,
+            serde_json::json!({
+                "policies": self.policies,
+                "autoverifiable_puzzles": self.autoverifiable_puzzles,
+            }),
+        )
+    }
+}
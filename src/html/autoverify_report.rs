@@ -0,0 +1,96 @@
+use axum::response::IntoResponse;
+use hyperspeedcube_cli_types::verification::Durations;
+
+use crate::autoverify::AutoVerifyReport;
+use crate::db::{SolveId, User};
+use crate::traits::RequestBody;
+use crate::{AppError, AppState};
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct AutoVerifyReportPage {
+    id: SolveId,
+    /// Whether to run the verifier again instead of using its stored output.
+    #[serde(default)]
+    rerun: bool,
+}
+
+pub struct AutoVerifyReportPageResponse {
+    user: Option<User>,
+    report: AutoVerifyReport,
+}
+
+impl RequestBody for AutoVerifyReportPage {
+    type Response = AutoVerifyReportPageResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        if !user.as_ref().ok_or(AppError::NotLoggedIn)?.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        Ok(AutoVerifyReportPageResponse {
+            user,
+            report: state.autoverify_report(self.id, self.rerun).await?,
+        })
+    }
+}
+
+impl IntoResponse for AutoVerifyReportPageResponse {
+    fn into_response(self) -> axum::response::Response {
+        let verifications = &self.report.verifications;
+        let durations = verifications
+            .iter()
+            .enumerate()
+            .map(|(i, verification)| {
+                serde_json::json!({
+                    "title": (verifications.len() > 1).then(|| format!("Solve {}", i + 1)),
+                    "rows": render_durations(&verification.durations),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        crate::render_html_template(
+            "autoverify-report.html",
+            &self.user,
+            serde_json::json!({
+                "report": self.report,
+                "durations": durations,
+            }),
+        )
+    }
+}
+
+/// Returns a `[name, duration]` pair for each duration measured by the
+/// verifier.
+fn render_durations(durations: &Durations) -> Vec<[String; 2]> {
+    let Durations {
+        scramble_network_latency,
+        scramble_application,
+        inspection,
+        speedsolve,
+        memo,
+        blindsolve,
+        timestamp_network_latency,
+    } = *durations;
+
+    [
+        ("Scramble network latency", scramble_network_latency),
+        ("Scramble application", scramble_application),
+        ("Inspection", inspection),
+        ("Speedsolve", speedsolve),
+        ("Memorization", memo),
+        ("Blindsolve", blindsolve),
+        ("Timestamp network latency", timestamp_network_latency),
+    ]
+    .into_iter()
+    .map(|(name, duration)| {
+        [
+            name.to_string(),
+            duration.map_or("unknown".to_string(), |dur| dur.to_string()),
+        ]
+    })
+    .collect()
+}
@@ -0,0 +1,104 @@
+use chrono::Utc;
+
+use crate::chart::{ChartPoint, StepChart, SvgResponse};
+use crate::db::{CategoryQuery, FullSolve, RankedFullSolve, User};
+use crate::html::leaderboards::as_of_cutoff;
+use crate::html::leaderboards::per_puzzle::PuzzleLeaderboardTable;
+use crate::traits::{Linkable, RequestBody};
+use crate::{AppError, AppState};
+
+const RECORD_HISTORY_CHART_WIDTH: f64 = 800.0;
+const RECORD_HISTORY_CHART_HEIGHT: f64 = 300.0;
+
+const SPARKLINE_WIDTH: f64 = 160.0;
+const SPARKLINE_HEIGHT: f64 = 32.0;
+
+/// Returns the function used to format results in `category_query`.
+fn value_formatter(category_query: &CategoryQuery) -> fn(i32) -> String {
+    match category_query {
+        CategoryQuery::Speed { .. } => crate::util::render_time,
+        CategoryQuery::Fmc { .. } => |move_count| move_count.to_string(),
+    }
+}
+
+/// Returns a chart point for each solve that has a result in `category_query`.
+fn chart_points<'a>(
+    solves: impl IntoIterator<Item = &'a FullSolve>,
+    category_query: &CategoryQuery,
+) -> Vec<ChartPoint> {
+    let format_value = value_formatter(category_query);
+    solves
+        .into_iter()
+        .filter_map(|solve| {
+            let value = match category_query {
+                CategoryQuery::Speed { .. } => solve.speed_cs,
+                CategoryQuery::Fmc { .. } => solve.move_count,
+            }?;
+            let solver_name = solve.solver.display_name();
+            Some(ChartPoint {
+                date: solve.solve_date,
+                value,
+                label: format!(
+                    "{} by {solver_name} on {}",
+                    format_value(value),
+                    solve.solve_date.format("%Y-%m-%d"),
+                ),
+                series: solver_name,
+                url: Some(solve.relative_url()),
+            })
+        })
+        .collect()
+}
+
+/// Returns a small inline SVG chart of a personal best progression, without
+/// axes.
+pub fn pb_sparkline(solves: &[RankedFullSolve], category_query: &CategoryQuery) -> String {
+    StepChart {
+        width: SPARKLINE_WIDTH,
+        height: SPARKLINE_HEIGHT,
+        points: chart_points(solves.iter().map(|s| &s.solve), category_query),
+        end: Utc::now(),
+        format_value: value_formatter(category_query),
+        axes: false,
+    }
+    .to_svg()
+}
+
+/// Chart of the record progression for a puzzle and category. Takes the same
+/// parameters as the per-puzzle leaderboard.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct RecordHistoryChart(PuzzleLeaderboardTable);
+
+impl RequestBody for RecordHistoryChart {
+    type Response = SvgResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        _user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let category_query = self.0.category_query()?;
+        let puzzle = state
+            .get_puzzle(self.0.id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let as_of = as_of_cutoff(self.0.as_of)?;
+
+        let records = state
+            .get_record_history(&puzzle, &category_query, as_of)
+            .await?;
+
+        Ok(SvgResponse(
+            StepChart {
+                width: RECORD_HISTORY_CHART_WIDTH,
+                height: RECORD_HISTORY_CHART_HEIGHT,
+                points: chart_points(&records, &category_query),
+                end: as_of.map_or_else(Utc::now, |t| t.min(Utc::now())),
+                format_value: value_formatter(&category_query),
+                axes: true,
+            }
+            .to_svg(),
+        ))
+    }
+}
@@ -3,16 +3,17 @@ use std::collections::HashMap;
 use axum::body::Body;
 use axum::http::Response;
 use axum::response::IntoResponse;
+use chrono::{DateTime, NaiveDate, Utc};
 use itertools::Itertools;
 
-use super::LeaderboardEvent;
+use super::{LeaderboardEvent, as_of_cutoff};
 use crate::db::{CategoryQuery, MainPageCategory, ProgramQuery, ScoreQuery, User, VariantQuery};
 use crate::html::solve_table::{
     LeaderboardTableColumns, LeaderboardTableRows, SolveTableRow, SolvesTable,
     SolvesTablesResponse, UserTableRow,
 };
 use crate::traits::{Linkable, RequestBody};
-use crate::{AppError, AppState};
+use crate::{AppError, AppResult, AppState};
 
 #[derive(serde::Deserialize, Debug, Clone)]
 pub struct GlobalLeaderboardTable {
@@ -21,8 +22,17 @@ pub struct GlobalLeaderboardTable {
     pub macros: Option<bool>,
     pub variant: Option<VariantQuery>,
     pub program: Option<ProgramQuery>,
+
+    /// Show the leaderboard as it stood at the end of this date.
+    pub as_of: Option<NaiveDate>,
 }
 impl GlobalLeaderboardTable {
+    /// Returns the exclusive upper bound on solve, upload, and verification
+    /// times for the leaderboard.
+    pub fn as_of_cutoff(&self) -> AppResult<Option<DateTime<Utc>>> {
+        as_of_cutoff(self.as_of)
+    }
+
     pub fn global_leaderboard_query(&self) -> GlobalLeaderboardQuery {
         let event = self.event.unwrap_or_default();
 
@@ -45,6 +55,9 @@ impl GlobalLeaderboardTable {
             }
             .into(),
             LeaderboardEvent::Distinct => ScoreQuery::Distinct.into(),
+            LeaderboardEvent::Sor => ScoreQuery::Sor.into(),
+            LeaderboardEvent::Parallel => ScoreQuery::Parallel.into(),
+            LeaderboardEvent::Kinch => ScoreQuery::Kinch.into(),
         }
     }
 }
@@ -73,30 +86,22 @@ impl RequestBody for GlobalLeaderboardTable {
         state: crate::AppState,
         _user: Option<crate::db::User>,
     ) -> Result<Self::Response, crate::AppError> {
+        let as_of = self.as_of_cutoff()?;
         match self.global_leaderboard_query() {
             GlobalLeaderboardQuery::Category(query) => {
                 let solver_counts: HashMap<MainPageCategory, i64> = state
-                    .get_all_puzzles_counts(&query)
+                    .get_all_puzzles_counts(&query, as_of)
                     .await?
                     .into_iter()
                     .collect();
 
-                let solves = state.get_all_puzzles_leaderboard(&query).await?;
+                let solves = state.get_all_puzzles_leaderboard(&query, as_of).await?;
 
                 let rows = solves
                     .into_iter()
                     .map(|(solve_event, solve)| {
                         let total_solvers = *solver_counts
-                            .get(&match query {
-                                CategoryQuery::Speed { .. } => MainPageCategory::Speed {
-                                    puzzle: solve.puzzle.id,
-                                    variant: solve.variant.as_ref().map(|v| v.id),
-                                    material: solve.program.material,
-                                },
-                                CategoryQuery::Fmc { .. } => MainPageCategory::Fmc {
-                                    puzzle: solve.puzzle.id,
-                                },
-                            })
+                            .get(&query.main_page_category_of_solve(&solve))
                             .unwrap_or(&0);
 
                         SolveTableRow::new(&solve_event, &solve, None, Some(total_solvers), &query)
@@ -106,6 +111,7 @@ impl RequestBody for GlobalLeaderboardTable {
 
                 Ok(SolvesTable {
                     heading: None,
+                    sparkline: None,
                     table_rows: LeaderboardTableRows::Solves(rows),
                     columns: LeaderboardTableColumns {
                         puzzle: true,
@@ -119,12 +125,13 @@ impl RequestBody for GlobalLeaderboardTable {
                         program: true,
                         total_solvers: true,
                         score: false,
+                        claimed_by: false,
                     },
                 }
                 .grouped())
             }
             GlobalLeaderboardQuery::Score(query) => {
-                let users_and_scores = state.get_score_leaderboard(query).await?;
+                let users_and_scores = state.get_score_leaderboard(query, as_of).await?;
 
                 let rows = users_and_scores
                     .into_iter()
@@ -138,6 +145,7 @@ impl RequestBody for GlobalLeaderboardTable {
 
                 Ok(SolvesTable {
                     heading: None,
+                    sparkline: None,
                     table_rows: LeaderboardTableRows::Users(rows),
                     columns: LeaderboardTableColumns {
                         puzzle: false,
@@ -151,6 +159,7 @@ impl RequestBody for GlobalLeaderboardTable {
                         program: false,
                         total_solvers: false,
                         score: true,
+                        claimed_by: false,
                     },
                 }
                 .grouped())
@@ -205,6 +214,8 @@ impl IntoResponse for GlobalLeaderboardResponse {
             &self.user,
             serde_json::json!({
                 "pending_submissions_count": self.pending_submissions_count,
+                "feed_url": "/feeds/records.atom",
+                "feed_title": "Records",
             }),
         )
     }
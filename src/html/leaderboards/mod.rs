@@ -1,6 +1,22 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+use crate::{AppError, AppResult};
+
 pub mod global;
 pub mod per_puzzle;
 
+/// Returns the exclusive upper bound on timestamps for a leaderboard as it
+/// stood at the end of `as_of` (UTC).
+pub fn as_of_cutoff(as_of: Option<NaiveDate>) -> AppResult<Option<DateTime<Utc>>> {
+    let Some(as_of) = as_of else {
+        return Ok(None);
+    };
+    let next_day = as_of
+        .succ_opt()
+        .ok_or_else(|| AppError::InvalidQuery(format!("date {as_of} is out of range")))?;
+    Ok(Some(next_day.and_time(NaiveTime::MIN).and_utc()))
+}
+
 #[derive(serde::Deserialize, Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum LeaderboardEvent {
@@ -19,4 +35,29 @@ pub enum LeaderboardEvent {
     FmcCa,
     /// Distinct puzzles (aggregate)
     Distinct,
+    /// Sum of ranks (aggregate)
+    Sor,
+    /// Parallel sum of ranks (aggregate)
+    Parallel,
+    /// Kinch rank (aggregate)
+    Kinch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_of_cutoff() {
+        assert_eq!(as_of_cutoff(None).ok(), Some(None));
+        let date = NaiveDate::from_ymd_opt(2024, 12, 31).expect("invalid date");
+        let cutoff = as_of_cutoff(Some(date))
+            .expect("error computing cutoff")
+            .expect("missing cutoff");
+        assert_eq!(cutoff.to_rfc3339(), "2025-01-01T00:00:00+00:00");
+        assert!(matches!(
+            as_of_cutoff(Some(NaiveDate::MAX)),
+            Err(AppError::InvalidQuery(_)),
+        ));
+    }
 }
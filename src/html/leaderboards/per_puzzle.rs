@@ -1,11 +1,12 @@
 use axum::body::Body;
 use axum::response::{IntoResponse, Response};
+use chrono::NaiveDate;
 
-use super::LeaderboardEvent;
 use super::global::{GlobalLeaderboardQuery, GlobalLeaderboardTable};
+use super::{LeaderboardEvent, as_of_cutoff};
 use crate::db::{
-    Category, CategoryQuery, CombinedVariant, Event, ProgramQuery, Puzzle, PuzzleId,
-    RankedFullSolve, User, VariantQuery,
+    CategoryQuery, CombinedVariant, ProgramQuery, Puzzle, PuzzleId, RankedFullSolve, User,
+    VariantQuery,
 };
 use crate::html::solve_table::{
     LeaderboardTableColumns, LeaderboardTableRows, SolveTableRow, SolvesTable, SolvesTablesResponse,
@@ -54,6 +55,8 @@ impl IntoResponse for PuzzleLeaderboardResponse {
             "puzzle.html",
             &self.user,
             serde_json::json!({
+                "feed_url": format!("/feeds/puzzle.atom?id={}", self.puzzle.id.0),
+                "feed_title": format!("{} records", self.puzzle.name),
                 "puzzle": self.puzzle,
                 "variants": self.variants,
                 "history": self.history,
@@ -75,86 +78,78 @@ pub struct PuzzleLeaderboardTable {
 
     #[serde(default)]
     pub history: bool,
-}
 
-impl RequestBody for PuzzleLeaderboardTable {
-    type Response = SolvesTablesResponse;
+    /// Show the leaderboard as it stood at the end of this date.
+    pub as_of: Option<NaiveDate>,
+}
 
-    async fn request(
-        self,
-        state: AppState,
-        _user: Option<User>,
-    ) -> Result<Self::Response, AppError> {
+impl PuzzleLeaderboardTable {
+    /// Returns the category query for the leaderboard.
+    pub fn category_query(&self) -> Result<CategoryQuery, AppError> {
         let global = GlobalLeaderboardTable {
             event: self.event,
             filters: self.filters,
             macros: self.macros,
-            variant: Some(self.variant.unwrap_or(VariantQuery::Default)),
-            program: Some(self.program.unwrap_or(ProgramQuery::Default)),
+            variant: Some(self.variant.clone().unwrap_or(VariantQuery::Default)),
+            program: Some(self.program.clone().unwrap_or(ProgramQuery::Default)),
+            as_of: self.as_of,
         };
-        let GlobalLeaderboardQuery::Category(category_query) = global.global_leaderboard_query()
-        else {
-            return Err(AppError::InvalidQuery("bad category".to_string()));
-        };
-        let puzzle = state.get_puzzle(self.id).await?.ok_or(AppError::NotFound)?;
+        match global.global_leaderboard_query() {
+            GlobalLeaderboardQuery::Category(category_query) => Ok(category_query),
+            GlobalLeaderboardQuery::Score(_) => {
+                Err(AppError::InvalidQuery("bad category".to_string()))
+            }
+        }
+    }
 
-        let solves = if self.history {
+    /// Returns the ranked solves on the leaderboard, or the record history if
+    /// `history` is set (in which case all ranks are 0).
+    pub async fn ranked_solves(
+        &self,
+        state: &AppState,
+        puzzle: &Puzzle,
+        category_query: &CategoryQuery,
+    ) -> Result<Vec<RankedFullSolve>, AppError> {
+        let as_of = as_of_cutoff(self.as_of)?;
+        Ok(if self.history {
             state
-                .get_record_history(&puzzle, &category_query)
+                .get_record_history(puzzle, category_query, as_of)
                 .await?
                 .into_iter()
                 .map(|solve| RankedFullSolve { rank: 0, solve })
                 .collect()
         } else {
             state
-                .get_event_leaderboard(&puzzle, &category_query)
+                .get_event_leaderboard(puzzle, category_query, as_of)
                 .await?
-        };
+        })
+    }
+}
+
+impl RequestBody for PuzzleLeaderboardTable {
+    type Response = SolvesTablesResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        _user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let category_query = self.category_query()?;
+        let puzzle = state.get_puzzle(self.id).await?.ok_or(AppError::NotFound)?;
+
+        let solves = self.ranked_solves(&state, &puzzle, &category_query).await?;
 
         let solve_rows = solves
             .into_iter()
             .map(|RankedFullSolve { rank, solve }| {
-                let event = Event {
-                    puzzle: puzzle.clone(),
-                    category: match &category_query {
-                        CategoryQuery::Speed {
-                            average,
-                            blind,
-                            filters,
-                            macros,
-                            one_handed,
-                            variant: _,
-                            program: _,
-                        } => {
-                            let default_filters = match &solve.variant {
-                                Some(v) => v.primary_filters,
-                                None => puzzle.primary_filters,
-                            };
-                            let default_macros = match &solve.variant {
-                                Some(v) => v.primary_macros,
-                                None => puzzle.primary_macros,
-                            };
-                            Category::Speed {
-                                average: *average,
-                                blind: *blind,
-                                filters: filters.unwrap_or(default_filters),
-                                macros: macros.unwrap_or(default_macros),
-                                one_handed: *one_handed,
-                                variant: solve.variant.clone(),
-                                material: solve.program.material,
-                            }
-                        }
-                        CategoryQuery::Fmc { computer_assisted } => Category::Fmc {
-                            computer_assisted: *computer_assisted,
-                        },
-                    },
-                };
+                let event = category_query.event_of_solve(&solve);
                 SolveTableRow::new(&event, &solve, Some(rank), None, &category_query)
             })
             .collect();
 
         Ok(SolvesTable {
             heading: None,
+            sparkline: None,
             table_rows: LeaderboardTableRows::Solves(solve_rows),
             columns: LeaderboardTableColumns {
                 puzzle: false,
@@ -168,6 +163,7 @@ impl RequestBody for PuzzleLeaderboardTable {
                 program: true,
                 total_solvers: false,
                 score: false,
+                claimed_by: false,
             },
         }
         .into())
@@ -1,9 +1,13 @@
 pub mod audit_log;
+pub mod autoverify_policy;
+pub mod autoverify_report;
 pub mod categories;
+pub mod charts;
 pub mod forms;
 pub mod leaderboards;
 pub mod not_found;
 pub mod otp;
+pub mod search;
 pub mod settings;
 pub mod sign_in;
 pub mod sign_out;
@@ -12,3 +16,4 @@ pub mod solve_table;
 pub mod submissions;
 pub mod user_page;
 pub mod users;
+pub mod webhooks;
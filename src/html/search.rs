@@ -0,0 +1,310 @@
+use axum::response::IntoResponse;
+use chrono::{NaiveDate, NaiveTime};
+use itertools::Itertools;
+
+use super::leaderboards::as_of_cutoff;
+use super::solve_table::{
+    LeaderboardTableColumns, LeaderboardTableRows, SolveTableRow, SolvesTable, SolvesTablesResponse,
+};
+use crate::db::{PuzzleId, SearchFacet, SolveSearchQuery, User, VerificationState};
+use crate::traits::RequestBody;
+use crate::{AppError, AppState};
+
+/// Default number of solves per page.
+const DEFAULT_PAGE_SIZE: i64 = 50;
+/// Maximum number of solves per page.
+const MAX_PAGE_SIZE: i64 = 200;
+
+/// Query parameters for a solve search.
+#[derive(serde::Deserialize, Debug, Default, Clone)]
+pub struct SolveSearch {
+    /// Full-text search of notes.
+    pub q: Option<String>,
+    pub solver: Option<String>,
+    pub puzzle: Option<String>,
+    pub puzzle_id: Option<PuzzleId>,
+    pub program: Option<String>,
+    pub variant: Option<String>,
+
+    pub average: Option<bool>,
+    pub blind: Option<bool>,
+    pub filters: Option<bool>,
+    pub macros: Option<bool>,
+    pub one_handed: Option<bool>,
+    pub computer_assisted: Option<bool>,
+
+    /// First solve date to include.
+    pub from: Option<NaiveDate>,
+    /// Last solve date to include.
+    pub to: Option<NaiveDate>,
+    pub state: Option<VerificationState>,
+
+    /// Page number, starting at 1.
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+impl SolveSearch {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1)
+    }
+    pub fn per_page(&self) -> i64 {
+        self.per_page.unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    pub fn search_query(&self) -> Result<SolveSearchQuery, AppError> {
+        fn non_empty(s: &Option<String>) -> Option<String> {
+            Some(s.as_ref()?.trim().to_string()).filter(|s| !s.is_empty())
+        }
+
+        if self.page() < 1 {
+            return Err(AppError::InvalidQuery(
+                "page must be at least 1".to_string(),
+            ));
+        }
+        if !(1..=MAX_PAGE_SIZE).contains(&self.per_page()) {
+            return Err(AppError::InvalidQuery(format!(
+                "per_page must be between 1 and {MAX_PAGE_SIZE}",
+            )));
+        }
+
+        Ok(SolveSearchQuery {
+            text: non_empty(&self.q),
+            solver: non_empty(&self.solver),
+            puzzle: non_empty(&self.puzzle),
+            puzzle_id: self.puzzle_id,
+            program: non_empty(&self.program),
+            variant: non_empty(&self.variant),
+
+            average: self.average,
+            blind: self.blind,
+            filters: self.filters,
+            macros: self.macros,
+            one_handed: self.one_handed,
+            computer_assisted: self.computer_assisted,
+
+            solved_after: self
+                .from
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc()),
+            solved_before: as_of_cutoff(self.to)?,
+            state: self.state,
+
+            offset: (self.page() - 1).saturating_mul(self.per_page()),
+            limit: self.per_page(),
+        })
+    }
+
+    /// Returns the URL of the search page with the same parameters, plus any
+    /// changes made by `f`.
+    fn url_with(&self, f: impl FnOnce(&mut Self)) -> String {
+        let mut search = self.clone();
+        f(&mut search);
+
+        let mut params = url::form_urlencoded::Serializer::new(String::new());
+        let mut param = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                params.append_pair(key, &value);
+            }
+        };
+        param("q", search.q);
+        param("solver", search.solver);
+        param("puzzle", search.puzzle);
+        param("puzzle_id", search.puzzle_id.map(|id| id.0.to_string()));
+        param("program", search.program);
+        param("variant", search.variant);
+        param("average", search.average.map(|b| b.to_string()));
+        param("blind", search.blind.map(|b| b.to_string()));
+        param("filters", search.filters.map(|b| b.to_string()));
+        param("macros", search.macros.map(|b| b.to_string()));
+        param("one_handed", search.one_handed.map(|b| b.to_string()));
+        param(
+            "computer_assisted",
+            search.computer_assisted.map(|b| b.to_string()),
+        );
+        param("from", search.from.map(|date| date.to_string()));
+        param("to", search.to.map(|date| date.to_string()));
+        param("state", search.state.map(|state| state.name().to_string()));
+        param("page", search.page.map(|page| page.to_string()));
+        param("per_page", search.per_page.map(|n| n.to_string()));
+
+        format!("/search?{}", params.finish())
+    }
+}
+
+/// Solve search page.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct SearchPage(pub SolveSearch);
+
+pub struct SearchPageResponse {
+    user: Option<User>,
+    search: SolveSearch,
+    total: i64,
+    page_count: i64,
+    prev_url: Option<String>,
+    next_url: Option<String>,
+    table: SolvesTablesResponse,
+    puzzles: Vec<serde_json::Value>,
+    programs: Vec<serde_json::Value>,
+}
+
+impl RequestBody for SearchPage {
+    type Response = SearchPageResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        let search = self.0;
+        let results = state
+            .search_solves(&search.search_query()?, user.as_ref())
+            .await?;
+
+        let rows = results
+            .solves
+            .iter()
+            .map(|solve| {
+                let mut row = SolveTableRow::new(
+                    &solve.primary_event(),
+                    solve,
+                    None,
+                    None,
+                    &solve.primary_category_query(),
+                );
+                if !solve.can_view_speed(user.as_ref()) {
+                    row.speed_cs = None;
+                    row.speed_verified = None;
+                }
+                if !solve.can_view_fmc(user.as_ref()) {
+                    row.move_count = None;
+                    row.fmc_verified = None;
+                }
+                row
+            })
+            .collect_vec();
+
+        let page = search.page();
+        let page_count = ((results.total + search.per_page() - 1) / search.per_page()).max(1);
+        let prev_url = (page > 1).then(|| search.url_with(|s| s.page = Some(page - 1)));
+        let next_url = (page < page_count).then(|| search.url_with(|s| s.page = Some(page + 1)));
+
+        let facet_json = |facet: &SearchFacet, url: String| {
+            serde_json::json!({
+                "name": facet.name,
+                "count": facet.count,
+                "url": url,
+            })
+        };
+        let puzzles = results
+            .puzzles
+            .iter()
+            .map(|facet| {
+                let url = search.url_with(|s| {
+                    s.puzzle_id = facet.id.map(PuzzleId);
+                    s.page = None;
+                });
+                facet_json(facet, url)
+            })
+            .collect();
+        let programs = results
+            .programs
+            .iter()
+            .map(|facet| {
+                let url = search.url_with(|s| {
+                    s.program = Some(facet.name.clone());
+                    s.page = None;
+                });
+                facet_json(facet, url)
+            })
+            .collect();
+
+        let table = SolvesTable {
+            heading: None,
+            sparkline: None,
+            table_rows: LeaderboardTableRows::Solves(rows),
+            columns: LeaderboardTableColumns {
+                puzzle: true,
+                rank: false,
+                solver: true,
+                record_holder: false,
+                speed_cs: true,
+                move_count: true,
+                verified: true,
+                date: true,
+                program: true,
+                total_solvers: false,
+                score: false,
+                claimed_by: false,
+            },
+        }
+        .into();
+
+        Ok(SearchPageResponse {
+            user,
+            search,
+            total: results.total,
+            page_count,
+            prev_url,
+            next_url,
+            table,
+            puzzles,
+            programs,
+        })
+    }
+}
+
+impl IntoResponse for SearchPageResponse {
+    fn into_response(self) -> axum::response::Response {
+        let search = &self.search;
+        let flag = |name: &str, label: &str, value: Option<bool>| {
+            serde_json::json!({
+                "name": name,
+                "label": label,
+                "value": value.map(|b| b.to_string()).unwrap_or_default(),
+            })
+        };
+        let states = VerificationState::ALL
+            .iter()
+            .map(|&state| {
+                serde_json::json!({
+                    "name": state.name(),
+                    "selected": search.state == Some(state),
+                })
+            })
+            .collect_vec();
+        crate::render_html_template(
+            "search.html",
+            &self.user,
+            serde_json::json!({
+                "search": {
+                    "q": search.q.clone().unwrap_or_default(),
+                    "solver": search.solver.clone().unwrap_or_default(),
+                    "puzzle": search.puzzle.clone().unwrap_or_default(),
+                    "puzzle_id": search.puzzle_id.map(|id| id.0.to_string()).unwrap_or_default(),
+                    "program": search.program.clone().unwrap_or_default(),
+                    "variant": search.variant.clone().unwrap_or_default(),
+                    "from": search.from.map(|date| date.to_string()).unwrap_or_default(),
+                    "to": search.to.map(|date| date.to_string()).unwrap_or_default(),
+                    "per_page": search.per_page.map(|n| n.to_string()).unwrap_or_default(),
+                },
+                "flags": [
+                    flag("average", "Average", search.average),
+                    flag("blind", "Blindfolded", search.blind),
+                    flag("filters", "Piece filters", search.filters),
+                    flag("macros", "Macros", search.macros),
+                    flag("one_handed", "One-handed", search.one_handed),
+                    flag("computer_assisted", "Computer-assisted", search.computer_assisted),
+                ],
+                "states": states,
+                "total": self.total,
+                "page": search.page(),
+                "page_count": self.page_count,
+                "prev_url": self.prev_url,
+                "next_url": self.next_url,
+                "tables": self.table.tables,
+                "puzzles": self.puzzles,
+                "programs": self.programs,
+            }),
+        )
+    }
+}
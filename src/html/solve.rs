@@ -2,7 +2,10 @@ use axum::body::Body;
 use axum::response::{IntoResponse, Response};
 
 pub use crate::db::FullSolve;
-use crate::db::{Program, Puzzle, RenderedAuditLogEntry, SolveId, User};
+use crate::db::{
+    AverageComponent, EventClass, Program, Puzzle, RecordApproval, RejectionReason,
+    RenderedAuditLogEntry, SolveAppeal, SolveId, User, average_components,
+};
 use crate::traits::{Linkable, RequestBody};
 use crate::{AppError, AppState};
 
@@ -28,6 +31,17 @@ pub struct SolvePageResponse {
     solver_notes_html: Option<String>,
     log_entries: Vec<RenderedAuditLogEntry>,
     autoverify_queue_index: Option<usize>,
+    average_components: Vec<AverageComponent>,
+    extra_log_files: Vec<serde_json::Value>,
+    auto_verifier_version: Option<String>,
+    /// Moderator reviewing the solve, shown to moderators.
+    claim: Option<serde_json::Value>,
+    /// Record approvals waiting for a second moderator, shown to moderators.
+    record_approvals: Vec<RecordApproval>,
+    /// Open appeal against a rejection, shown to moderators and the solver.
+    appeal: Option<SolveAppeal>,
+    /// Whether the user is the solver and can appeal a rejection.
+    can_appeal: bool,
 }
 
 impl RequestBody for SolvePage {
@@ -147,7 +161,52 @@ impl RequestBody for SolvePage {
             .filter_map(|entry| entry.display_public())
             .collect();
 
-        let autoverify_queue_index = state.autoverifier.index_of(self.id).await;
+        let autoverify_queue_index = state.autoverify_queue_index(self.id).await?;
+
+        let average_components = if solve.flags.average && show_speed {
+            average_components(&state.get_average_components(self.id).await?)
+        } else {
+            vec![]
+        };
+        let extra_log_files = if show_fmc {
+            state
+                .get_extra_log_file_names(self.id)
+                .await?
+                .into_iter()
+                .map(|(id, name)| serde_json::json!({ "id": id, "name": name }))
+                .collect()
+        } else {
+            vec![]
+        };
+        let auto_verifier_version = match &user {
+            Some(u) if u.moderator => state.get_auto_verifier_version(self.id).await?,
+            _ => None,
+        };
+        let claim = match &user {
+            Some(u) if u.moderator => state.get_solve_claim(self.id).await?.map(|claim| {
+                serde_json::json!({
+                    "moderator_name": claim.moderator.display_name(),
+                    "moderator_link": claim.moderator.relative_url(),
+                    "expires_at": claim.expires_at,
+                    "mine": claim.moderator.id == u.id,
+                })
+            }),
+            _ => None,
+        };
+        let mut record_approvals = vec![];
+        if user.as_ref().is_some_and(|u| u.moderator) {
+            for event_class in [EventClass::Speed, EventClass::Fmc] {
+                record_approvals.extend(state.get_record_approval(self.id, event_class).await?);
+            }
+        }
+        let appeal = if show_verification_status {
+            state.get_open_appeal(self.id).await?
+        } else {
+            None
+        };
+        let can_appeal = user.as_ref().is_some_and(|u| u.id == solve.solver.id)
+            && (solve.speed_verified == Some(false) || solve.fmc_verified == Some(false))
+            && appeal.is_none();
 
         Ok(SolvePageResponse {
             can_edit: edit_auth.is_some(),
@@ -166,6 +225,13 @@ impl RequestBody for SolvePage {
             solver_notes_html,
             log_entries,
             autoverify_queue_index,
+            average_components,
+            extra_log_files,
+            auto_verifier_version,
+            claim,
+            record_approvals,
+            appeal,
+            can_appeal,
         })
     }
 }
@@ -196,6 +262,14 @@ impl IntoResponse for SolvePageResponse {
                 "solver_notes_html": self.solver_notes_html,
                 "log_entries": self.log_entries,
                 "autoverify_queue_index": self.autoverify_queue_index,
+                "average_components": self.average_components,
+                "extra_log_files": self.extra_log_files,
+                "auto_verifier_version": self.auto_verifier_version,
+                "claim": self.claim,
+                "record_approvals": self.record_approvals,
+                "rejection_reasons": RejectionReason::options_json(),
+                "appeal": self.appeal,
+                "can_appeal": self.can_appeal,
             }),
         )
     }
@@ -204,6 +278,8 @@ impl IntoResponse for SolvePageResponse {
 #[derive(serde::Deserialize)]
 pub struct SolveFile {
     id: SolveId,
+    /// ID of a log file after the first, for averages.
+    file: Option<i32>,
 }
 
 pub struct SolveFileResponse {
@@ -222,11 +298,12 @@ impl RequestBody for SolveFile {
         if !solve.can_view_fmc(user.as_ref()) {
             return Err(AppError::NotAuthorized);
         }
+        let contents = match self.file {
+            Some(file_id) => state.get_extra_log_file_contents(self.id, file_id).await?,
+            None => state.get_log_file_contents(self.id, &state.pool).await?,
+        };
         Ok(SolveFileResponse {
-            contents: state
-                .get_log_file_contents(self.id, &state.pool)
-                .await?
-                .ok_or(AppError::NotFound)?,
+            contents: contents.ok_or(AppError::NotFound)?,
         })
     }
 }
@@ -34,6 +34,9 @@ pub struct SolveTableRow {
     pub program_abbreviation: String,
 
     pub total_solvers: Option<i64>,
+
+    /// Name of the moderator reviewing the solve.
+    pub claimed_by: Option<String>,
 }
 impl SolveTableRow {
     pub fn new(
@@ -111,6 +114,8 @@ impl SolveTableRow {
             program_abbreviation: solve.program.abbr.clone(),
 
             total_solvers,
+
+            claimed_by: None,
         }
     }
 }
@@ -140,6 +145,8 @@ impl From<SolvesTable> for SolvesTablesResponse {
 #[derive(serde::Serialize, Debug, Clone)]
 pub struct SolvesTable {
     pub heading: Option<String>,
+    /// Inline SVG chart shown below the heading.
+    pub sparkline: Option<String>,
     pub table_rows: LeaderboardTableRows,
     pub columns: LeaderboardTableColumns,
 }
@@ -180,6 +187,7 @@ impl SolvesTable {
 
                     SolvesTable {
                         heading: Some(group_name.to_string()),
+                        sparkline: None,
                         table_rows: solves.into(),
                         columns: columns.clone(),
                     }
@@ -224,4 +232,5 @@ pub struct LeaderboardTableColumns {
     pub program: bool,
     pub total_solvers: bool,
     pub score: bool,
+    pub claimed_by: bool,
 }
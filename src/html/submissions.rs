@@ -114,6 +114,7 @@ impl RequestBody for SolverSubmissionsTable {
 
         Ok(SolvesTable {
             heading: None,
+            sparkline: None,
             table_rows: LeaderboardTableRows::Solves(solves),
             columns: LeaderboardTableColumns {
                 puzzle: true,
@@ -127,6 +128,7 @@ impl RequestBody for SolverSubmissionsTable {
                 program: true,
                 total_solvers: false,
                 score: false,
+                claimed_by: false,
             },
         }
         .into())
@@ -139,6 +141,8 @@ pub struct PendingSubmissionsPage {}
 pub struct PendingSubmissionsPageResponse {
     user: Option<User>,
     autoverify_queue: Vec<serde_json::Value>,
+    duplicates: Vec<serde_json::Value>,
+    appeals: Vec<serde_json::Value>,
 }
 
 impl RequestBody for PendingSubmissionsPage {
@@ -154,20 +158,62 @@ impl RequestBody for PendingSubmissionsPage {
         }
 
         let mut autoverify_queue = vec![];
-        for solve_id in state.autoverifier.queue_snapshot().await {
-            let solve = state.get_solve(solve_id).await?;
+        for entry in state.get_autoverify_queue().await? {
+            let solve = state.get_solve(entry.solve_id).await?;
+            let status = if entry.is_leased() {
+                "verifying"
+            } else if entry.needs_manual_review {
+                "needs manual review"
+            } else if entry.is_waiting_to_retry() {
+                "waiting to retry"
+            } else {
+                "queued"
+            };
             autoverify_queue.push(serde_json::json!({
-                "id": solve_id,
+                "id": entry.solve_id,
                 "link": solve.relative_url(),
                 "solver_name": solve.solver.display_name(),
                 "solver_link": solve.solver.relative_url(),
                 "upload_date": solve.upload_date,
+                "enqueued_at": entry.enqueued_at,
+                "attempts": entry.attempts,
+                "next_attempt_at": entry.next_attempt_at.filter(|_| entry.is_waiting_to_retry()),
+                "last_error": entry.last_error,
+                "status": status,
             }));
         }
 
+        // Only moderators can see the pending solves themselves.
+        let mut duplicates = vec![];
+        let mut appeals = vec![];
+        if user.as_ref().is_some_and(|user| user.moderator) {
+            for duplicate in state.get_pending_duplicate_content().await? {
+                duplicates.push(serde_json::json!({
+                    "id": duplicate.solve_id,
+                    "link": duplicate.solve_id.relative_url(),
+                    "what": duplicate.what(),
+                    "other_id": duplicate.other_solve_id,
+                    "other_link": duplicate.other_solve_id.relative_url(),
+                    "other_solver_name": duplicate.other_solver.display_name(),
+                    "other_solver_link": duplicate.other_solver.relative_url(),
+                    "same_solver": duplicate.same_solver,
+                }));
+            }
+            for appeal in state.get_open_appeals().await? {
+                appeals.push(serde_json::json!({
+                    "id": appeal.solve_id,
+                    "link": appeal.solve_id.relative_url(),
+                    "message": appeal.message,
+                    "filed_at": appeal.filed_at,
+                }));
+            }
+        }
+
         Ok(PendingSubmissionsPageResponse {
             user,
             autoverify_queue,
+            duplicates,
+            appeals,
         })
     }
 }
@@ -177,7 +223,11 @@ impl IntoResponse for PendingSubmissionsPageResponse {
         crate::render_html_template(
             "pending-submissions.html",
             &self.user,
-            serde_json::json!({ "autoverify_queue": self.autoverify_queue }),
+            serde_json::json!({
+                "autoverify_queue": self.autoverify_queue,
+                "duplicates": self.duplicates,
+                "appeals": self.appeals,
+            }),
         )
     }
 }
@@ -198,23 +248,30 @@ impl RequestBody for PendingSubmissionsTable {
             return Err(AppError::NotAuthorized);
         }
 
+        let claims = state.get_pending_solve_claims().await?;
+
         let solves = state
             .get_pending_submissions()
             .await?
             .into_iter()
             .map(|solve| {
-                SolveTableRow::new(
+                let mut row = SolveTableRow::new(
                     &solve.primary_event(),
                     &solve,
                     None,
                     None,
                     &solve.primary_category_query(),
-                )
+                );
+                row.claimed_by = claims
+                    .get(&solve.id)
+                    .map(|claim| claim.moderator.display_name());
+                row
             })
             .collect();
 
         Ok(SolvesTable {
             heading: None,
+            sparkline: None,
             table_rows: LeaderboardTableRows::Solves(solves),
             columns: LeaderboardTableColumns {
                 puzzle: true,
@@ -228,6 +285,7 @@ impl RequestBody for PendingSubmissionsTable {
                 program: true,
                 total_solvers: false,
                 score: false,
+                claimed_by: true,
             },
         }
         .into())
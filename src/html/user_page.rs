@@ -4,13 +4,13 @@ use axum::body::Body;
 use axum::response::{IntoResponse, Response};
 use itertools::Itertools;
 
+use super::charts::pb_sparkline;
 use super::leaderboards::global::{GlobalLeaderboardQuery, GlobalLeaderboardTable};
 use super::solve_table::{
     LeaderboardTableColumns, LeaderboardTableRows, SolveTableRow, SolvesTable, SolvesTablesResponse,
 };
 use crate::db::{
-    Category, CategoryQuery, Event, MainPageCategory, ProgramQuery, RankedFullSolve, User, UserId,
-    VariantQuery,
+    CategoryQuery, MainPageCategory, ProgramQuery, RankedFullSolve, User, UserId, VariantQuery,
 };
 use crate::html::leaderboards::LeaderboardEvent;
 use crate::traits::RequestBody;
@@ -76,6 +76,8 @@ impl IntoResponse for SolverLeaderboardResponse {
                 "target_user_name": target_user_name,
                 "can_edit": self.can_edit,
                 "pending_submissions_count": self.pending_submissions_count,
+                "feed_url": format!("/feeds/solver.atom?id={}", self.target_user.id.0),
+                "feed_title": format!("Verified solves by {target_user_name}"),
             }),
         )
     }
@@ -96,6 +98,80 @@ pub struct SolverLeaderboardTable {
     pub history: bool,
 }
 
+impl SolverLeaderboardTable {
+    /// Returns the category query for the leaderboard.
+    pub fn category_query(&self) -> Result<CategoryQuery, AppError> {
+        let global = GlobalLeaderboardTable {
+            event: self.event,
+            filters: self.filters,
+            macros: self.macros,
+            variant: self.variant.clone(),
+            program: self.program.clone(),
+            as_of: None,
+        };
+        match global.global_leaderboard_query() {
+            GlobalLeaderboardQuery::Category(category_query) => Ok(category_query),
+            GlobalLeaderboardQuery::Score(_) => {
+                Err(AppError::InvalidQuery("bad category".to_string()))
+            }
+        }
+    }
+
+    /// Returns a table for each event with the solver's personal best
+    /// progression, newest first.
+    async fn pb_history_tables(
+        &self,
+        state: &AppState,
+        category_query: &CategoryQuery,
+    ) -> Result<SolvesTablesResponse, AppError> {
+        let progressions = state.get_solver_pb_history(self.id, category_query).await?;
+
+        let columns = LeaderboardTableColumns {
+            puzzle: false,
+            rank: true,
+            solver: false,
+            record_holder: false,
+            speed_cs: matches!(category_query, CategoryQuery::Speed { .. }),
+            move_count: matches!(category_query, CategoryQuery::Fmc { .. }),
+            verified: false,
+            date: true,
+            program: true,
+            total_solvers: false,
+            score: false,
+            claimed_by: false,
+        };
+
+        let tables = progressions
+            .into_iter()
+            .filter_map(|(_category, solves)| {
+                let latest = solves.last()?;
+                let event = category_query.event_of_solve(&latest.solve);
+                Some((latest.rank, event.name(), solves))
+            })
+            .sorted_by(|(rank1, name1, _), (rank2, name2, _)| (rank1, name1).cmp(&(rank2, name2)))
+            .map(|(_rank, name, solves)| {
+                let sparkline = pb_sparkline(&solves, category_query);
+                let rows = solves
+                    .into_iter()
+                    .rev()
+                    .map(|RankedFullSolve { rank, solve }| {
+                        let event = category_query.event_of_solve(&solve);
+                        SolveTableRow::new(&event, &solve, Some(rank), None, category_query)
+                    })
+                    .collect_vec();
+                SolvesTable {
+                    heading: Some(name),
+                    sparkline: Some(sparkline),
+                    table_rows: LeaderboardTableRows::Solves(rows),
+                    columns: columns.clone(),
+                }
+            })
+            .collect();
+
+        Ok(SolvesTablesResponse { tables })
+    }
+}
+
 impl RequestBody for SolverLeaderboardTable {
     type Response = SolvesTablesResponse;
 
@@ -104,19 +180,13 @@ impl RequestBody for SolverLeaderboardTable {
         state: AppState,
         _user: Option<User>,
     ) -> Result<Self::Response, AppError> {
-        let global = GlobalLeaderboardTable {
-            event: self.event,
-            filters: self.filters,
-            macros: self.macros,
-            variant: self.variant,
-            program: self.program,
-        };
-        let GlobalLeaderboardQuery::Category(category_query) = global.global_leaderboard_query()
-        else {
-            return Err(AppError::InvalidQuery("bad category".to_string()));
-        };
+        let category_query = self.category_query()?;
+        if self.history {
+            return self.pb_history_tables(&state, &category_query).await;
+        }
+
         let total_solvers: HashMap<MainPageCategory, i64> = state
-            .get_all_puzzles_counts(&category_query)
+            .get_all_puzzles_counts(&category_query, None)
             .await?
             .into_iter()
             .collect();
@@ -129,48 +199,14 @@ impl RequestBody for SolverLeaderboardTable {
                 (solve.rank, *total_solvers.get(category).unwrap_or(&0))
             })
             .map(|(_category, RankedFullSolve { rank, solve })| {
-                let event = Event {
-                    puzzle: solve.puzzle.clone(),
-                    category: match &category_query {
-                        CategoryQuery::Speed {
-                            average,
-                            blind,
-                            filters,
-                            macros,
-                            one_handed,
-                            variant: _,
-                            program: _,
-                        } => {
-                            let default_filters = match &solve.variant {
-                                Some(v) => v.primary_filters,
-                                None => solve.puzzle.primary_filters,
-                            };
-                            let default_macros = match &solve.variant {
-                                Some(v) => v.primary_macros,
-                                None => solve.puzzle.primary_macros,
-                            };
-                            Category::Speed {
-                                average: *average,
-                                blind: *blind,
-                                filters: filters.unwrap_or(default_filters),
-                                macros: macros.unwrap_or(default_macros),
-                                one_handed: *one_handed,
-                                variant: solve.variant.clone(),
-                                material: solve.program.material,
-                            }
-                        }
-
-                        CategoryQuery::Fmc { computer_assisted } => Category::Fmc {
-                            computer_assisted: *computer_assisted,
-                        },
-                    },
-                };
+                let event = category_query.event_of_solve(&solve);
                 SolveTableRow::new(&event, &solve, Some(rank), None, &category_query)
             })
             .collect();
 
         Ok(SolvesTable {
             heading: None,
+            sparkline: None,
             table_rows: LeaderboardTableRows::Solves(solve_rows),
             columns: LeaderboardTableColumns {
                 puzzle: true,
@@ -184,6 +220,7 @@ impl RequestBody for SolverLeaderboardTable {
                 program: true,
                 total_solvers: false,
                 score: false,
+                claimed_by: false,
             },
         }
         .grouped())
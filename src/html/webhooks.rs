@@ -0,0 +1,56 @@
+use axum::response::IntoResponse;
+use itertools::Itertools;
+
+use crate::db::{User, Webhook, WebhookDelivery};
+use crate::traits::RequestBody;
+use crate::webhooks::WebhookEvent;
+use crate::{AppError, AppState};
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct WebhooksPage {}
+
+pub struct WebhooksPageResponse {
+    user: Option<User>,
+    webhooks: Vec<Webhook>,
+    deliveries: Vec<WebhookDelivery>,
+    api_docs_link: String,
+}
+
+impl RequestBody for WebhooksPage {
+    type Response = WebhooksPageResponse;
+
+    async fn request(
+        self,
+        state: AppState,
+        user: Option<User>,
+    ) -> Result<Self::Response, AppError> {
+        if !user.as_ref().ok_or(AppError::NotLoggedIn)?.moderator {
+            return Err(AppError::NotAuthorized);
+        }
+
+        Ok(WebhooksPageResponse {
+            user,
+            webhooks: state.get_all_webhooks().await?,
+            deliveries: state.get_recent_webhook_deliveries().await?,
+            api_docs_link: format!(
+                "{}/blob/main/docs/api.md#webhooks",
+                env!("CARGO_PKG_REPOSITORY")
+            ),
+        })
+    }
+}
+
+impl IntoResponse for WebhooksPageResponse {
+    fn into_response(self) -> axum::response::Response {
+        crate::render_html_template(
+            "webhooks.html",
+            &self.user,
+            serde_json::json!({
+                "webhooks": self.webhooks,
+                "deliveries": self.deliveries,
+                "event_names": WebhookEvent::ALL.iter().map(|event| event.name()).collect_vec(),
+                "api_docs_link": self.api_docs_link,
+            }),
+        )
+    }
+}
@@ -0,0 +1,781 @@
+//! Import of solves from CSV or JSON files.
+//!
+//! Files use the same column names as the exports in [`crate::export`], so an
+//! export can be imported again. See `docs/api.md` for documentation.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use eyre::{Result, bail, eyre};
+use itertools::Itertools;
+
+use crate::AppState;
+use crate::db::{FullSolve, SolveDbFields, UserData, UserId};
+
+/// Row of an import file, with each non-empty field as a string.
+pub struct ImportRow {
+    /// Line number in CSV files, or index starting at 1 in JSON files.
+    pub line: usize,
+    pub fields: HashMap<String, String>,
+}
+impl ImportRow {
+    fn get(&self, column: &str) -> Option<&str> {
+        self.fields
+            .get(column)
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn parse<T: FromStr>(&self, column: &str) -> Result<Option<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        self.get(column)
+            .map(|s| s.parse().map_err(|e| eyre!("invalid {column} {s:?}: {e}")))
+            .transpose()
+    }
+
+    fn flag(&self, column: &str) -> Result<bool> {
+        match self.get(column) {
+            None | Some("false" | "FALSE" | "0" | "no") => Ok(false),
+            Some("true" | "TRUE" | "1" | "yes") => Ok(true),
+            Some(other) => bail!("invalid {column} {other:?}"),
+        }
+    }
+
+    fn solve_date(&self) -> Result<DateTime<Utc>> {
+        let s = self
+            .get("solve_date")
+            .ok_or_else(|| eyre!("missing solve_date"))?;
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+            return Ok(datetime.to_utc());
+        }
+        match s.parse::<NaiveDate>() {
+            Ok(date) => Ok(date
+                .and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default())
+                .and_utc()),
+            Err(_) => bail!("invalid solve_date {s:?}"),
+        }
+    }
+}
+
+/// Reads rows from a CSV file with a header row or a JSON file containing an
+/// array of objects, based on the file extension.
+pub fn read_rows(path: &Path) -> Result<Vec<ImportRow>> {
+    let contents = std::fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => csv_rows(&contents),
+        Some("json") => json_rows(&contents),
+        _ => bail!(
+            "unknown file type for {}; expected .csv or .json",
+            path.display()
+        ),
+    }
+}
+
+fn csv_rows(contents: &str) -> Result<Vec<ImportRow>> {
+    let mut records = parse_csv(contents)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Ok(vec![]);
+    };
+    records
+        .filter(|(_, record)| record.iter().any(|field| !field.is_empty()))
+        .map(|(line, record)| {
+            if record.len() != header.len() {
+                bail!(
+                    "line {line}: expected {} fields but found {}",
+                    header.len(),
+                    record.len(),
+                );
+            }
+            let fields = header
+                .iter()
+                .map(|column| column.trim().to_string())
+                .zip(record.into_iter().map(unescape_formula))
+                .collect();
+            Ok(ImportRow { line, fields })
+        })
+        .collect()
+}
+
+/// Removes the `'` that exports put before fields that would otherwise be
+/// interpreted as formulas.
+fn unescape_formula(field: String) -> String {
+    match field.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(crate::export::FORMULA_PREFIXES) => rest.to_string(),
+        _ => field,
+    }
+}
+
+/// Parses CSV as specified in RFC 4180, returning each record along with the
+/// line number on which it starts.
+fn parse_csv(contents: &str) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut in_quotes = false;
+
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => (),
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("line {record_line}: unterminated quoted field");
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    Ok(records)
+}
+
+fn json_rows(contents: &str) -> Result<Vec<ImportRow>> {
+    let objects: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(contents)?;
+    Ok(objects
+        .into_iter()
+        .enumerate()
+        .map(|(i, object)| ImportRow {
+            line: i + 1,
+            fields: object
+                .into_iter()
+                .filter_map(|(k, v)| match v {
+                    serde_json::Value::Null => None,
+                    serde_json::Value::String(s) => Some((k, s)),
+                    other => Some((k, other.to_string())),
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Solver of an imported solve.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ImportSolver {
+    Existing(UserId),
+    /// New solver, to be created with the given name.
+    New(String),
+}
+
+/// Fields that identify duplicate solves.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DuplicateKey {
+    solver: ImportSolver,
+    puzzle_id: i32,
+    variant_id: Option<i32>,
+    flags: [bool; 6],
+    speed_cs: Option<i32>,
+    move_count: Option<i32>,
+    solve_date: NaiveDate,
+}
+impl DuplicateKey {
+    fn new(solver: ImportSolver, data: &SolveDbFields) -> Self {
+        Self {
+            solver,
+            puzzle_id: data.puzzle_id,
+            variant_id: data.variant_id,
+            flags: [
+                data.average,
+                data.blind,
+                data.filters,
+                data.macros,
+                data.one_handed,
+                data.computer_assisted,
+            ],
+            speed_cs: data.speed_cs,
+            move_count: data.move_count,
+            solve_date: data.solve_date.date_naive(),
+        }
+    }
+
+    fn from_existing(solve: &FullSolve) -> Self {
+        Self::new(
+            ImportSolver::Existing(solve.solver.id),
+            &SolveDbFields::from(solve.clone()),
+        )
+    }
+}
+
+/// Solve to be imported.
+struct PlannedSolve {
+    line: usize,
+    solver: ImportSolver,
+    data: SolveDbFields,
+    description: String,
+}
+
+/// Lookup tables for resolving names and IDs in import files.
+struct ImportLookup {
+    puzzle_names: HashMap<i32, String>,
+    puzzles_by_name: HashMap<String, i32>,
+    puzzles_by_hsc_id: HashMap<String, i32>,
+    program_abbrs: HashMap<i32, String>,
+    programs_by_abbr: HashMap<String, i32>,
+    variant_names: HashMap<i32, String>,
+    variants_by_name: HashMap<String, i32>,
+    user_ids: HashSet<UserId>,
+    users_by_name: HashMap<String, Vec<UserId>>,
+}
+impl ImportLookup {
+    async fn new(state: &AppState) -> Result<Self> {
+        let puzzles = state.get_all_puzzles().await?;
+        let programs = state.get_all_programs().await?;
+        let variants = state.get_all_variants().await?;
+        let users = state.get_all_users().await?;
+
+        let mut puzzles_by_hsc_id: HashMap<String, i32> = state
+            .get_legacy_hsc_puzzle_ids()
+            .await?
+            .into_iter()
+            .map(|(hsc_id, puzzle_id)| (hsc_id, puzzle_id.0))
+            .collect();
+        // Prefer the ID stored on the puzzle itself
+        puzzles_by_hsc_id.extend(
+            puzzles
+                .iter()
+                .filter_map(|p| Some((p.hsc_id.clone()?, p.id.0))),
+        );
+
+        Ok(Self {
+            puzzle_names: puzzles.iter().map(|p| (p.id.0, p.name.clone())).collect(),
+            puzzles_by_name: puzzles.iter().map(|p| (p.name.clone(), p.id.0)).collect(),
+            puzzles_by_hsc_id,
+            program_abbrs: programs.iter().map(|p| (p.id.0, p.abbr.clone())).collect(),
+            programs_by_abbr: programs.iter().map(|p| (p.abbr.clone(), p.id.0)).collect(),
+            variant_names: variants.iter().map(|v| (v.id.0, v.name.clone())).collect(),
+            variants_by_name: variants
+                .iter()
+                .flat_map(|v| [(v.name.clone(), v.id.0), (v.abbr.clone(), v.id.0)])
+                .collect(),
+            user_ids: users.iter().filter(|u| !u.dummy).map(|u| u.id).collect(),
+            users_by_name: users
+                .iter()
+                .filter(|u| !u.dummy)
+                .filter_map(|u| Some((u.name.clone()?, u.id)))
+                .into_group_map(),
+        })
+    }
+
+    fn puzzle(&self, row: &ImportRow) -> Result<i32> {
+        if let Some(id) = row.parse::<i32>("puzzle_id")? {
+            return match self.puzzle_names.contains_key(&id) {
+                true => Ok(id),
+                false => bail!("unknown puzzle_id {id}"),
+            };
+        }
+        if let Some(hsc_id) = row.get("hsc_id") {
+            return self
+                .puzzles_by_hsc_id
+                .get(hsc_id)
+                .copied()
+                .ok_or_else(|| eyre!("unknown hsc_id {hsc_id:?}"));
+        }
+        if let Some(name) = row.get("puzzle_name") {
+            return self
+                .puzzles_by_name
+                .get(name)
+                .copied()
+                .ok_or_else(|| eyre!("unknown puzzle_name {name:?}"));
+        }
+        bail!("missing puzzle_id, hsc_id, or puzzle_name")
+    }
+
+    fn program(&self, row: &ImportRow) -> Result<i32> {
+        if let Some(id) = row.parse::<i32>("program_id")? {
+            return match self.program_abbrs.contains_key(&id) {
+                true => Ok(id),
+                false => bail!("unknown program_id {id}"),
+            };
+        }
+        if let Some(abbr) = row.get("program_abbr") {
+            return self
+                .programs_by_abbr
+                .get(abbr)
+                .copied()
+                .ok_or_else(|| eyre!("unknown program_abbr {abbr:?}"));
+        }
+        bail!("missing program_id or program_abbr")
+    }
+
+    fn variant(&self, row: &ImportRow) -> Result<Option<i32>> {
+        if let Some(id) = row.parse::<i32>("variant_id")? {
+            return match self.variant_names.contains_key(&id) {
+                true => Ok(Some(id)),
+                false => bail!("unknown variant_id {id}"),
+            };
+        }
+        row.get("variant_name")
+            .map(|name| {
+                self.variants_by_name
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| eyre!("unknown variant_name {name:?}"))
+            })
+            .transpose()
+    }
+
+    fn solver(&self, row: &ImportRow) -> Result<ImportSolver> {
+        if let Some(id) = row.parse::<i32>("solver_id")? {
+            return match self.user_ids.contains(&UserId(id)) {
+                true => Ok(ImportSolver::Existing(UserId(id))),
+                false => bail!("unknown solver_id {id}"),
+            };
+        }
+        let Some(name) = row.get("solver_name") else {
+            bail!("missing solver_id or solver_name");
+        };
+        match self.users_by_name.get(name).map(Vec::as_slice) {
+            None | Some([]) => Ok(ImportSolver::New(name.to_string())),
+            Some([id]) => Ok(ImportSolver::Existing(*id)),
+            Some(ids) => bail!(
+                "ambiguous solver_name {name:?} could be any of user IDs {}",
+                ids.iter().map(|id| id.0).join(", "),
+            ),
+        }
+    }
+
+    fn solve_data(&self, row: &ImportRow, solve_date: DateTime<Utc>) -> Result<SolveDbFields> {
+        let speed_cs = row.parse::<i32>("speed_cs")?.filter(|&cs| cs > 0);
+        let move_count = row.parse::<i32>("move_count")?.filter(|&n| n > 0);
+        if speed_cs.is_none() && move_count.is_none() {
+            bail!("missing speed_cs or move_count");
+        }
+
+        Ok(SolveDbFields {
+            puzzle_id: self.puzzle(row)?,
+            variant_id: self.variant(row)?,
+            program_id: self.program(row)?,
+
+            solver_id: 0, // set when importing
+            solve_date,
+            solver_notes: row.get("solver_notes").unwrap_or_default().to_string(),
+            moderator_notes: None, // set when importing
+            auto_verify_output: None,
+
+            average: row.flag("average")?,
+            blind: row.flag("blind")?,
+            filters: row.flag("filters")?,
+            macros: row.flag("macros")?,
+            one_handed: row.flag("one_handed")?,
+            computer_assisted: row.flag("computer_assisted")?,
+
+            move_count,
+            speed_cs,
+            memo_cs: row.parse::<i32>("memo_cs")?.filter(|&cs| cs > 0),
+
+            log_file: None,
+            video_url: row.get("video_url").map(str::to_owned),
+        })
+    }
+
+    fn describe(&self, solver: &ImportSolver, data: &SolveDbFields) -> String {
+        let solver = match solver {
+            ImportSolver::Existing(id) => format!("user #{}", id.0),
+            ImportSolver::New(name) => format!("new solver {name:?}"),
+        };
+        let puzzle = self
+            .puzzle_names
+            .get(&data.puzzle_id)
+            .map_or("?", String::as_str);
+        let variant = data
+            .variant_id
+            .and_then(|id| self.variant_names.get(&id))
+            .map(|name| format!(" ({name})"))
+            .unwrap_or_default();
+        let program = self
+            .program_abbrs
+            .get(&data.program_id)
+            .map_or("?", String::as_str);
+        let results = [
+            data.speed_cs.map(crate::util::render_time),
+            data.move_count.map(|n| format!("{n} STM")),
+        ]
+        .into_iter()
+        .flatten()
+        .join(" / ");
+        let flags = [
+            ("average", data.average),
+            ("blind", data.blind),
+            ("filters", data.filters),
+            ("macros", data.macros),
+            ("one-handed", data.one_handed),
+            ("computer assisted", data.computer_assisted),
+        ]
+        .into_iter()
+        .filter(|&(_, b)| b)
+        .map(|(s, _)| s)
+        .join(", ");
+        format!(
+            "{puzzle}{variant} {results} by {solver} in {program} on {} [{flags}]",
+            data.solve_date.format("%Y-%m-%d"),
+        )
+    }
+}
+
+impl AppState {
+    /// Imports solves from a CSV or JSON file, skipping rows that cannot be
+    /// resolved or that duplicate an existing solve. If `dry_run` is set, only
+    /// prints what would be imported.
+    ///
+    /// Solves and new solvers are added by the migration dummy user, so that
+    /// they are marked as imported in the audit log. Imported solves are not
+    /// verified.
+    pub async fn import_solves(&self, path: &Path, dry_run: bool) -> Result<()> {
+        let rows = read_rows(path)?;
+        let lookup = ImportLookup::new(self).await?;
+
+        let mut existing_solves: HashMap<UserId, Vec<DuplicateKey>> = HashMap::new();
+        let mut seen = HashSet::new();
+        let mut planned = vec![];
+        let mut conflicts = 0;
+
+        for row in &rows {
+            let result = async {
+                let solve_date = row.solve_date()?;
+                let solver = lookup.solver(row)?;
+                let data = lookup.solve_data(row, solve_date)?;
+                let key = DuplicateKey::new(solver.clone(), &data);
+
+                if let ImportSolver::Existing(id) = &solver {
+                    let id = *id;
+                    if !existing_solves.contains_key(&id) {
+                        let keys = self
+                            .get_solver_submissions(id)
+                            .await?
+                            .iter()
+                            .map(DuplicateKey::from_existing)
+                            .collect();
+                        existing_solves.insert(id, keys);
+                    }
+                    if existing_solves[&id].contains(&key) {
+                        bail!("duplicate of an existing solve");
+                    }
+                }
+                if !seen.insert(key) {
+                    bail!("duplicate of an earlier row");
+                }
+
+                let description = lookup.describe(&solver, &data);
+                eyre::Ok(PlannedSolve {
+                    line: row.line,
+                    solver,
+                    data,
+                    description,
+                })
+            }
+            .await;
+
+            match result {
+                Ok(solve) => {
+                    println!("{}", report_planned(&solve));
+                    planned.push(solve);
+                }
+                Err(e) => {
+                    println!("{}", report_skipped(row.line, &e));
+                    conflicts += 1;
+                }
+            }
+        }
+
+        let new_solvers = planned
+            .iter()
+            .filter_map(|solve| match &solve.solver {
+                ImportSolver::New(name) => Some(name.clone()),
+                ImportSolver::Existing(_) => None,
+            })
+            .unique()
+            .collect_vec();
+        print!(
+            "{}",
+            report_summary(planned.len(), &new_solvers, conflicts, dry_run),
+        );
+        if dry_run {
+            return Ok(());
+        }
+
+        let migration = self.get_migration_dummy_user().await?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut new_solver_ids = HashMap::new();
+        for name in new_solvers {
+            let id = self.add_user(&migration, new_solver_data(&name)).await?;
+            new_solver_ids.insert(name, id);
+        }
+
+        for PlannedSolve {
+            line,
+            solver,
+            mut data,
+            ..
+        } in planned
+        {
+            let solver_id = match solver {
+                ImportSolver::Existing(id) => id,
+                ImportSolver::New(name) => *new_solver_ids
+                    .get(&name)
+                    .ok_or_else(|| eyre!("solver {name:?} was not created"))?,
+            };
+            data.solver_id = solver_id.0;
+            data.moderator_notes = Some(format!("Imported from {file_name}, line {line}"));
+            let solve_id = self
+                .add_solve_external(&migration, data, vec![], false, false)
+                .await?;
+            println!("Imported line {line} as solve #{}", solve_id.0);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the line of the import report for a row that will be imported.
+fn report_planned(solve: &PlannedSolve) -> String {
+    format!("+ line {}: {}", solve.line, solve.description)
+}
+
+/// Returns the line of the import report for a row that will be skipped.
+fn report_skipped(line: usize, error: &eyre::Report) -> String {
+    format!("! line {line}: {error}")
+}
+
+/// Returns the end of the import report, listing new solvers and the totals.
+fn report_summary(
+    planned_count: usize,
+    new_solvers: &[String],
+    conflicts: usize,
+    dry_run: bool,
+) -> String {
+    let mut ret = String::new();
+    for name in new_solvers {
+        ret += &format!("+ solver {name:?}\n");
+    }
+    ret += &format!(
+        "{planned_count} solves and {} new solvers to import; {conflicts} rows skipped\n",
+        new_solvers.len(),
+    );
+    if dry_run {
+        ret += "Dry run; nothing was imported.\n";
+    }
+    ret
+}
+
+fn new_solver_data(name: &str) -> UserData {
+    UserData {
+        email: None,
+        discord_id: Default::default(),
+        name: Some(name.to_string()),
+        moderator: false,
+        moderator_notes: String::new(),
+        dummy: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup() -> ImportLookup {
+        ImportLookup {
+            puzzle_names: HashMap::from([(1, "3x3x3x3".to_string())]),
+            puzzles_by_name: HashMap::from([("3x3x3x3".to_string(), 1)]),
+            puzzles_by_hsc_id: HashMap::from([("ft_hypercube:3".to_string(), 1)]),
+            program_abbrs: HashMap::from([(2, "HSC2".to_string())]),
+            programs_by_abbr: HashMap::from([("HSC2".to_string(), 2)]),
+            variant_names: HashMap::from([(3, "Physical".to_string())]),
+            variants_by_name: HashMap::from([("Physical".to_string(), 3), ("phys".to_string(), 3)]),
+            user_ids: HashSet::from([UserId(10), UserId(11), UserId(12)]),
+            users_by_name: HashMap::from([
+                ("Alice".to_string(), vec![UserId(10)]),
+                ("Sam".to_string(), vec![UserId(11), UserId(12)]),
+            ]),
+        }
+    }
+
+    fn csv(contents: &str) -> Vec<ImportRow> {
+        csv_rows(contents).expect("error parsing CSV")
+    }
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let records = parse_csv(
+            "a,\"b,c\",\"say \"\"hi\"\"\"\r\n\
+             \"multi\nline\",,x\n\
+             last",
+        )
+        .expect("error parsing CSV");
+        let expected: Vec<(usize, Vec<String>)> = vec![
+            (1, vec!["a".into(), "b,c".into(), "say \"hi\"".into()]),
+            (2, vec!["multi\nline".into(), "".into(), "x".into()]),
+            (4, vec!["last".into()]),
+        ];
+        assert_eq!(records, expected);
+    }
+
+    #[test]
+    fn test_parse_csv_unterminated_quote() {
+        let err = parse_csv("a,b\n\"c,d\n").expect_err("expected error");
+        assert_eq!(err.to_string(), "line 2: unterminated quoted field");
+    }
+
+    #[test]
+    fn test_csv_rows() {
+        let rows = csv(" solver_name ,puzzle_name,speed_cs\nAlice,3x3x3x3,1234\n,,\nBob,,\n");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].get("solver_name"), Some("Alice"));
+        assert_eq!(rows[0].get("speed_cs"), Some("1234"));
+        assert_eq!(rows[1].line, 4);
+        assert_eq!(rows[1].get("puzzle_name"), None);
+
+        let err = csv_rows("a,b\n1,2,3\n").err().expect("expected error");
+        assert_eq!(err.to_string(), "line 2: expected 2 fields but found 3");
+
+        let rows = csv("solver_name,solver_notes\n'=Alice,'quoted\n");
+        assert_eq!(rows[0].get("solver_name"), Some("=Alice"));
+        assert_eq!(rows[0].get("solver_notes"), Some("'quoted"));
+    }
+
+    #[test]
+    fn test_valid_rows() {
+        let lookup = lookup();
+        let rows = csv(
+            "solver_name,hsc_id,variant_name,program_abbr,solve_date,speed_cs,move_count,blind\n\
+             Alice,ft_hypercube:3,phys,HSC2,2024-05-06,6123,,yes\n\
+             New Solver,ft_hypercube:3,,HSC2,2024-05-06T01:02:03Z,,200,\n",
+        );
+
+        let date = rows[0].solve_date().expect("error parsing date");
+        assert_eq!(date.to_rfc3339(), "2024-05-06T12:00:00+00:00");
+        let solver = lookup.solver(&rows[0]).expect("error resolving solver");
+        assert_eq!(solver, ImportSolver::Existing(UserId(10)));
+        let data = lookup
+            .solve_data(&rows[0], date)
+            .expect("error resolving solve");
+        assert_eq!(data.puzzle_id, 1);
+        assert_eq!(data.variant_id, Some(3));
+        assert_eq!(data.program_id, 2);
+        assert_eq!(data.speed_cs, Some(6123));
+        assert_eq!(data.move_count, None);
+        assert!(data.blind && !data.average);
+        assert_eq!(
+            lookup.describe(&solver, &data),
+            "3x3x3x3 (Physical) 1m 01.23s by user #10 in HSC2 on 2024-05-06 [blind]",
+        );
+
+        let date = rows[1].solve_date().expect("error parsing date");
+        assert_eq!(date.to_rfc3339(), "2024-05-06T01:02:03+00:00");
+        let solver = lookup.solver(&rows[1]).expect("error resolving solver");
+        assert_eq!(solver, ImportSolver::New("New Solver".to_string()));
+        let data = lookup
+            .solve_data(&rows[1], date)
+            .expect("error resolving solve");
+        assert_eq!(data.move_count, Some(200));
+        assert_eq!(
+            lookup.describe(&solver, &data),
+            "3x3x3x3 200 STM by new solver \"New Solver\" in HSC2 on 2024-05-06 []",
+        );
+    }
+
+    #[test]
+    fn test_bad_rows() {
+        let lookup = lookup();
+        let rows = csv(
+            "solver_id,solver_name,puzzle_name,program_abbr,solve_date,speed_cs,average\n\
+             ,Sam,3x3x3x3,HSC2,2024-05-06,100,\n\
+             99,,3x3x3x3,HSC2,2024-05-06,100,\n\
+             10,,4x4x4x4,HSC2,2024-05-06,100,\n\
+             10,,3x3x3x3,HSC2,2024-05-06,,\n\
+             10,,3x3x3x3,HSC2,2024-05-06,abc,\n\
+             10,,3x3x3x3,HSC2,2024-05-06,100,maybe\n\
+             10,,3x3x3x3,HSC2,yesterday,100,\n",
+        );
+        let date = DateTime::UNIX_EPOCH;
+
+        let err = |result: Result<_>| result.err().expect("expected error").to_string();
+        assert_eq!(
+            err(lookup.solver(&rows[0]).map(|_| ())),
+            "ambiguous solver_name \"Sam\" could be any of user IDs 11, 12",
+        );
+        assert_eq!(
+            err(lookup.solver(&rows[1]).map(|_| ())),
+            "unknown solver_id 99",
+        );
+        assert_eq!(
+            err(lookup.solve_data(&rows[2], date).map(|_| ())),
+            "unknown puzzle_name \"4x4x4x4\"",
+        );
+        assert_eq!(
+            err(lookup.solve_data(&rows[3], date).map(|_| ())),
+            "missing speed_cs or move_count",
+        );
+        assert!(err(lookup.solve_data(&rows[4], date).map(|_| ())).starts_with("invalid speed_cs"));
+        assert_eq!(
+            err(lookup.solve_data(&rows[5], date).map(|_| ())),
+            "invalid average \"maybe\"",
+        );
+        assert_eq!(
+            err(rows[6].solve_date().map(|_| ())),
+            "invalid solve_date \"yesterday\"",
+        );
+    }
+
+    #[test]
+    fn test_dry_run_report() {
+        let solve = PlannedSolve {
+            line: 2,
+            solver: ImportSolver::New("Bob".to_string()),
+            data: lookup()
+                .solve_data(
+                    &csv("puzzle_id,program_id,move_count\n1,2,50\n")[0],
+                    DateTime::UNIX_EPOCH,
+                )
+                .expect("error resolving solve"),
+            description: "3x3x3x3 50 STM".to_string(),
+        };
+        assert_eq!(report_planned(&solve), "+ line 2: 3x3x3x3 50 STM");
+        assert_eq!(
+            report_skipped(3, &eyre!("duplicate of an earlier row")),
+            "! line 3: duplicate of an earlier row",
+        );
+        assert_eq!(
+            report_summary(1, &["Bob".to_string()], 1, true),
+            "+ solver \"Bob\"\n\
+             1 solves and 1 new solvers to import; 1 rows skipped\n\
+             Dry run; nothing was imported.\n",
+        );
+        assert_eq!(
+            report_summary(0, &[], 0, false),
+            "0 solves and 0 new solvers to import; 0 rows skipped\n",
+        );
+    }
+}
@@ -29,6 +29,7 @@ extern crate lazy_static;
 mod macros;
 mod api;
 mod autoverify;
+mod chart;
 mod cli;
 mod cookies;
 mod db;
@@ -36,12 +37,20 @@ mod discord;
 mod email;
 mod env;
 mod error;
+mod export;
+mod feeds;
 mod html;
+mod import;
 mod puzzle_groups;
+mod reverify;
 mod routes;
 mod static_files;
+#[cfg(test)]
+mod tests;
 mod traits;
 mod util;
+mod verifier;
+mod webhooks;
 
 use static_files::{HBS, render_html_template, render_template};
 
@@ -64,6 +73,8 @@ struct AppState {
     recently_submitted: Arc<Mutex<HashMap<Vec<u8>, RecentlySubmittedSolve>>>,
     /// Queue of solves to auto-verify.
     pub autoverifier: Arc<SolveAutoVerifier>,
+    /// HTTP client for delivering webhooks.
+    http_client: reqwest::Client,
 
     /// Discord bot state.
     discord: Option<DiscordAppState>,
@@ -208,6 +219,9 @@ async fn main() {
     // Load handlebars templates.
     lazy_static::initialize(&HBS);
 
+    // Check the verifier configuration before any worker needs it.
+    lazy_static::initialize(&env::EXTRA_VERIFIERS);
+
     // Set gateway intents, which decides what events the bot will be notified about
     let intents = sy::GatewayIntents::non_privileged() | sy::GatewayIntents::GUILD_MEMBERS;
 
@@ -251,6 +265,7 @@ async fn main() {
         pkce_hash_values: Default::default(),
         recently_submitted: Default::default(),
         autoverifier: Arc::new(SolveAutoVerifier::default()),
+        http_client: reqwest::Client::new(),
 
         discord: Some(DiscordAppState { http, cache, shard }),
         turnstile: Some(Arc::new(TurnstileClient::new(
@@ -273,6 +288,10 @@ async fn main() {
                 commands: vec![
                     // User commands
                     discord::user::user(),
+                    // Leaderboard commands
+                    discord::lookup::wr(),
+                    discord::lookup::pb(),
+                    discord::lookup::leaderboard(),
                     // Verify commands
                     discord::verify::accept(),
                     discord::verify::reject(),
@@ -282,13 +301,18 @@ async fn main() {
                     discord::admin::shutdown(),
                     discord::admin::restart(),
                     discord::admin::update(),
+                    discord::admin::reverify(),
                     // Block/unblock commands
                     discord::panic::panic(),
                 ],
-                event_handler: |_sy_ctx, ev, _ctx, _| {
+                event_handler: |sy_ctx, ev, _ctx, state| {
                     Box::pin(async move {
                         match ev {
                             sy::FullEvent::Ready { .. } => tracing::info!("Discord bot is ready"),
+                            sy::FullEvent::InteractionCreate { interaction } => {
+                                discord::verify::handle_interaction(sy_ctx, state, interaction)
+                                    .await?;
+                            }
                             _ => (),
                         }
                         Ok(())
@@ -326,34 +350,37 @@ async fn main() {
         }
     });
 
+    args.command
+        .unwrap_or_default()
+        .execute(state, shutdown_rx)
+        .await
+        .expect("error executing command");
+}
+
+async fn run_web_server(state: AppState, mut shutdown_rx: mpsc::Receiver<String>) {
+    let restart_requested = Arc::clone(&state.restart_requested);
+
+    // Only the web server processes the auto-verification queue, so that CLI
+    // commands don't take over solves from a running server.
     tokio::spawn({
         let state = state.clone();
         async move {
             if !matches!(std::fs::exists(&*crate::env::HSC2_PATH), Ok(true)) {
                 tracing::error!("Error finding HSC2 executable. Auto-verification may fail.");
             }
-
-            loop {
-                let solve_id = state.autoverifier.wait_for_next().await;
-                if let Err(e) = state.autoverify_solve_immediately(solve_id).await {
-                    tracing::error!("Autoverify error: {e}");
+            for (program_abbr, path) in &*crate::env::EXTRA_VERIFIERS {
+                if !matches!(std::fs::exists(path), Ok(true)) {
+                    tracing::error!(
+                        "Error finding {program_abbr} verifier executable. \
+                         Auto-verification may fail."
+                    );
                 }
-                state.alert_discord_of_auto_verification(solve_id).await;
-                state.autoverifier.pop_next().await;
             }
+
+            state.run_autoverify_workers().await;
         }
     });
 
-    args.command
-        .unwrap_or_default()
-        .execute(state, shutdown_rx)
-        .await
-        .expect("error executing command");
-}
-
-async fn run_web_server(state: AppState, mut shutdown_rx: mpsc::Receiver<String>) {
-    let restart_requested = Arc::clone(&state.restart_requested);
-
     let app = routes::router()
         .layer(axum::extract::DefaultBodyLimit::max(100 * 1024 * 1024)) // 100 MiB
         .layer(
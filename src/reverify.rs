@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools;
+
+use crate::autoverify::{AutoVerifyOutcome, VerifiedSolve};
+use crate::db::{AuditLogEvent, PuzzleId, SolveId, User, UserId};
+use crate::traits::Linkable;
+use crate::{AppResult, AppState};
+
+/// Maximum number of solves to list in a Discord report.
+const MAX_DISCORD_REPORT_LINES: usize = 20;
+
+/// Which auto-verified solves to re-verify.
+#[derive(Debug, Default, Clone)]
+pub struct ReverifyFilter {
+    /// Only solves last verified by this verifier version.
+    /// [`crate::db::UNKNOWN_VERIFIER_VERSION`] matches solves verified before
+    /// versions were recorded.
+    pub verifier_version: Option<String>,
+    /// Only solves of this puzzle.
+    pub puzzle_id: Option<PuzzleId>,
+}
+
+/// What was done with a solve whose result changed when it was re-verified.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReverifyAction {
+    /// The solve was updated with the new result.
+    Updated,
+    /// The solve was left unchanged and marked as needing manual review,
+    /// because it has been accepted or a moderator has already reviewed it.
+    HeldForReview,
+}
+
+/// Solve whose verdict, move count, or time changed when it was re-verified.
+#[derive(Debug, Clone)]
+pub struct ReverifyChange {
+    pub solve_id: SolveId,
+    pub changes: Vec<String>,
+    pub action: ReverifyAction,
+}
+impl ReverifyChange {
+    /// Returns a description of the change, without the solve ID.
+    fn details(&self) -> String {
+        let action = match self.action {
+            ReverifyAction::Updated => "updated",
+            ReverifyAction::HeldForReview => "held for review",
+        };
+        format!("({action}): {}", self.changes.join("; "))
+    }
+}
+
+/// Results of re-verifying a set of solves.
+#[derive(Debug, Default, Clone)]
+pub struct ReverifyReport {
+    /// Number of solves that were re-verified successfully.
+    pub checked: usize,
+    pub changed: Vec<ReverifyChange>,
+    /// Solves that could not be re-verified.
+    pub errors: Vec<(SolveId, String)>,
+}
+impl ReverifyReport {
+    fn summary(&self) -> String {
+        format!(
+            "Re-verified {} solves: {} changed, {} held for review, {} errors",
+            self.checked,
+            self.changed.len(),
+            self.changed
+                .iter()
+                .filter(|change| change.action == ReverifyAction::HeldForReview)
+                .count(),
+            self.errors.len(),
+        )
+    }
+
+    /// Returns a plain text report listing every changed solve.
+    pub fn to_text(&self) -> String {
+        itertools::chain!(
+            [self.summary()],
+            self.changed.iter().map(|change| format!(
+                "Solve {} {}",
+                change.solve_id,
+                change.details()
+            )),
+            self.errors
+                .iter()
+                .map(|(solve_id, e)| format!("Solve {solve_id} (error): {e}")),
+        )
+        .join("\n")
+    }
+
+    /// Returns a Markdown report suitable for a Discord message.
+    pub fn to_markdown(&self) -> String {
+        let lines = itertools::chain!(
+            self.changed.iter().map(|change| format!(
+                "- {} {}",
+                change.solve_id.md_link(false),
+                change.details()
+            )),
+            self.errors.iter().map(|(solve_id, e)| {
+                format!(
+                    "- {} (error): {}",
+                    solve_id.md_link(false),
+                    crate::util::md_escape(e)
+                )
+            }),
+        )
+        .collect_vec();
+        let mut ret = format!(":mag: {}", self.summary());
+        for line in lines.iter().take(MAX_DISCORD_REPORT_LINES) {
+            ret += "\n";
+            ret += line;
+        }
+        if lines.len() > MAX_DISCORD_REPORT_LINES {
+            ret += &format!("\n… and {} more", lines.len() - MAX_DISCORD_REPORT_LINES);
+        }
+        ret
+    }
+}
+
+impl AppState {
+    /// Starts re-verifying solves in the background and posts the report to
+    /// the private updates channel when done. Returns `false` if a
+    /// re-verification is already running.
+    pub fn start_reverify_campaign(&self, filter: ReverifyFilter, requested_by: String) -> bool {
+        let Some(guard) = self.autoverifier.try_start_reverify() else {
+            return false;
+        };
+        tracing::info!(?filter, "{requested_by} started re-verification");
+
+        let state = self.clone();
+        tokio::spawn(async move {
+            let message = match state.reverify_solves(&filter).await {
+                Ok(report) => report.to_markdown(),
+                Err(e) => format!(":x: Re-verification requested by {requested_by} failed: {e}"),
+            };
+            drop(guard);
+            state.send_private_discord_update(message).await;
+        });
+        true
+    }
+
+    /// Re-verifies every auto-verified solve matching `filter` with the
+    /// current verifier.
+    ///
+    /// Solves whose verdict, move count, or time has not changed only have
+    /// their verifier output updated. Other solves are updated unless they
+    /// have been accepted or reviewed by a moderator, in which case they are
+    /// held for manual review instead.
+    pub async fn reverify_solves(&self, filter: &ReverifyFilter) -> AppResult<ReverifyReport> {
+        let editor = self.get_hsc_auto_verify_dummy_user().await?;
+        let solve_ids = self
+            .get_solves_to_reverify(filter.verifier_version.as_deref(), filter.puzzle_id)
+            .await?;
+
+        tracing::info!("Re-verifying {} solves", solve_ids.len());
+
+        // Verifier versions by program, so that each verifier is only asked
+        // once per run.
+        let mut verifier_versions = HashMap::new();
+        let mut report = ReverifyReport::default();
+        for solve_id in solve_ids {
+            match self
+                .reverify_solve(&editor, solve_id, &mut verifier_versions)
+                .await
+            {
+                Ok(change) => {
+                    report.checked += 1;
+                    report.changed.extend(change);
+                }
+                Err(e) => {
+                    tracing::warn!("Error re-verifying solve {solve_id}: {e}");
+                    report.errors.push((solve_id, e.to_string()));
+                }
+            }
+        }
+
+        tracing::info!("{}", report.summary());
+        Ok(report)
+    }
+
+    /// Re-verifies a single solve and returns what changed, if anything.
+    async fn reverify_solve(
+        &self,
+        editor: &User,
+        solve_id: SolveId,
+        verifier_versions: &mut HashMap<String, String>,
+    ) -> AppResult<Option<ReverifyChange>> {
+        let old = self.get_solve(solve_id).await?;
+        let result = self
+            .run_autoverify_with_versions(solve_id, false, verifier_versions)
+            .await?;
+        let verifier_version = result.verifier_version.clone();
+
+        // `None` means the solve would need manual review.
+        let (new_fmc_verified, new_speed_verified, new_move_count, new_speed_cs) =
+            match &result.outcome {
+                AutoVerifyOutcome::Ineligible(_) => (
+                    old.move_count.map(|_| false),
+                    old.speed_cs.map(|_| false),
+                    old.move_count,
+                    old.speed_cs,
+                ),
+                AutoVerifyOutcome::Verified(verified) => (
+                    verified.verify_fmc.then_some(true),
+                    verified.verify_speed.then_some(true),
+                    verified.fields.move_count,
+                    verified.fields.speed_cs,
+                ),
+            };
+
+        let reviewed_by_moderator =
+            |verified_by: Option<UserId>| verified_by.is_some_and(|id| id != editor.id);
+
+        let mut changes = vec![];
+        for (name, old_verified, old_verified_by, new_verified) in [
+            (
+                "FMC",
+                old.fmc_verified,
+                old.fmc_verified_by,
+                new_fmc_verified,
+            ),
+            (
+                "Speed",
+                old.speed_verified,
+                old.speed_verified_by,
+                new_speed_verified,
+            ),
+        ] {
+            // A moderator's verdict is not expected to match the autoverifier.
+            if !reviewed_by_moderator(old_verified_by) && old_verified != new_verified {
+                changes.push(format!(
+                    "{name} verdict {} → {}",
+                    verdict_str(old_verified),
+                    verdict_str(new_verified),
+                ));
+            }
+        }
+        if old.move_count != new_move_count {
+            changes.push(format!(
+                "move count {} → {}",
+                old.move_count.map_or("none".to_string(), |n| n.to_string()),
+                new_move_count.map_or("none".to_string(), |n| n.to_string()),
+            ));
+        }
+        if old.speed_cs != new_speed_cs {
+            changes.push(format!(
+                "time {} → {}",
+                old.speed_cs
+                    .map_or("none".to_string(), crate::util::render_time),
+                new_speed_cs.map_or("none".to_string(), crate::util::render_time),
+            ));
+        }
+
+        if changes.is_empty() {
+            // Only the verifier output is new.
+            if let AutoVerifyOutcome::Verified(verified) = result.outcome {
+                let VerifiedSolve { fields, .. } = *verified;
+                if let Some(auto_verify_output) = fields.auto_verify_output {
+                    self.set_auto_verify_output(solve_id, auto_verify_output, &verifier_version)
+                        .await?;
+                }
+            }
+            return Ok(None);
+        }
+
+        let summary = changes.join("; ");
+        let protected = old.fmc_verified == Some(true)
+            || old.speed_verified == Some(true)
+            || reviewed_by_moderator(old.fmc_verified_by)
+            || reviewed_by_moderator(old.speed_verified_by);
+
+        let action = if protected {
+            let reason = format!(
+                "Re-verification with verifier version {verifier_version} \
+                 changed the result of a reviewed solve: {summary}",
+            );
+            tracing::warn!("Solve {solve_id}: {reason}");
+
+            let mut transaction = self.pool.begin().await?;
+            Self::add_solve_log_entry(
+                &mut transaction,
+                editor,
+                solve_id,
+                AuditLogEvent::Updated {
+                    object: None,
+                    fields: BTreeMap::new(),
+                    comment: Some(reason.clone()),
+                },
+            )
+            .await?;
+            transaction.commit().await?;
+
+            self.hold_autoverify_for_review(solve_id, &reason).await?;
+            ReverifyAction::HeldForReview
+        } else {
+            tracing::info!("Solve {solve_id}: re-verification changed {summary}");
+            let comment =
+                format!("Re-verified with verifier version {verifier_version}: {summary}");
+            self.save_autoverify_result(editor, solve_id, result, &comment)
+                .await?;
+
+            // Saving never clears a verdict, so a solve that the autoverifier
+            // used to reject must be sent back for manual review explicitly.
+            if old.fmc_verified.is_some() && new_fmc_verified.is_none() {
+                self.verify_fmc(editor, solve_id, None, &comment).await?;
+            }
+            if old.speed_verified.is_some() && new_speed_verified.is_none() {
+                self.verify_speed(editor, solve_id, None, &comment).await?;
+            }
+            ReverifyAction::Updated
+        };
+
+        Ok(Some(ReverifyChange {
+            solve_id,
+            changes,
+            action,
+        }))
+    }
+}
+
+fn verdict_str(verified: Option<bool>) -> &'static str {
+    match verified {
+        Some(true) => "accepted",
+        Some(false) => "rejected",
+        None => "needs review",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::autoverify::SolveAutoVerifier;
+
+    #[test]
+    fn test_reverify_guard() {
+        let autoverifier = Arc::new(SolveAutoVerifier::default());
+        let guard = autoverifier.try_start_reverify().expect("not running");
+        assert!(autoverifier.try_start_reverify().is_none());
+        drop(guard);
+        assert!(autoverifier.try_start_reverify().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_reverify_guard_released_on_panic() {
+        let autoverifier = Arc::new(SolveAutoVerifier::default());
+        let guard = autoverifier.try_start_reverify().expect("not running");
+        let task = tokio::spawn(async move {
+            let _guard = guard;
+            panic!("re-verification failed");
+        });
+        assert!(task.await.is_err());
+        assert!(autoverifier.try_start_reverify().is_some());
+    }
+
+    #[test]
+    fn test_reverify_report_text() {
+        let report = ReverifyReport {
+            checked: 3,
+            changed: vec![
+                ReverifyChange {
+                    solve_id: SolveId(1),
+                    changes: vec!["move count 40 → 41".to_string()],
+                    action: ReverifyAction::Updated,
+                },
+                ReverifyChange {
+                    solve_id: SolveId(2),
+                    changes: vec![
+                        "Speed verdict accepted → needs review".to_string(),
+                        "time 10.00 → 11.00".to_string(),
+                    ],
+                    action: ReverifyAction::HeldForReview,
+                },
+            ],
+            errors: vec![(SolveId(3), "log file missing".to_string())],
+        };
+        assert_eq!(
+            report.to_text(),
+            "Re-verified 3 solves: 2 changed, 1 held for review, 1 errors\n\
+             Solve #1 (updated): move count 40 → 41\n\
+             Solve #2 (held for review): Speed verdict accepted → needs review; time 10.00 → 11.00\n\
+             Solve #3 (error): log file missing",
+        );
+    }
+
+    #[test]
+    fn test_verdict_str() {
+        assert_eq!(verdict_str(Some(true)), "accepted");
+        assert_eq!(verdict_str(Some(false)), "rejected");
+        assert_eq!(verdict_str(None), "needs review");
+    }
+}
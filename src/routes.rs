@@ -1,5 +1,5 @@
 use crate::traits::RequestBody;
-use crate::{AppState, api, html, static_files};
+use crate::{AppState, api, export, feeds, html, static_files};
 
 pub(crate) fn router() -> axum::Router<AppState> {
     use axum::routing::{get, post};
@@ -106,6 +106,10 @@ pub(crate) fn router() -> axum::Router<AppState> {
             "/pending-submissions",
             get(html::submissions::PendingSubmissionsPage::as_handler_query),
         )
+        .route(
+            "/chart/record-history",
+            get(html::charts::RecordHistoryChart::as_handler_query),
+        )
         .route(
             "/categories",
             get(html::categories::CategoriesPage::as_handler_query),
@@ -122,7 +126,20 @@ pub(crate) fn router() -> axum::Router<AppState> {
             "/audit-log/user",
             get(html::audit_log::UserAuditLogPage::as_handler_query),
         )
+        .route("/search", get(html::search::SearchPage::as_handler_query))
         .route("/users", get(html::users::UsersPage::as_handler_query))
+        .route(
+            "/webhooks",
+            get(html::webhooks::WebhooksPage::as_handler_query),
+        )
+        .route(
+            "/solve/autoverify-report",
+            get(html::autoverify_report::AutoVerifyReportPage::as_handler_query),
+        )
+        .route(
+            "/autoverify-policy",
+            get(html::autoverify_policy::AutoVerifyPolicyPage::as_handler_query),
+        )
         .route(
             "/settings",
             get(html::settings::SettingsPage::as_handler_query),
@@ -140,6 +157,18 @@ pub(crate) fn router() -> axum::Router<AppState> {
             "/verify-solve",
             post(api::verify_solve::VerifySolveRequest::as_multipart_form_handler),
         )
+        .route(
+            "/claim-solve",
+            post(api::verify_solve::ClaimSolveRequest::as_multipart_form_handler),
+        )
+        .route(
+            "/release-solve-claim",
+            post(api::verify_solve::ReleaseSolveClaimRequest::as_multipart_form_handler),
+        )
+        .route(
+            "/appeal-solve",
+            post(api::verify_solve::AppealSolveRequest::as_multipart_form_handler),
+        )
         .route(
             "/update-variant",
             post(api::categories::UpdateVariant::as_multipart_form_handler),
@@ -152,6 +181,14 @@ pub(crate) fn router() -> axum::Router<AppState> {
             "/update-puzzle",
             post(api::categories::UpdatePuzzle::as_multipart_form_handler),
         )
+        .route(
+            "/update-webhook",
+            post(api::webhooks::UpdateWebhook::as_multipart_form_handler),
+        )
+        .route(
+            "/update-autoverify-policy",
+            post(api::autoverify::UpdateAutoVerifyPolicy::as_multipart_form_handler),
+        )
         .route(
             "/update-user",
             post(api::edit_user::UpdateUser::as_multipart_form_handler),
@@ -160,6 +197,52 @@ pub(crate) fn router() -> axum::Router<AppState> {
             "/api/solver-pbs",
             get(api::pb::PbsInCategoryRequest::as_handler_query),
         )
+        .route(
+            "/api/solve/autoverify-report",
+            get(api::autoverify::AutoVerifyReportRequest::as_handler_query),
+        )
+        .route(
+            "/api/v1/leaderboard",
+            get(api::v1::GlobalLeaderboardApiRequest::as_handler_query),
+        )
+        .route(
+            "/api/v1/puzzle",
+            get(api::v1::PuzzleLeaderboardApiRequest::as_handler_query),
+        )
+        .route(
+            "/api/v1/solver",
+            get(api::v1::SolverApiRequest::as_handler_query),
+        )
+        .route(
+            "/api/v1/solve",
+            get(api::v1::SolveApiRequest::as_handler_query),
+        )
+        .route(
+            "/api/v1/search",
+            get(api::v1::SearchApiRequest::as_handler_query),
+        )
+        // Feeds
+        .route(
+            "/feeds/records.atom",
+            get(feeds::RecordsFeed::as_handler_query),
+        )
+        .route(
+            "/feeds/puzzle.atom",
+            get(feeds::PuzzleFeed::as_handler_query),
+        )
+        .route(
+            "/feeds/solver.atom",
+            get(feeds::SolverFeed::as_handler_query),
+        )
+        // Exports
+        .route(
+            "/export/solves.csv",
+            get(export::SolvesCsvExport::as_handler_query),
+        )
+        .route(
+            "/export/solves.json",
+            get(export::SolvesJsonExport::as_handler_query),
+        )
         // Resources
         .nest_service("/js", ServeEmbed::<static_files::JsFiles>::new())
         .nest_service("/css", ServeEmbed::<static_files::CssFiles>::new())
@@ -0,0 +1,57 @@
+use super::TestApp;
+use crate::AppError;
+use crate::db::RejectionReason;
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn appealed_solve_goes_back_to_pending(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+    let solver = app.solver().await;
+    let [a, b] = app.moderators().await;
+    let solve_id = app.add_pending_speedsolve(8350).await;
+
+    app.state
+        .verify_speed_with_reason(
+            &a,
+            solve_id,
+            Some(false),
+            Some(RejectionReason::VideoMissing),
+            "",
+        )
+        .await
+        .expect("error rejecting solve");
+    assert!(matches!(
+        app.state
+            .file_appeal(&b, solve_id, "Please look again")
+            .await,
+        Err(AppError::NotAuthorized),
+    ));
+
+    app.state
+        .file_appeal(&solver, solve_id, "The video is linked in the notes")
+        .await
+        .expect("error appealing solve");
+    let solve = app
+        .state
+        .get_solve(solve_id)
+        .await
+        .expect("error getting solve");
+    assert_eq!(solve.speed_verified, None);
+    assert!(matches!(
+        app.state.file_appeal(&solver, solve_id, "Again").await,
+        Err(AppError::CannotAppeal(_)),
+    ));
+
+    app.state
+        .verify_speed(&b, solve_id, Some(false), "")
+        .await
+        .expect("error rejecting solve");
+    assert!(
+        app.state
+            .get_open_appeal(solve_id)
+            .await
+            .expect("error getting appeal")
+            .is_none(),
+        "appeal should be resolved once the solve is reviewed",
+    );
+}
@@ -0,0 +1,168 @@
+use chrono::{TimeDelta, Utc};
+
+use super::{TestApp, solve_verification};
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn autoverified_solve_sends_record_alerts(pool: sqlx::PgPool) {
+    let mut app = TestApp::new(pool).await;
+
+    let completion = Utc::now() - TimeDelta::minutes(5);
+    let solve_id = app
+        .submit_log_file(&[solve_verification(
+            "ft_hypercube:3",
+            83.5,
+            350,
+            completion,
+            &[],
+        )])
+        .await;
+    let solve = app.wait_for_autoverify(solve_id).await;
+
+    assert_eq!(solve.puzzle.hsc_id.as_deref(), Some("ft_hypercube:3"));
+    assert_eq!(solve.speed_cs, Some(8350));
+    assert_eq!(solve.move_count, Some(350));
+    assert_eq!(solve.speed_verified, Some(true));
+    assert_eq!(solve.fmc_verified, Some(true));
+    assert_eq!(
+        app.state
+            .get_auto_verifier_version(solve_id)
+            .await
+            .expect("error getting verifier version")
+            .as_deref(),
+        Some("fake-hsc2 0.0.0"),
+    );
+
+    // The first solve in a category is a world record for both speed and
+    // fewest moves.
+    let mut event_classes = vec![];
+    for _ in 0..2 {
+        let alert = app.next_record_alert().await;
+        assert_eq!(alert["event"], "record");
+        assert_eq!(alert["solve"]["id"], solve_id.0);
+        event_classes.push(alert["event_class"].clone());
+    }
+    event_classes.sort_by_key(|class| class.to_string());
+    assert_eq!(event_classes, ["fewest-moves", "speed"]);
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn slow_scramble_needs_manual_speed_review(pool: sqlx::PgPool) {
+    let mut app = TestApp::new(pool).await;
+
+    let solve_id = app
+        .submit_log_file(&[solve_verification(
+            "ft_hypercube:3",
+            83.5,
+            350,
+            Utc::now(),
+            &[("scramble_application", 60.0)],
+        )])
+        .await;
+    let solve = app.wait_for_autoverify(solve_id).await;
+
+    // Scramble application time only affects the speedsolve.
+    assert_eq!(solve.speed_verified, None);
+    assert_eq!(solve.fmc_verified, Some(true));
+
+    let alert = app.next_record_alert().await;
+    assert_eq!(alert["event_class"], "fewest-moves");
+    assert!(app.received_record_alerts().is_empty());
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn new_puzzle_is_created(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+
+    let solve_id = app
+        .submit_log_file(&[solve_verification(
+            "ft_hypercube:9",
+            3600.0,
+            20000,
+            Utc::now(),
+            &[],
+        )])
+        .await;
+    let solve = app.wait_for_autoverify(solve_id).await;
+
+    assert_eq!(solve.puzzle.name, "9×9×9×9");
+    assert_eq!(solve.puzzle.hsc_id.as_deref(), Some("ft_hypercube:9"));
+    assert_eq!(solve.speed_verified, Some(true));
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn ineligible_puzzle_is_rejected(pool: sqlx::PgPool) {
+    let mut app = TestApp::new(pool).await;
+
+    let solve_id = app
+        .submit_log_file(&[solve_verification(
+            "experimental_puzzle",
+            10.0,
+            20,
+            Utc::now(),
+            &[],
+        )])
+        .await;
+    let solve = app.wait_for_autoverify(solve_id).await;
+
+    // The solve is rejected with the move count and time that the verifier
+    // reported, rather than made-up ones.
+    assert_eq!(solve.puzzle.name, "Other");
+    assert_eq!(solve.move_count, Some(20));
+    assert_eq!(solve.speed_cs, Some(1000));
+    assert_eq!(solve.fmc_verified, Some(false));
+    assert_eq!(solve.speed_verified, Some(false));
+    assert!(app.received_record_alerts().is_empty());
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn empty_log_file_needs_manual_review(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+
+    let solve_id = app.submit_log_file(&[]).await;
+    let solve = app.wait_for_autoverify(solve_id).await;
+
+    assert_eq!(solve.speed_verified, None);
+    assert_eq!(solve.fmc_verified, None);
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn report_uses_stored_verifier_output(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+
+    let solve_id = app
+        .submit_log_file(&[solve_verification(
+            "ft_hypercube:3",
+            83.5,
+            350,
+            Utc::now(),
+            &[],
+        )])
+        .await;
+    app.wait_for_autoverify(solve_id).await;
+
+    // The fake verifier would report no solves for this log file.
+    sqlx::query("UPDATE Solve SET log_file_contents = '[]' WHERE id = $1")
+        .bind(solve_id.0)
+        .execute(&app.state.pool)
+        .await
+        .expect("error replacing log file");
+
+    let report = app
+        .state
+        .autoverify_report(solve_id, false)
+        .await
+        .expect("error getting report");
+    assert!(report.from_stored_output);
+    assert_eq!(report.verifier_version, "fake-hsc2 0.0.0");
+    assert_eq!(report.verifications.len(), 1);
+    assert!(report.verify_speed);
+    assert!(report.verify_fmc);
+
+    assert!(app.state.autoverify_report(solve_id, true).await.is_err());
+}
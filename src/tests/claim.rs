@@ -0,0 +1,45 @@
+use super::TestApp;
+use crate::AppError;
+use crate::db::SolveId;
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn claimed_solve_can_only_be_verified_by_claimant(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+    let [a, b] = app.moderators().await;
+    let solve_id = app.add_pending_speedsolve(8350).await;
+
+    app.state
+        .claim_solve(&a, solve_id)
+        .await
+        .expect("error claiming solve");
+    assert!(matches!(
+        app.state.claim_solve(&b, solve_id).await,
+        Err(AppError::SolveClaimedByOtherModerator(_)),
+    ));
+    assert!(matches!(
+        app.state.verify_speed(&b, solve_id, Some(true), "").await,
+        Err(AppError::SolveClaimedByOtherModerator(_)),
+    ));
+
+    app.state
+        .verify_speed(&a, solve_id, Some(true), "")
+        .await
+        .expect("error verifying solve");
+    assert!(
+        app.state
+            .get_solve_claim(solve_id)
+            .await
+            .expect("error getting claim")
+            .is_none(),
+        "claim should be released once the solve is reviewed",
+    );
+    assert!(matches!(
+        app.state.claim_solve(&a, solve_id).await,
+        Err(AppError::SolveNotPending),
+    ));
+    assert!(matches!(
+        app.state.claim_solve(&a, SolveId(i32::MAX)).await,
+        Err(AppError::NotFound),
+    ));
+}
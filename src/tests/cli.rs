@@ -0,0 +1,31 @@
+use super::TestApp;
+use crate::cli::CliPuzzleCommand;
+use crate::db::PuzzleId;
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn update_names_renames_puzzles_to_match_hsc2(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+
+    sqlx::query("UPDATE Puzzle SET name = 'Old name' WHERE hsc_id = 'ft_hypercube:3'")
+        .execute(&app.state.pool)
+        .await
+        .expect("error renaming puzzle");
+
+    CliPuzzleCommand::UpdateNames
+        .execute(&app.state)
+        .await
+        .expect("error updating puzzle names");
+
+    let get_name = |id| async move {
+        app.state
+            .get_puzzle(PuzzleId(id))
+            .await
+            .expect("error getting puzzle")
+            .expect("missing puzzle")
+            .name
+    };
+    assert_eq!(get_name(2).await, "3×3×3×3");
+    // Puzzles without an HSC2 ID are left alone.
+    assert_eq!(get_name(1).await, "Other");
+}
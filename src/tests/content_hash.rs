@@ -0,0 +1,118 @@
+use chrono::Utc;
+
+use super::{TestApp, solve_verification};
+use crate::db::SolveId;
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn copied_log_file_is_flagged(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+
+    let original = app
+        .submit_log_file(&[solve_verification(
+            "ft_hypercube:3",
+            83.5,
+            350,
+            Utc::now(),
+            &[],
+        )])
+        .await;
+
+    // Someone else submits the same log file.
+    let copy = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO Solve
+                (solver_id, puzzle_id, program_id,
+                 average, blind, filters, macros, one_handed, computer_assisted,
+                 log_file_name, log_file_contents)
+            SELECT 1, puzzle_id, program_id,
+                    average, blind, filters, macros, one_handed, computer_assisted,
+                    log_file_name, log_file_contents
+                FROM Solve WHERE id = $1
+            RETURNING id",
+    )
+    .bind(original.0)
+    .fetch_one(&app.state.pool)
+    .await
+    .expect("error copying solve");
+    let copy = SolveId(copy);
+
+    let duplicates = app
+        .state
+        .record_content_hashes(copy)
+        .await
+        .expect("error recording content hashes");
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].kind, "log_file");
+    assert_eq!(duplicates[0].other_solve_id, original);
+    assert!(!duplicates[0].same_solver);
+
+    // Recording the same hashes again does not report them again.
+    let duplicates = app
+        .state
+        .record_content_hashes(copy)
+        .await
+        .expect("error recording content hashes");
+    assert!(duplicates.is_empty());
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn reused_scramble_is_flagged(pool: sqlx::PgPool) {
+    let app = TestApp::new(pool).await;
+
+    // Two different log files with the same scramble, by different solvers.
+    let mut solve_ids = vec![];
+    for (solver_id, log_file) in [
+        (
+            4,
+            "solve {\n  scramble \"full\" seed=\"0123abcd\"\n  duration 1000\n}\n",
+        ),
+        (
+            5,
+            "solve {\n  scramble \"full\" seed=\"0123abcd\"\n  duration 2000\n}\n",
+        ),
+    ] {
+        let solve_id = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO Solve
+                    (solver_id, puzzle_id, program_id,
+                     average, blind, filters, macros, one_handed, computer_assisted,
+                     log_file_name, log_file_contents)
+                VALUES ($1, 2, 2, FALSE, FALSE, TRUE, FALSE, FALSE, FALSE, 'test.hsc', $2)
+                RETURNING id",
+        )
+        .bind(solver_id)
+        .bind(log_file.as_bytes())
+        .fetch_one(&app.state.pool)
+        .await
+        .expect("error adding solve");
+        solve_ids.push(SolveId(solve_id));
+    }
+
+    let duplicates = app
+        .state
+        .record_content_hashes(solve_ids[0])
+        .await
+        .expect("error recording content hashes");
+    assert!(duplicates.is_empty());
+
+    let duplicates = app
+        .state
+        .record_content_hashes(solve_ids[1])
+        .await
+        .expect("error recording content hashes");
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].kind, "scramble_seed");
+    assert_eq!(duplicates[0].other_solve_id, solve_ids[0]);
+    assert!(!duplicates[0].same_solver);
+
+    let scramble_seed =
+        sqlx::query_scalar::<_, Option<String>>("SELECT scramble_seed FROM Solve WHERE id = $1")
+            .bind(solve_ids[1].0)
+            .fetch_one(&app.state.pool)
+            .await
+            .expect("error getting scramble seed");
+    assert_eq!(
+        scramble_seed.as_deref().map(str::trim_end),
+        Some("0123abcd")
+    );
+}
@@ -0,0 +1,10 @@
+-- Minimal leaderboard data. Submitted solves start out as puzzle 1 ("Other"),
+-- so it must be inserted first.
+
+INSERT INTO Program (abbr, name, material) VALUES
+    ('X', 'Other Virtual', FALSE),
+    ('HSC2', 'Hyperspeedcube 2', FALSE);
+
+INSERT INTO Puzzle (name, primary_filters, primary_macros, hsc_id) VALUES
+    ('Other', FALSE, FALSE, NULL),
+    ('3×3×3×3', TRUE, FALSE, 'ft_hypercube:3');
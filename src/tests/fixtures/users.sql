@@ -0,0 +1,10 @@
+-- Dummy users, which must exist before migrating because some migrations
+-- record audit log entries by the "Migration" user (ID 2).
+
+INSERT INTO UserAccount (name, moderator, dummy) VALUES
+    ('CLI', TRUE, TRUE),
+    ('Migration', TRUE, TRUE),
+    ('HSC Auto-Verify', TRUE, TRUE),
+    ('Test Solver', FALSE, FALSE),
+    ('Test Moderator A', TRUE, FALSE),
+    ('Test Moderator B', TRUE, FALSE);
@@ -0,0 +1,317 @@
+//! Integration tests that run against a local Postgres database, with
+//! `tests/fake-hsc2/fake-hsc2.sh` standing in for the Hyperspeedcube CLI.
+//!
+//! These tests are ignored by default. To run them, set `DATABASE_URL` to a
+//! Postgres user that can create databases and run `cargo test -- --ignored`.
+//! Each test gets its own database, which is dropped if the test passes.
+
+use std::sync::Arc;
+use std::sync::Once;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum_typed_multipart::{FieldData, FieldMetadata};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+
+use crate::AppState;
+use crate::api::submit_solve::{AutoSolveData, AutoSubmitSolveRequest};
+use crate::autoverify::SolveAutoVerifier;
+use crate::db::{FullSolve, SolveId, User, UserId, WebhookData};
+use crate::traits::RequestBody;
+
+mod appeal;
+mod autoverify;
+mod claim;
+mod cli;
+mod content_hash;
+mod record_approval;
+
+/// Path to the fake Hyperspeedcube CLI.
+const FAKE_HSC2_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fake-hsc2/fake-hsc2.sh");
+
+/// Maximum time to wait for background work, such as auto-verification or
+/// webhook delivery.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// ID of the "Test Solver" user in `fixtures/users.sql`.
+const TEST_SOLVER_ID: UserId = UserId(4);
+/// IDs of the "Test Moderator A" and "Test Moderator B" users in
+/// `fixtures/users.sql`.
+const TEST_MODERATOR_IDS: [UserId; 2] = [UserId(5), UserId(6)];
+
+static SET_ENV: Once = Once::new();
+
+/// Server state connected to a test database, with a webhook that records
+/// every world record alert.
+pub(crate) struct TestApp {
+    pub state: AppState,
+    record_alerts: mpsc::UnboundedReceiver<serde_json::Value>,
+}
+
+impl TestApp {
+    /// Migrates the test database, adds the fixtures, starts the
+    /// auto-verification workers, and subscribes a local webhook to records.
+    pub async fn new(pool: PgPool) -> Self {
+        set_env();
+
+        // Some migrations refer to the dummy users, so they must be added
+        // between the initial schema and the rest of the migrations. The
+        // initial migration is idempotent, so running it twice is harmless.
+        sqlx::raw_sql(include_str!(
+            "../../migrations/20250220024922_initial.up.sql"
+        ))
+        .execute(&pool)
+        .await
+        .expect("error creating initial schema");
+        sqlx::raw_sql(include_str!("fixtures/users.sql"))
+            .execute(&pool)
+            .await
+            .expect("error adding users");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("error migrating database");
+        sqlx::raw_sql(include_str!("fixtures/leaderboards.sql"))
+            .execute(&pool)
+            .await
+            .expect("error adding leaderboard data");
+
+        let (shutdown_tx, _) = mpsc::channel(1);
+        let state = AppState {
+            pool,
+            otps: Default::default(),
+            pkce_hash_values: Default::default(),
+            recently_submitted: Default::default(),
+            autoverifier: Arc::new(SolveAutoVerifier::default()),
+            http_client: reqwest::Client::new(),
+
+            discord: None,
+            turnstile: None,
+
+            block_logins: Arc::new(AtomicBool::new(false)),
+            block_solve_submissions: Arc::new(AtomicBool::new(false)),
+            block_user_actions: Arc::new(AtomicBool::new(false)),
+            block_moderator_actions: Arc::new(AtomicBool::new(false)),
+
+            shutdown_tx,
+            restart_requested: Arc::new(AtomicBool::new(false)),
+        };
+
+        tokio::spawn({
+            let state = state.clone();
+            async move { state.run_autoverify_workers().await }
+        });
+
+        let record_alerts = start_webhook_receiver(&state).await;
+
+        Self {
+            state,
+            record_alerts,
+        }
+    }
+
+    /// Returns the user that submits solves.
+    pub async fn solver(&self) -> User {
+        self.state
+            .get_user(TEST_SOLVER_ID)
+            .await
+            .expect("error getting test solver")
+    }
+
+    /// Returns two moderators who are not dummy users.
+    pub async fn moderators(&self) -> [User; 2] {
+        let [a, b] = TEST_MODERATOR_IDS;
+        let get = |id| async move {
+            self.state
+                .get_user(id)
+                .await
+                .expect("error getting test moderator")
+        };
+        [get(a).await, get(b).await]
+    }
+
+    /// Adds a speedsolve by the test solver that is waiting for manual
+    /// verification and returns its ID.
+    pub async fn add_pending_speedsolve(&self, speed_cs: i32) -> SolveId {
+        let solve_id = sqlx::query_scalar::<_, i32>(
+            "INSERT INTO Solve
+                    (solver_id, puzzle_id, program_id,
+                     average, blind, filters, macros, one_handed, computer_assisted,
+                     speed_cs, video_url)
+                VALUES ($1, 2, 2, FALSE, FALSE, TRUE, FALSE, FALSE, FALSE, $2, 'https://youtu.be/test')
+                RETURNING id",
+        )
+        .bind(TEST_SOLVER_ID.0)
+        .bind(speed_cs)
+        .fetch_one(&self.state.pool)
+        .await
+        .expect("error adding solve");
+        SolveId(solve_id)
+    }
+
+    /// Submits a log file for auto-verification as the test solver and
+    /// returns the new solve's ID. `verifications` is the output that the
+    /// fake verifier reports for the log file.
+    pub async fn submit_log_file(&self, verifications: &[serde_json::Value]) -> SolveId {
+        let contents = serde_json::to_vec(verifications).expect("error serializing log file");
+        let request = AutoSubmitSolveRequest(AutoSolveData {
+            program_abbr: "HSC2".to_string(),
+            solver_notes: None,
+            computer_assisted: false,
+            will_upload_video: false,
+            average: Some(verifications.len() > 1),
+            log_file: vec![FieldData {
+                metadata: FieldMetadata {
+                    file_name: Some("test.hsc".to_string()),
+                    ..Default::default()
+                },
+                contents: Bytes::from(contents),
+            }],
+        });
+        request
+            .request(self.state.clone(), Some(self.solver().await))
+            .await
+            .expect("error submitting solve");
+
+        let solve_id = sqlx::query_scalar::<_, i32>("SELECT MAX(id) FROM Solve")
+            .fetch_one(&self.state.pool)
+            .await
+            .expect("error getting solve ID");
+        SolveId(solve_id)
+    }
+
+    /// Waits for a solve to leave the auto-verification queue and returns it.
+    pub async fn wait_for_autoverify(&self, solve_id: SolveId) -> FullSolve {
+        tokio::time::timeout(TIMEOUT, async {
+            while self
+                .state
+                .get_autoverify_queue()
+                .await
+                .expect("error getting autoverify queue")
+                .iter()
+                .any(|entry| entry.solve_id == solve_id && !entry.needs_manual_review)
+            {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("timed out waiting for auto-verification");
+
+        self.state
+            .get_solve(solve_id)
+            .await
+            .expect("error getting solve")
+    }
+
+    /// Waits for the next world record alert and returns its webhook payload.
+    pub async fn next_record_alert(&mut self) -> serde_json::Value {
+        tokio::time::timeout(TIMEOUT, self.record_alerts.recv())
+            .await
+            .expect("timed out waiting for record alert")
+            .expect("webhook receiver stopped")
+    }
+
+    /// Returns every world record alert received so far.
+    pub fn received_record_alerts(&mut self) -> Vec<serde_json::Value> {
+        std::iter::from_fn(|| self.record_alerts.try_recv().ok()).collect()
+    }
+}
+
+/// Points the server at the fake Hyperspeedcube CLI.
+fn set_env() {
+    SET_ENV.call_once(|| {
+        // SAFETY: Every test calls this before doing anything else, and `Once`
+        // blocks them until it returns, so no other thread reads or writes the
+        // environment at the same time.
+        unsafe {
+            std::env::set_var("HSC2_PATH", FAKE_HSC2_PATH);
+            std::env::set_var("DOMAIN_NAME", "http://localhost:3000");
+        }
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    });
+}
+
+/// Starts a web server on a random local port and subscribes it to world
+/// record alerts. Returns a channel that receives each alert's payload.
+async fn start_webhook_receiver(state: &AppState) -> mpsc::UnboundedReceiver<serde_json::Value> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("error binding webhook receiver");
+    let addr = listener.local_addr().expect("error getting local address");
+    let app = axum::Router::new().route(
+        "/",
+        axum::routing::post(move |axum::Json(payload): axum::Json<serde_json::Value>| {
+            let _ = tx.send(payload);
+            std::future::ready(())
+        }),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let cli = state
+        .get_cli_dummy_user()
+        .await
+        .expect("error getting CLI user");
+    state
+        .add_webhook(
+            &cli,
+            WebhookData {
+                name: "Test".to_string(),
+                url: format!("http://{addr}/"),
+                secret: None,
+                events: vec!["record".to_string()],
+                enabled: true,
+            },
+        )
+        .await
+        .expect("error adding webhook");
+
+    rx
+}
+
+/// Returns the output that the verifier would report for a single
+/// Hyperspeedcube solve of `puzzle_id`, as JSON.
+///
+/// `durations` overrides the other durations measured by the verifier, in
+/// seconds. By default, each of them is a tenth of a second, except for
+/// memorization and blindsolving, which are absent.
+pub(crate) fn solve_verification(
+    puzzle_id: &str,
+    speedsolve_secs: f64,
+    solution_stm: u64,
+    completion: DateTime<Utc>,
+    durations: &[(&str, f64)],
+) -> serde_json::Value {
+    // Durations are represented as `[seconds, nanoseconds]`.
+    let duration =
+        |secs: f64| serde_json::json!([secs.trunc() as i64, (secs.fract() * 1e9) as i64]);
+    let mut durations_json = serde_json::json!({
+        "scramble_network_latency": duration(0.1),
+        "scramble_application": duration(0.1),
+        "inspection": duration(0.1),
+        "speedsolve": duration(speedsolve_secs),
+        "memo": null,
+        "blindsolve": null,
+        "timestamp_network_latency": duration(0.1),
+    });
+    for &(name, secs) in durations {
+        durations_json[name] = duration(secs);
+    }
+
+    serde_json::json!({
+        "puzzle_canonical_id": puzzle_id,
+        "solution_stm": solution_stm,
+        "used_filters": false,
+        "used_macros": false,
+        "durations": durations_json,
+        "verified_timestamps": {
+            "scramble": completion - chrono::TimeDelta::seconds(speedsolve_secs as i64),
+            "completion": completion,
+        },
+        "errors": [],
+    })
+}
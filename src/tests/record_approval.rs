@@ -0,0 +1,115 @@
+use chrono::{TimeDelta, Utc};
+
+use super::{TestApp, solve_verification};
+use crate::AppError;
+use crate::db::{EventClass, VerifyOutcome};
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn new_record_needs_two_moderators(pool: sqlx::PgPool) {
+    let mut app = TestApp::new(pool).await;
+    let [a, b] = app.moderators().await;
+
+    // The first solve in a category does not displace a record.
+    let old_record = app.add_pending_speedsolve(9000).await;
+    app.state
+        .verify_speed(&a, old_record, Some(true), "")
+        .await
+        .expect("error verifying solve");
+    app.next_record_alert().await;
+
+    let new_record = app.add_pending_speedsolve(8350).await;
+    let outcome = app
+        .state
+        .verify_speed(&a, new_record, Some(true), "looks good")
+        .await
+        .expect("error approving record");
+    assert_eq!(outcome, VerifyOutcome::AwaitingSecondModerator);
+    let solve = app
+        .state
+        .get_solve(new_record)
+        .await
+        .expect("error getting solve");
+    assert_eq!(solve.speed_verified, None);
+    assert!(matches!(
+        app.state.verify_speed(&a, new_record, Some(true), "").await,
+        Err(AppError::RecordNeedsSecondModerator),
+    ));
+
+    let outcome = app
+        .state
+        .verify_speed(&b, new_record, Some(true), "")
+        .await
+        .expect("error confirming record");
+    assert_eq!(outcome, VerifyOutcome::Applied);
+    let solve = app
+        .state
+        .get_solve(new_record)
+        .await
+        .expect("error getting solve");
+    assert_eq!(solve.speed_verified, Some(true));
+    assert_eq!(solve.speed_verified_by, Some(b.id));
+
+    let alert = app.next_record_alert().await;
+    assert_eq!(alert["solve"]["id"], new_record.0);
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires a local Postgres database"]
+async fn autoverified_record_needs_a_moderator(pool: sqlx::PgPool) {
+    let mut app = TestApp::new(pool).await;
+    let [a, _] = app.moderators().await;
+
+    // The first solve in a category does not displace a record.
+    let completion = Utc::now() - TimeDelta::minutes(5);
+    let old_record = app
+        .submit_log_file(&[solve_verification(
+            "ft_hypercube:3",
+            90.0,
+            350,
+            completion,
+            &[],
+        )])
+        .await;
+    let solve = app.wait_for_autoverify(old_record).await;
+    assert_eq!(solve.speed_verified, Some(true));
+    app.next_record_alert().await;
+    app.next_record_alert().await;
+
+    // The autoverifier's acceptance only counts as the first approval.
+    let new_record = app
+        .submit_log_file(&[solve_verification(
+            "ft_hypercube:3",
+            83.5,
+            400,
+            completion,
+            &[],
+        )])
+        .await;
+    let solve = app.wait_for_autoverify(new_record).await;
+    assert_eq!(solve.speed_verified, None);
+    assert!(
+        app.state
+            .get_record_approval(new_record, EventClass::Speed)
+            .await
+            .expect("error getting record approval")
+            .is_some()
+    );
+    assert!(app.received_record_alerts().is_empty());
+
+    app.state
+        .verify_speed(&a, new_record, Some(true), "")
+        .await
+        .expect("error confirming record");
+    let solve = app
+        .state
+        .get_solve(new_record)
+        .await
+        .expect("error getting solve");
+    assert_eq!(solve.speed_verified, Some(true));
+    assert_eq!(solve.speed_verified_by, Some(a.id));
+
+    let alert = app.next_record_alert().await;
+    assert_eq!(alert["solve"]["id"], new_record.0);
+    assert_eq!(alert["event_class"], "speed");
+}
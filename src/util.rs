@@ -37,6 +37,15 @@ pub fn html_render_time(time_cs: i32) -> String {
     }
 }
 
+/// Escapes text for inclusion in XML, such as SVG or Atom.
+pub fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 pub fn html_render_rank(rank: i32) -> String {
     let icon_html = match rank {
         1 => svg_icon("assets/gold-hypercube.svg"),
@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::{AsyncRead, AsyncReadExt};
+use hyperspeedcube_cli_types::verification::SolveVerification;
+
+use crate::{AppError, AppResult};
+
+/// Maximum number of bytes of stderr to include in an error or audit log
+/// entry.
+const MAX_STDERR_EXCERPT: usize = 4 * 1024; // 4 KiB
+
+lazy_static! {
+    /// Verifier for each program, keyed by uppercase program abbreviation.
+    static ref VERIFIERS: HashMap<String, Box<dyn Verifier>> = {
+        let mut ret = HashMap::<String, Box<dyn Verifier>>::new();
+        ret.insert("HSC2".to_string(), Box::new(VerifierRunner::hsc2()));
+        for (program_abbr, path) in &*crate::env::EXTRA_VERIFIERS {
+            let runner = VerifierRunner::new(path, VerifierLimits::default());
+            ret.insert(program_abbr.to_uppercase(), Box::new(runner));
+        }
+        ret
+    };
+}
+
+/// Returns the verifier for log files from a program, if there is one.
+pub fn verifier_for_program(program_abbr: &str) -> Option<&'static dyn Verifier> {
+    VERIFIERS
+        .get(&program_abbr.to_uppercase())
+        .map(|verifier| &**verifier)
+}
+
+/// Log file verifier for a program.
+///
+/// Every verifier produces the same normalized [`SolveVerification`], with
+/// puzzles identified by their Hyperspeedcube ID, so the auto-verification
+/// policy applies to solves from any program the same way.
+pub trait Verifier: Send + Sync {
+    /// Returns the version of the verifier, which is recorded with each
+    /// result.
+    fn version(&self) -> BoxFuture<'_, AppResult<String>>;
+
+    /// Verifies a log file and returns every solve it contains.
+    fn verify<'a>(
+        &'a self,
+        log_file_contents: &'a [u8],
+    ) -> BoxFuture<'a, AppResult<LogFileVerification>>;
+}
+
+/// Solves found in a log file by a [`Verifier`].
+#[derive(Debug)]
+pub struct LogFileVerification {
+    pub solves: Vec<SolveVerification>,
+    /// Anything the verifier wrote to stderr, truncated to a length suitable
+    /// for the audit log.
+    pub stderr: String,
+}
+
+/// Resource limits for a verifier process.
+#[derive(Debug, Clone)]
+pub struct VerifierLimits {
+    /// Wall-clock time after which the whole process group is killed.
+    pub timeout: Duration,
+    /// CPU time after which the kernel kills the process.
+    pub cpu_time: Duration,
+    /// Maximum address space of the process, in bytes.
+    pub memory_bytes: u64,
+    /// Maximum size of stdout or stderr, and of any file the process writes,
+    /// in bytes.
+    pub max_output_bytes: u64,
+    /// Maximum size of a log file to verify, in bytes. Larger log files are
+    /// rejected without starting a process.
+    pub max_log_bytes: usize,
+}
+impl Default for VerifierLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(60),
+            cpu_time: Duration::from_secs(60),
+            memory_bytes: 2 << 30,      // 2 GiB
+            max_output_bytes: 16 << 20, // 16 MiB
+            max_log_bytes: 16 << 20,    // 16 MiB
+        }
+    }
+}
+
+/// Output from a verifier process that exited normally.
+#[derive(Debug)]
+pub struct VerifierOutput {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    /// Stderr, truncated to a length suitable for the audit log.
+    pub stderr: String,
+}
+
+/// Runs the Hyperspeedcube CLI on untrusted input with resource limits.
+///
+/// The process runs in its own process group, so that the whole group can be
+/// killed if it runs out of time or produces too much output.
+#[derive(Debug, Clone)]
+pub struct VerifierRunner {
+    program: String,
+    limits: VerifierLimits,
+}
+
+impl VerifierRunner {
+    pub fn new(program: impl Into<String>, limits: VerifierLimits) -> Self {
+        Self {
+            program: program.into(),
+            limits,
+        }
+    }
+
+    /// Returns a runner for [`crate::env::HSC2_PATH`] with the default limits.
+    pub fn hsc2() -> Self {
+        Self::new(&*crate::env::HSC2_PATH, VerifierLimits::default())
+    }
+
+    /// Runs `verify` on a log file.
+    pub async fn verify_log_file(&self, log_file_contents: &[u8]) -> AppResult<VerifierOutput> {
+        let max_log_bytes = self.limits.max_log_bytes;
+        if log_file_contents.len() > max_log_bytes {
+            return Err(AppError::VerificationFailed(format!(
+                "log file is {} bytes, which exceeds the limit of {max_log_bytes} bytes",
+                log_file_contents.len(),
+            )));
+        }
+
+        let f = tempfile::NamedTempFile::new()?;
+        std::fs::write(&f, log_file_contents)?;
+        let output = self.run([OsStr::new("verify"), f.path().as_os_str()]).await;
+        drop(f);
+        output
+    }
+
+    /// Runs `puzzle` to get metadata for a puzzle.
+    pub async fn puzzle_info(&self, hsc_puzzle_id: &str) -> AppResult<VerifierOutput> {
+        self.run(["puzzle", hsc_puzzle_id]).await
+    }
+
+    async fn run(
+        &self,
+        args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+    ) -> AppResult<VerifierOutput> {
+        let mut command = std::process::Command::new(&self.program);
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(unix)]
+        self.apply_unix_limits(&mut command);
+
+        let mut child = async_process::Command::from(command)
+            .kill_on_drop(true)
+            .spawn()?;
+        // Declared after `child` so that the group is killed before the child
+        // is dropped, which reaps it.
+        let _process_group = ProcessGroupGuard(child.id());
+        let stdout_pipe = child.stdout.take();
+        let stderr_pipe = child.stderr.take();
+        let max_output_bytes = self.limits.max_output_bytes;
+
+        // Output is read into buffers outside the timeout so that whatever was
+        // written before a timeout or error can still be reported.
+        let mut stdout = vec![];
+        let mut stderr = vec![];
+        let result = tokio::time::timeout(self.limits.timeout, async {
+            futures::try_join!(
+                read_limited(stdout_pipe, max_output_bytes, &mut stdout),
+                read_limited(stderr_pipe, max_output_bytes, &mut stderr),
+            )?;
+            child.status().await
+        })
+        .await;
+
+        let stderr = stderr_excerpt(&stderr);
+        let status = match result {
+            Ok(Ok(status)) => status,
+            Ok(Err(e)) if e.kind() == io::ErrorKind::FileTooLarge => {
+                return Err(AppError::VerificationFailed(format!(
+                    "verifier output exceeded {max_output_bytes} bytes. stderr:\n{stderr}",
+                )));
+            }
+            Ok(Err(e)) => {
+                let message = format!("{e}. stderr:\n{stderr}");
+                return Err(io::Error::new(e.kind(), message).into());
+            }
+            Err(_) => return Err(AppError::SolveVerificationTimeout(stderr)),
+        };
+
+        #[cfg(unix)]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+            return Err(AppError::VerificationFailed(format!(
+                "verifier was killed by signal {signal}, \
+                 probably for exceeding a resource limit. stderr:\n{stderr}",
+            )));
+        }
+
+        Ok(VerifierOutput {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Starts the process in a new process group and limits its resources.
+    #[cfg(unix)]
+    fn apply_unix_limits(&self, command: &mut std::process::Command) {
+        use std::os::unix::process::CommandExt;
+
+        let cpu_seconds = self.limits.cpu_time.as_secs().max(1);
+        let memory_bytes = self.limits.memory_bytes;
+        let max_output_bytes = self.limits.max_output_bytes;
+
+        command.process_group(0);
+        // SAFETY: The closure only calls `setrlimit()`, which is
+        // async-signal-safe, and does not allocate.
+        unsafe {
+            command.pre_exec(move || {
+                let set_rlimit = |resource, limit: u64| {
+                    let rlimit = libc::rlimit {
+                        rlim_cur: limit as libc::rlim_t,
+                        rlim_max: limit as libc::rlim_t,
+                    };
+                    match libc::setrlimit(resource, &rlimit) {
+                        0 => Ok(()),
+                        _ => Err(io::Error::last_os_error()),
+                    }
+                };
+                set_rlimit(libc::RLIMIT_CPU, cpu_seconds)?;
+                set_rlimit(libc::RLIMIT_AS, memory_bytes)?;
+                set_rlimit(libc::RLIMIT_FSIZE, max_output_bytes)?;
+                set_rlimit(libc::RLIMIT_CORE, 0)?;
+                Ok(())
+            });
+        }
+    }
+}
+
+/// Any executable that accepts the same arguments as the Hyperspeedcube CLI
+/// and writes the same JSON to stdout can be used as a verifier.
+impl Verifier for VerifierRunner {
+    fn version(&self) -> BoxFuture<'_, AppResult<String>> {
+        Box::pin(async move {
+            let output = self.run(["--version"]).await?;
+            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !output.status.success() || version.is_empty() {
+                return Err(AppError::VerificationFailed(format!(
+                    "unable to get verifier version ({}). stderr:\n{}",
+                    output.status, output.stderr,
+                )));
+            }
+            Ok(version)
+        })
+    }
+
+    fn verify<'a>(
+        &'a self,
+        log_file_contents: &'a [u8],
+    ) -> BoxFuture<'a, AppResult<LogFileVerification>> {
+        Box::pin(async move {
+            let output = self.verify_log_file(log_file_contents).await?;
+            let solves = serde_json::from_slice(&output.stdout).map_err(|e| {
+                AppError::VerificationFailed(format!(
+                    "invalid verifier output ({e}, {}). stderr:\n{}",
+                    output.status, output.stderr,
+                ))
+            })?;
+            // Keep results from a verifier that exits unsuccessfully, but
+            // record the exit status along with stderr.
+            let stderr = match output.status.success() {
+                true => output.stderr,
+                false => format!("verifier {}\n{}", output.status, output.stderr)
+                    .trim()
+                    .to_string(),
+            };
+            Ok(LogFileVerification { solves, stderr })
+        })
+    }
+}
+
+/// Reads a pipe to the end, appending to `buf` as data arrives, or returns an
+/// error with [`io::ErrorKind::FileTooLarge`] if it is longer than `limit`
+/// bytes.
+async fn read_limited(
+    pipe: Option<impl AsyncRead + Unpin>,
+    limit: u64,
+    buf: &mut Vec<u8>,
+) -> io::Result<()> {
+    let Some(mut pipe) = pipe else {
+        return Ok(());
+    };
+    let mut chunk = [0; 8192];
+    loop {
+        let n = pipe.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        let remaining = (limit as usize).saturating_sub(buf.len());
+        buf.extend_from_slice(&chunk[..n.min(remaining)]);
+        if n > remaining {
+            return Err(io::ErrorKind::FileTooLarge.into());
+        }
+    }
+}
+
+/// Kills every process in the group led by a verifier process when dropped,
+/// including any processes it left running after it exited.
+struct ProcessGroupGuard(u32);
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Ok(pid) = libc::pid_t::try_from(self.0) {
+            // SAFETY: `kill()` has no memory safety requirements. If the
+            // verifier exited normally, it has already been reaped, but its
+            // process group ID cannot be reused while any process it left
+            // behind is still alive.
+            unsafe {
+                libc::kill(-pid, libc::SIGKILL);
+            }
+        }
+    }
+}
+
+/// Returns the end of `stderr` as a string, truncated to at most
+/// [`MAX_STDERR_EXCERPT`] bytes.
+fn stderr_excerpt(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stderr = stderr.trim();
+    if stderr.len() <= MAX_STDERR_EXCERPT {
+        return stderr.to_string();
+    }
+    let mut start = stderr.len() - MAX_STDERR_EXCERPT;
+    while !stderr.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("...{}", &stderr[start..])
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    /// Returns a runner for a shell script, along with the directory that
+    /// contains it.
+    fn script_runner(script: &str, timeout: Duration) -> (tempfile::TempDir, VerifierRunner) {
+        let dir = tempfile::tempdir().expect("error creating temporary directory");
+        let path = dir.path().join("verifier.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).expect("error writing script");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .expect("error making script executable");
+        let limits = VerifierLimits {
+            timeout,
+            ..VerifierLimits::default()
+        };
+        let runner = VerifierRunner::new(path.to_string_lossy(), limits);
+        (dir, runner)
+    }
+
+    #[tokio::test]
+    async fn test_timeout_keeps_stderr() {
+        let (_dir, runner) =
+            script_runner("echo 'still working' >&2\nsleep 10", Duration::from_secs(1));
+        match runner.puzzle_info("3x3x3").await {
+            Err(AppError::SolveVerificationTimeout(stderr)) => assert_eq!(stderr, "still working"),
+            other => panic!("expected timeout, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_exit_keeps_stderr() {
+        let (_dir, runner) = script_runner(
+            "echo '[]'\necho 'bad log' >&2\nexit 3",
+            Duration::from_secs(10),
+        );
+        let output = runner.verify(b"log").await.expect("error verifying");
+        assert!(output.solves.is_empty());
+        assert!(output.stderr.starts_with("verifier exit status: 3"));
+        assert!(output.stderr.ends_with("bad log"));
+    }
+
+    #[tokio::test]
+    async fn test_process_group_is_killed_after_exit() {
+        let (dir, runner) = script_runner(
+            "(sleep 1; touch \"$(dirname \"$0\")/survived\") >/dev/null 2>&1 &\necho '[]'",
+            Duration::from_secs(10),
+        );
+        runner.verify(b"log").await.expect("error verifying");
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!dir.path().join("survived").exists());
+    }
+}
@@ -0,0 +1,310 @@
+//! Outgoing webhooks for solve lifecycle events.
+//!
+//! Each payload is POSTed as JSON and signed with HMAC-SHA256 using the secret
+//! shared with the receiver. Failed deliveries are retried with exponential
+//! backoff, and every attempt is recorded in the `WebhookDelivery` table for
+//! 30 days. Pending retries are only held in memory, so they are lost if the server
+//! restarts. See `docs/api.md` for documentation of the payload format.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use itertools::Itertools;
+use sha2_for_hmac::Sha256;
+
+use crate::AppState;
+use crate::api::v1::{ApiEvent, ApiSolve};
+use crate::db::{Event, EventClass, FullSolve, SolveId, Webhook};
+
+/// Maximum number of attempts to deliver a message to a webhook.
+const MAX_ATTEMPTS: i32 = 5;
+/// Delay before the first retry. Each subsequent retry waits twice as long as
+/// the previous one.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(10);
+/// Timeout for each attempt.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Length of a randomly generated delivery ID.
+const DELIVERY_ID_LEN: usize = 16;
+
+/// HTTP header containing the event name.
+const EVENT_HEADER: &str = "X-Leaderboards-Event";
+/// HTTP header containing the delivery ID.
+const DELIVERY_HEADER: &str = "X-Leaderboards-Delivery";
+/// HTTP header containing the payload signature.
+const SIGNATURE_HEADER: &str = "X-Leaderboards-Signature";
+
+/// Solve lifecycle event that can be sent to webhooks.
+#[derive(serde::Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// Solve was submitted.
+    Submitted,
+    /// Solve was analyzed by the auto-verification system.
+    Autoverified,
+    /// Solve was manually accepted by a moderator. Acceptance by the
+    /// auto-verification system is only sent as [`Self::Autoverified`].
+    Verified,
+    /// Solve was manually rejected by a moderator. Rejection by the
+    /// auto-verification system is only sent as [`Self::Autoverified`].
+    Rejected,
+    /// Solve was manually unverified by a moderator.
+    Unverified,
+    /// Solve set or tied a world record.
+    Record,
+}
+impl WebhookEvent {
+    pub const ALL: &[Self] = &[
+        Self::Submitted,
+        Self::Autoverified,
+        Self::Verified,
+        Self::Rejected,
+        Self::Unverified,
+        Self::Record,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Submitted => "submitted",
+            Self::Autoverified => "autoverified",
+            Self::Verified => "verified",
+            Self::Rejected => "rejected",
+            Self::Unverified => "unverified",
+            Self::Record => "record",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|event| event.name() == s)
+    }
+}
+
+/// JSON payload sent to webhooks.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    /// Identifier shared by all attempts to deliver the same payload.
+    pub delivery_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Public view of the solve, after the event.
+    pub solve: ApiSolve,
+    /// Class of event that was verified or in which a record was set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_class: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record: Option<ApiRecord>,
+}
+
+/// Record set by a solve.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ApiRecord {
+    pub event: ApiEvent,
+    /// Previous record, which was defeated or tied.
+    pub previous: Option<ApiSolve>,
+}
+impl ApiRecord {
+    pub fn new(event: &Event, previous: Option<&FullSolve>) -> Self {
+        Self {
+            event: event.into(),
+            previous: previous.map(|solve| ApiSolve::from_full_solve(solve, None)),
+        }
+    }
+}
+
+/// Serialized payload to deliver to webhooks.
+#[derive(Debug, Clone)]
+pub struct WebhookMessage {
+    pub delivery_id: String,
+    pub event: WebhookEvent,
+    pub solve_id: SolveId,
+    pub body: String,
+}
+
+impl AppState {
+    /// Sends an event about a solve to every enabled webhook that subscribes
+    /// to it. Delivery happens in the background.
+    pub async fn send_webhooks(
+        &self,
+        event: WebhookEvent,
+        solve: &FullSolve,
+        event_class: Option<EventClass>,
+        record: Option<ApiRecord>,
+    ) {
+        let webhooks = match self.get_all_webhooks().await {
+            Ok(webhooks) => webhooks
+                .into_iter()
+                .filter(|webhook| webhook.subscribes_to(event))
+                .collect_vec(),
+            Err(err) => {
+                tracing::warn!(%err, "Failed to get webhooks");
+                return;
+            }
+        };
+        if webhooks.is_empty() {
+            return;
+        }
+        if let Err(err) = self.prune_webhook_deliveries().await {
+            tracing::warn!(%err, "Failed to prune webhook deliveries");
+        }
+
+        let delivery_id = crate::util::random_b64_string(DELIVERY_ID_LEN);
+        let payload = WebhookPayload {
+            event,
+            delivery_id: delivery_id.clone(),
+            timestamp: Utc::now(),
+            solve: ApiSolve::from_full_solve(solve, None),
+            event_class: event_class.map(EventClass::long_name),
+            record,
+        };
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::warn!(%err, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+        let message = WebhookMessage {
+            delivery_id,
+            event,
+            solve_id: solve.id,
+            body,
+        };
+
+        for webhook in webhooks {
+            let state = self.clone();
+            let message = message.clone();
+            tokio::spawn(async move { state.deliver_webhook(webhook, message).await });
+        }
+    }
+
+    /// Delivers a message to a webhook, retrying with exponential backoff
+    /// until it succeeds or [`MAX_ATTEMPTS`] is reached.
+    async fn deliver_webhook(&self, webhook: Webhook, message: WebhookMessage) {
+        let signature = format!(
+            "sha256={}",
+            to_hex(&hmac_sha256(
+                webhook.secret.as_bytes(),
+                message.body.as_bytes(),
+            )),
+        );
+        let mut retry_delay = INITIAL_RETRY_DELAY;
+        for attempt in 1..=MAX_ATTEMPTS {
+            let result = self
+                .http_client
+                .post(&webhook.url)
+                .timeout(REQUEST_TIMEOUT)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, message.event.name())
+                .header(DELIVERY_HEADER, &message.delivery_id)
+                .header(SIGNATURE_HEADER, &signature)
+                .body(message.body.clone())
+                .send()
+                .await;
+
+            let (status_code, error) = match result {
+                Ok(response) => {
+                    let status = response.status();
+                    let error = (!status.is_success()).then(|| format!("HTTP status {status}"));
+                    (Some(i32::from(status.as_u16())), error)
+                }
+                Err(err) => (None, Some(err.to_string())),
+            };
+
+            if let Err(err) = self
+                .add_webhook_delivery(webhook.id, &message, attempt, status_code, error.as_deref())
+                .await
+            {
+                tracing::warn!(%err, "Failed to record webhook delivery");
+            }
+
+            let Some(error) = error else {
+                return; // success
+            };
+            tracing::warn!(
+                webhook_id = ?webhook.id,
+                delivery_id = %message.delivery_id,
+                attempt,
+                %error,
+                "Failed to deliver webhook",
+            );
+
+            if attempt < MAX_ATTEMPTS {
+                tokio::time::sleep(retry_delay).await;
+                retry_delay *= 2;
+            }
+        }
+
+        tracing::error!(
+            webhook_id = ?webhook.id,
+            delivery_id = %message.delivery_id,
+            "Gave up delivering webhook",
+        );
+    }
+}
+
+/// Returns the HMAC-SHA256 of `message` using `key`.
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vectors from RFC 4231, section 4.
+    #[test]
+    fn test_hmac_sha256_rfc_4231() {
+        let long_key = [0xaa; 131];
+        let cases: [(&[u8], &[u8], &str); 6] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                &[0xaa; 20],
+                &[0xdd; 50],
+                "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            ),
+            (
+                &[
+                    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+                    0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19,
+                ],
+                &[0xcd; 50],
+                "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+            ),
+            (
+                &long_key,
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+            (
+                &long_key,
+                b"This is a test using a larger than block-size key and a larger than \
+                  block-size data. The key needs to be hashed before being used by the \
+                  HMAC algorithm.",
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+            ),
+        ];
+        for (key, data, expected) in cases {
+            assert_eq!(to_hex(&hmac_sha256(key, data)), expected);
+        }
+
+        // Test case 5 truncates the output to 128 bits.
+        let truncated = to_hex(&hmac_sha256(&[0x0c; 20], b"Test With Truncation"));
+        assert_eq!(&truncated[..32], "a3b6167473100ee06e0c796c2955552b");
+    }
+}
@@ -0,0 +1,40 @@
+#!/bin/sh
+# Stand-in for the Hyperspeedcube CLI, for testing auto-verification without
+# a real `hyperspeedcube` executable. Point `HSC2_PATH` at this script.
+#
+# - `fake-hsc2.sh --version` prints `$FAKE_HSC2_VERSION` (default
+#   `fake-hsc2 0.0.0`).
+# - `fake-hsc2.sh verify FILE` prints the contents of FILE, so a "log file" is
+#   the JSON array of solve verifications that the verifier should report. A
+#   file that does not start with `[` is treated as an invalid log file.
+# - `fake-hsc2.sh puzzle ID` prints `puzzles/ID.json` from
+#   `$FAKE_HSC2_PUZZLES` (default: the `puzzles` directory next to this
+#   script), with any `:` in ID replaced by `_`.
+
+set -eu
+
+case "${1:-}" in
+    --version)
+        echo "${FAKE_HSC2_VERSION:-fake-hsc2 0.0.0}"
+        ;;
+    verify)
+        if [ "$(head -c 1 "$2")" != "[" ]; then
+            echo "error: $2 is not a valid log file" >&2
+            exit 1
+        fi
+        cat "$2"
+        ;;
+    puzzle)
+        puzzles_dir="${FAKE_HSC2_PUZZLES:-$(dirname "$0")/puzzles}"
+        puzzle_file="$puzzles_dir/$(echo "$2" | tr ':' '_').json"
+        if [ ! -f "$puzzle_file" ]; then
+            echo "error: no puzzle with ID $2" >&2
+            exit 1
+        fi
+        cat "$puzzle_file"
+        ;;
+    *)
+        echo "usage: $0 (--version | verify FILE | puzzle ID)" >&2
+        exit 2
+        ;;
+esac